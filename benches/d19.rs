@@ -1,19 +1,26 @@
 use std::time::Duration;
 
-use advent22::days::d19;
+use advent22::days::{
+    d19::{self, Blueprint},
+    CancelToken, NoProgress,
+};
 
 use criterion::{criterion_group, criterion_main, Criterion};
 
-fn small_example() -> Vec<&'static str> {
-    include_str!("../src/days/d19/ex1.txt")
-        .split('\n')
-        .collect()
+const SMALL_EXAMPLE: &str = include_str!("../src/days/d19/ex1.txt");
+const BIG_EXAMPLE: &str = include_str!("../src/days/d19/ex2.txt");
+
+fn small_example() -> Vec<Blueprint> {
+    d19::parse_input(SMALL_EXAMPLE).unwrap()
+}
+
+fn big_example() -> Vec<Blueprint> {
+    d19::parse_input(BIG_EXAMPLE).unwrap()
 }
 
-fn big_example() -> Vec<&'static str> {
-    include_str!("../src/days/d19/ex2.txt")
-        .split('\n')
-        .collect()
+fn bench_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    group.bench_function("big_example", |b| b.iter(|| d19::parse_input(BIG_EXAMPLE)));
 }
 
 fn bench_mini(c: &mut Criterion) {
@@ -21,7 +28,8 @@ fn bench_mini(c: &mut Criterion) {
     group.warm_up_time(Duration::from_millis(1));
     group.sample_size(100);
     group.bench_function("small_example", |b| {
-        b.iter(|| d19::run_program(&small_example(), 4, true))
+        let blueprints = small_example();
+        b.iter(|| d19::run_program(&blueprints, 4, true, &CancelToken::new(), &NoProgress))
     });
 }
 
@@ -30,7 +38,8 @@ fn bench_small_example(c: &mut Criterion) {
     group.warm_up_time(Duration::from_secs(1));
     group.sample_size(10);
     group.bench_function("small_example", |b| {
-        b.iter(|| d19::run_program(&small_example(), 24, true))
+        let blueprints = small_example();
+        b.iter(|| d19::run_program(&blueprints, 24, true, &CancelToken::new(), &NoProgress))
     });
 }
 
@@ -39,9 +48,16 @@ fn bench_big_example_mini(c: &mut Criterion) {
     group.warm_up_time(Duration::from_secs(1));
     group.sample_size(100);
     group.bench_function("big_example", |b| {
-        b.iter(|| d19::run_program(&big_example(), 4, true))
+        let blueprints = big_example();
+        b.iter(|| d19::run_program(&blueprints, 4, true, &CancelToken::new(), &NoProgress))
     });
 }
 
-criterion_group!(benches, bench_mini, bench_small_example, bench_big_example_mini);
+criterion_group!(
+    benches,
+    bench_parse,
+    bench_mini,
+    bench_small_example,
+    bench_big_example_mini
+);
 criterion_main!(benches);
@@ -0,0 +1,126 @@
+//! Benchmarks parsing and both parts of every registered day on both bundled examples.
+//!
+//! Run a single day with `cargo bench --bench days -- d7/`. The parts are timed on an input that
+//! was parsed once, so `d7/parse/small` and `d7/basic/small` together cost what a run of
+//! `d7 small basic` does. The big examples of the slow days take from seconds to a minute per
+//! iteration, so they only get the fewest samples Criterion allows. Even so, the big example of
+//! d19 takes several minutes; filter on `small` (as in `-- small`) for a quick run.
+
+use std::{path::Path, time::Duration};
+
+use advent22::{
+    days::{registry, CancelToken, DayInfo, NoProgress, Part},
+    input::{Example, InputSource, BUILD_INPUTS_DIR},
+};
+
+use criterion::{
+    criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, BenchmarkId, Criterion,
+    SamplingMode,
+};
+
+/// How much time the benchmarks of a day may take on one example.
+struct Budget {
+    sample_size: usize,
+    warm_up_time: Duration,
+    measurement_time: Duration,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Budget {
+            sample_size: 100,
+            warm_up_time: Duration::from_secs(1),
+            measurement_time: Duration::from_secs(3),
+        }
+    }
+}
+
+/// The least Criterion accepts, for the examples that take seconds or more per iteration
+const SLOW: Budget = Budget {
+    sample_size: 10,
+    warm_up_time: Duration::from_millis(1),
+    measurement_time: Duration::from_secs(1),
+};
+
+fn budget(day: u8, example: Example) -> Budget {
+    match (day, example) {
+        (16 | 19, Example::Big) => SLOW,
+        (16, Example::Small) => Budget {
+            sample_size: 10,
+            warm_up_time: Duration::from_millis(100),
+            measurement_time: Duration::from_secs(5),
+        },
+        (19, Example::Small) => Budget {
+            sample_size: 10,
+            warm_up_time: Duration::from_millis(1),
+            measurement_time: Duration::from_secs(20),
+        },
+        _ => Budget::default(),
+    }
+}
+
+fn apply(group: &mut BenchmarkGroup<WallTime>, budget: &Budget) {
+    group.sample_size(budget.sample_size);
+    group.warm_up_time(budget.warm_up_time);
+    group.measurement_time(budget.measurement_time);
+    group.sampling_mode(if budget.sample_size < 100 {
+        SamplingMode::Flat
+    } else {
+        SamplingMode::Auto
+    });
+}
+
+fn bench_day(c: &mut Criterion, day: &DayInfo) {
+    let mut group = c.benchmark_group(format!("d{}", day.number));
+    let cancel = CancelToken::new();
+
+    for example in [Example::Small, Example::Big] {
+        let source = InputSource::Example(example);
+        let read = |part: Part| {
+            source
+                .read(day.number, part == Part::Basic, Path::new(BUILD_INPUTS_DIR))
+                .unwrap_or_else(|e| panic!("day {}: {}", day.number, e))
+        };
+        // some days (like day 9) have a different small example for each part
+        let inputs = [read(Part::Basic), read(Part::Advanced)];
+        let same_input = inputs[0] == inputs[1];
+
+        apply(&mut group, &Budget::default());
+        for (part, input) in [Part::Basic, Part::Advanced].into_iter().zip(&inputs) {
+            let id = if same_input {
+                BenchmarkId::new("parse", &source)
+            } else {
+                BenchmarkId::new("parse", format!("{} {}", source, part))
+            };
+            group.bench_with_input(id, input, |b, input| {
+                b.iter(|| (day.parse)(input).expect("the example should be parsed"))
+            });
+            if same_input {
+                break;
+            }
+        }
+
+        apply(&mut group, &budget(day.number, example));
+        for (part, input) in [Part::Basic, Part::Advanced].into_iter().zip(&inputs) {
+            let parsed = (day.parse)(input).expect("the example should be parsed");
+            group.bench_function(BenchmarkId::new(part.to_string(), &source), |b| {
+                b.iter(|| {
+                    parsed
+                        .solve(part, &cancel, &NoProgress)
+                        .expect("the example should be solved")
+                })
+            });
+        }
+    }
+
+    group.finish();
+}
+
+fn bench_days(c: &mut Criterion) {
+    for day in registry() {
+        bench_day(c, day);
+    }
+}
+
+criterion_group!(benches, bench_days);
+criterion_main!(benches);
@@ -0,0 +1,232 @@
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+
+use crate::{
+    days::Part,
+    input::{InputError, InputSource},
+};
+
+/// The name of the manifest file inside an inputs directory.
+pub const MANIFEST_FILE_NAME: &str = "answers.txt";
+
+/// One expected answer from the manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer {
+    pub day: u8,
+    pub part: Part,
+    pub input: InputSource,
+    pub expected: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ManifestError {
+    /// 1-based line number in the manifest
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for ManifestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Read(InputError),
+    Parse(PathBuf, ManifestError),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Read(e) => write!(f, "{}", e),
+            LoadError::Parse(path, e) => write!(f, "{}: {}", path.display(), e),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// Parses a manifest where every line is `<day> <part> <input> <expected answer>`.
+///
+/// The input is `small`, `big` or a path (relative paths are resolved against `base_dir`).
+/// Newlines in multi-line answers are written as `\n` and backslashes as `\\`.
+/// Empty lines and lines starting with `#` are ignored.
+pub fn parse_manifest(manifest: &str, base_dir: &Path) -> Result<Vec<Answer>, ManifestError> {
+    let mut answers = Vec::new();
+
+    for (idx, line) in manifest.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error = |message: String| ManifestError {
+            line: idx + 1,
+            message,
+        };
+
+        let mut fields = line.splitn(4, char::is_whitespace);
+        let (Some(day), Some(part), Some(input), Some(expected)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            return Err(error(
+                "expected '<day> <part> <input> <answer>'".to_string(),
+            ));
+        };
+
+        let day = day
+            .parse::<u8>()
+            .map_err(|_| error(format!("invalid day: {}", day)))?;
+        let part =
+            Part::from_str(part, true).map_err(|_| error(format!("invalid part: {}", part)))?;
+        let input = match input.parse::<InputSource>() {
+            Ok(InputSource::File(path)) => InputSource::File(base_dir.join(path)),
+            Ok(source) => source,
+            Err(never) => match never {},
+        };
+        let expected = unescape(expected.trim()).map_err(error)?;
+
+        answers.push(Answer {
+            day,
+            part,
+            input,
+            expected,
+        });
+    }
+
+    Ok(answers)
+}
+
+pub fn load_manifest(path: &Path) -> Result<Vec<Answer>, LoadError> {
+    let manifest = fs::read_to_string(path).map_err(|source| {
+        LoadError::Read(InputError {
+            path: Some(path.to_path_buf()),
+            source,
+        })
+    })?;
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    parse_manifest(&manifest, base_dir).map_err(|e| LoadError::Parse(path.to_path_buf(), e))
+}
+
+fn unescape(s: &str) -> Result<String, String> {
+    let mut res = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => res.push('\n'),
+                Some('\\') => res.push('\\'),
+                Some(c) => return Err(format!("invalid escape sequence: \\{}", c)),
+                None => return Err("unfinished escape sequence".to_string()),
+            }
+        } else {
+            res.push(c);
+        }
+    }
+    Ok(res)
+}
+
+/// The opposite of the unescaping done by `parse_manifest`.
+pub fn escape(answer: &str) -> String {
+    answer.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+/// Compares an answer with the expected one, ignoring trailing whitespace (like the final newline of a CRT image).
+pub fn matches(expected: &str, actual: &str) -> bool {
+    expected.trim_end() == actual.trim_end()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::{Example, BUILD_INPUTS_DIR};
+
+    use super::*;
+
+    #[test]
+    fn parse_manifest_reads_all_fields() {
+        let manifest =
+            "# comment\n\n1 basic small 24000\n25 1 big 2=-1=0\n4 advanced inputs/d4.txt 4\n";
+        assert_eq!(
+            parse_manifest(manifest, Path::new("base")),
+            Ok(vec![
+                Answer {
+                    day: 1,
+                    part: Part::Basic,
+                    input: InputSource::Example(Example::Small),
+                    expected: "24000".to_string(),
+                },
+                Answer {
+                    day: 25,
+                    part: Part::Basic,
+                    input: InputSource::Example(Example::Big),
+                    expected: "2=-1=0".to_string(),
+                },
+                Answer {
+                    day: 4,
+                    part: Part::Advanced,
+                    input: InputSource::File(PathBuf::from("base/inputs/d4.txt")),
+                    expected: "4".to_string(),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_manifest_unescapes_multi_line_answers() {
+        let answers = parse_manifest("10 advanced small #.\\n.#\\\\", Path::new(".")).unwrap();
+        assert_eq!(answers[0].expected, "#.\n.#\\");
+        assert_eq!(escape(&answers[0].expected), "#.\\n.#\\\\");
+    }
+
+    #[test]
+    fn parse_manifest_reports_the_line_of_errors() {
+        assert_eq!(
+            parse_manifest("1 basic small 1\n1 basic small", Path::new(".")),
+            Err(ManifestError {
+                line: 2,
+                message: "expected '<day> <part> <input> <answer>'".to_string()
+            })
+        );
+        assert_eq!(
+            parse_manifest("x basic small 1", Path::new("."))
+                .unwrap_err()
+                .message,
+            "invalid day: x"
+        );
+        assert_eq!(
+            parse_manifest("1 third small 1", Path::new("."))
+                .unwrap_err()
+                .message,
+            "invalid part: third"
+        );
+    }
+
+    #[test]
+    fn bundled_manifest_covers_every_day_and_part() {
+        let path = Path::new(BUILD_INPUTS_DIR).join(MANIFEST_FILE_NAME);
+        let answers = load_manifest(&path).unwrap();
+        for day in 1..=25 {
+            for part in [Part::Basic, Part::Advanced] {
+                for example in [Example::Small, Example::Big] {
+                    assert!(
+                        answers.iter().any(|a| a.day == day
+                            && a.part == part
+                            && a.input == InputSource::Example(example)),
+                        "missing answer for day {} {} {:?}",
+                        day,
+                        part,
+                        example
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+/// Tells a long-running search to stop early.
+///
+/// The searches check the token often, so checking it is only an atomic load. A deadline is
+/// implemented by a watchdog thread that cancels the token when it expires, or stops as soon as
+/// the token and all its clones are dropped.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+    /// Dropping the last clone of it disconnects the watchdog
+    _watchdog: Option<Arc<mpsc::Sender<()>>>,
+}
+
+impl CancelToken {
+    /// A token that is only cancelled by calling [`CancelToken::cancel`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A token that is cancelled once `timeout` has passed.
+    pub fn with_timeout(timeout: Duration) -> Self {
+        let (sender, receiver) = mpsc::channel::<()>();
+        let token = CancelToken {
+            cancelled: Arc::default(),
+            _watchdog: Some(Arc::new(sender)),
+        };
+        let cancelled = Arc::downgrade(&token.cancelled);
+        thread::spawn(move || {
+            if receiver.recv_timeout(timeout) == Err(RecvTimeoutError::Timeout) {
+                if let Some(cancelled) = cancelled.upgrade() {
+                    cancelled.store(true, Ordering::Relaxed);
+                }
+            }
+        });
+        token
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clones_share_the_cancellation() {
+        let token = CancelToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+        token.cancel();
+        assert!(clone.is_cancelled());
+    }
+
+    #[test]
+    fn the_token_is_cancelled_after_the_timeout() {
+        let token = CancelToken::with_timeout(Duration::from_millis(10));
+        assert!(!token.is_cancelled());
+        thread::sleep(Duration::from_millis(200));
+        assert!(token.is_cancelled());
+    }
+
+    #[test]
+    fn the_watchdog_stops_when_the_token_is_dropped() {
+        let token = CancelToken::with_timeout(Duration::from_secs(60));
+        let sender = Arc::downgrade(token._watchdog.as_ref().unwrap());
+        let clone = token.clone();
+        drop(token);
+        assert!(sender.upgrade().is_some());
+        drop(clone);
+        assert!(sender.upgrade().is_none());
+    }
+}
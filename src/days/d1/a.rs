@@ -1,23 +1,9 @@
-pub fn main(input: &str) -> i32 {
-    println!("Hello, world!");
+use crate::days::{Part, SolveError};
 
-    let mut latest: i32 = 0;
-    let mut max: i32 = 0;
+use super::{podium_size, sum_top, CalorieLedger};
 
-    for l in input.lines() {
-        if !l.is_empty() {
-            let c = l.parse::<i32>().unwrap();
-            latest += c;
-        } else {
-            if latest > max {
-                max = latest;
-            }
-            latest = 0;
-        }
-    }
-    if latest > max {
-        max = latest;
-    }
-
-    max
+pub fn main(ledger: &CalorieLedger) -> Result<i32, SolveError> {
+    let count = podium_size(Part::Basic);
+    let top: Vec<_> = ledger.top(count).into_iter().map(|r| r.elf).collect();
+    sum_top(&top, count)
 }
@@ -1,21 +1,9 @@
-pub fn main(input: &str) -> i32 {
-    println!("Hello, world!");
+use crate::days::{Part, SolveError};
 
-    let mut sums: Vec<i32> = Vec::new();
-    let mut latest: i32 = 0;
+use super::{podium_size, sum_top, CalorieLedger};
 
-    for l in input.lines() {
-        if !l.is_empty() {
-            let c = l.parse::<i32>().unwrap();
-            latest += c;
-        } else {
-            sums.push(latest);
-            latest = 0;
-        }
-    }
-    sums.push(latest);
-
-    sums.sort();
-
-    sums[sums.len() - 1] + sums[sums.len() - 2] + sums[sums.len() - 3]
+pub fn main(ledger: &CalorieLedger) -> Result<i32, SolveError> {
+    let count = podium_size(Part::Advanced);
+    let top: Vec<_> = ledger.top(count).into_iter().map(|r| r.elf).collect();
+    sum_top(&top, count)
 }
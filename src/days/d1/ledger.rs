@@ -0,0 +1,229 @@
+//! The calories carried by every elf, ranked from the elf carrying the most.
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+/// An elf with the number of items it carries and their total calories. Elves are numbered from
+/// 1 in the order of the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elf {
+    pub number: usize,
+    pub calories: i32,
+    pub items: usize,
+}
+
+/// An elf and its place in the ranking, which it shares with the elves carrying as many calories
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ranked {
+    pub rank: usize,
+    pub elf: Elf,
+}
+
+/// The `n` elves carrying the most calories among those pushed so far, keeping no others.
+///
+/// Among elves carrying as many calories, the first ones of the input are ahead.
+pub struct Podium {
+    n: usize,
+    /// The elves kept, with the one that is behind the others on top
+    heap: BinaryHeap<(Reverse<i32>, usize, usize)>,
+}
+
+impl Podium {
+    pub fn new(n: usize) -> Self {
+        Podium {
+            n,
+            heap: BinaryHeap::with_capacity(n + 1),
+        }
+    }
+
+    pub fn push(&mut self, elf: Elf) {
+        self.heap
+            .push((Reverse(elf.calories), elf.number, elf.items));
+        if self.heap.len() > self.n {
+            self.heap.pop();
+        }
+    }
+
+    /// The elves kept, from the one carrying the most
+    pub fn into_elves(self) -> Vec<Elf> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|(Reverse(calories), number, items)| Elf {
+                number,
+                calories,
+                items,
+            })
+            .collect()
+    }
+}
+
+/// What the elves carry altogether
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub elves: usize,
+    pub items: usize,
+    pub calories: i64,
+    pub mean: f64,
+    /// The calories of the middle elf, or the mean of the two middle ones
+    pub median: f64,
+    pub most: i32,
+    /// How many elves carry the most calories
+    pub tied_for_most: usize,
+}
+
+/// Every elf of the input, in its order. There is always at least one elf, since even an empty
+/// input starts with one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalorieLedger {
+    elves: Vec<Elf>,
+}
+
+impl Default for CalorieLedger {
+    fn default() -> Self {
+        CalorieLedger::new()
+    }
+}
+
+impl CalorieLedger {
+    pub fn new() -> Self {
+        CalorieLedger {
+            elves: vec![Elf {
+                number: 1,
+                calories: 0,
+                items: 0,
+            }],
+        }
+    }
+
+    /// Gives an item to the last elf
+    pub fn add_item(&mut self, calories: i32) {
+        if let Some(elf) = self.elves.last_mut() {
+            elf.calories += calories;
+            elf.items += 1;
+        }
+    }
+
+    /// Starts the items of the next elf
+    pub fn next_elf(&mut self) {
+        self.elves.push(Elf {
+            number: self.elves.len() + 1,
+            calories: 0,
+            items: 0,
+        });
+    }
+
+    pub fn elves(&self) -> &[Elf] {
+        &self.elves
+    }
+
+    /// The `n` elves carrying the most calories, from the one carrying the most, found in one
+    /// pass that keeps no more than `n` of them
+    pub fn top(&self, n: usize) -> Vec<Ranked> {
+        let mut podium = Podium::new(n);
+        for &elf in &self.elves {
+            podium.push(elf);
+        }
+        rank(podium.into_elves())
+    }
+
+    pub fn ranking(&self) -> Vec<Ranked> {
+        self.top(self.elves.len())
+    }
+
+    pub fn stats(&self) -> Stats {
+        let mut calories: Vec<i32> = self.elves.iter().map(|elf| elf.calories).collect();
+        let (middle, even) = (calories.len() / 2, calories.len().is_multiple_of(2));
+        let (lower, &mut upper, _) = calories.select_nth_unstable(middle);
+        let median = match lower.iter().max() {
+            Some(&below) if even => (below as f64 + upper as f64) / 2.0,
+            _ => upper as f64,
+        };
+
+        let total: i64 = calories.iter().map(|&c| c as i64).sum();
+        let most = calories.iter().copied().max().unwrap_or(0);
+        Stats {
+            elves: self.elves.len(),
+            items: self.elves.iter().map(|elf| elf.items).sum(),
+            calories: total,
+            mean: total as f64 / self.elves.len() as f64,
+            median,
+            most,
+            tied_for_most: calories.iter().filter(|&&c| c == most).count(),
+        }
+    }
+}
+
+/// Ranks elves sorted from the one carrying the most, giving the same rank to the elves
+/// carrying as many calories, like 1, 2, 2, 4
+fn rank(elves: Vec<Elf>) -> Vec<Ranked> {
+    let mut ranked: Vec<Ranked> = Vec::with_capacity(elves.len());
+    for (i, elf) in elves.into_iter().enumerate() {
+        let rank = match ranked.last() {
+            Some(last) if last.elf.calories == elf.calories => last.rank,
+            _ => i + 1,
+        };
+        ranked.push(Ranked { rank, elf });
+    }
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ledger(elves: &[&[i32]]) -> CalorieLedger {
+        let mut ledger = CalorieLedger::new();
+        for (i, items) in elves.iter().enumerate() {
+            if i > 0 {
+                ledger.next_elf();
+            }
+            for &calories in items.iter() {
+                ledger.add_item(calories);
+            }
+        }
+        ledger
+    }
+
+    fn numbers(ranked: &[Ranked]) -> Vec<(usize, usize)> {
+        ranked.iter().map(|r| (r.rank, r.elf.number)).collect()
+    }
+
+    #[test]
+    fn elves_are_ranked_with_ties_sharing_a_rank() {
+        let ledger = ledger(&[&[5, 5], &[20], &[3, 7], &[1], &[20]]);
+        assert_eq!(
+            numbers(&ledger.ranking()),
+            [(1, 2), (1, 5), (3, 1), (3, 3), (5, 4)]
+        );
+        assert_eq!(numbers(&ledger.top(3)), [(1, 2), (1, 5), (3, 1)]);
+        assert_eq!(ledger.top(0), []);
+        assert_eq!(ledger.top(9).len(), 5);
+        assert_eq!(
+            ledger.top(1)[0].elf,
+            Elf {
+                number: 2,
+                calories: 20,
+                items: 1
+            }
+        );
+    }
+
+    #[test]
+    fn stats_cover_every_elf() {
+        let stats = ledger(&[&[5, 5], &[20], &[3, 7], &[1], &[20]]).stats();
+        assert_eq!(
+            stats,
+            Stats {
+                elves: 5,
+                items: 7,
+                calories: 61,
+                mean: 12.2,
+                median: 10.0,
+                most: 20,
+                tied_for_most: 2,
+            }
+        );
+        assert_eq!(ledger(&[&[1], &[4], &[2], &[8]]).stats().median, 3.0);
+        assert_eq!(CalorieLedger::new().stats().median, 0.0);
+    }
+}
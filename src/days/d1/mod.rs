@@ -1,16 +1,129 @@
+use std::io::BufRead;
+
+use super::{
+    stream::{for_each_line, StreamError},
+    Part, SolveError,
+};
+
 mod a;
 mod b;
+mod ledger;
+
+pub use ledger::{CalorieLedger, Elf, Podium, Ranked, Stats};
 
 pub struct Day;
 
+/// The calories of the item on a line, or `None` for the blank lines between the elves
+fn parse_line(idx: usize, line: &str) -> Result<Option<i32>, SolveError> {
+    if line.is_empty() {
+        return Ok(None);
+    }
+    line.parse::<i32>()
+        .map(Some)
+        .map_err(|_| SolveError::parse(idx + 1, 1, format!("invalid calories: {}", line)))
+}
+
+/// The items carried by every elf, in the order of the input
+fn parse_input(input: &str) -> Result<CalorieLedger, SolveError> {
+    let mut ledger = CalorieLedger::new();
+
+    for (idx, l) in input.lines().enumerate() {
+        match parse_line(idx, l)? {
+            Some(c) => ledger.add_item(c),
+            None => ledger.next_elf(),
+        }
+    }
+
+    Ok(ledger)
+}
+
+/// How many elves on top a part adds up
+fn podium_size(part: Part) -> usize {
+    match part {
+        Part::Basic => 1,
+        Part::Advanced => 3,
+    }
+}
+
+/// Adds up the calories of the elves on top, of which there must be as many as asked
+fn sum_top(top: &[Elf], count: usize) -> Result<i32, SolveError> {
+    if top.len() < count {
+        return Err(SolveError::unsupported(match count {
+            1 => "there must be at least one elf".to_string(),
+            _ => format!("there must be at least {} elves", count),
+        }));
+    }
+    Ok(top.iter().map(|elf| elf.calories).sum())
+}
+
+/// Solves a part one line at a time, keeping only the elves on top so far
+pub fn run_reader<R: BufRead>(reader: R, part: Part) -> Result<i32, StreamError> {
+    let count = podium_size(part);
+    let mut podium = Podium::new(count);
+    let mut elf = Elf {
+        number: 1,
+        calories: 0,
+        items: 0,
+    };
+
+    for_each_line(reader, |idx, line| {
+        match parse_line(idx, line)? {
+            Some(c) => {
+                elf.calories += c;
+                elf.items += 1;
+            }
+            None => {
+                podium.push(elf);
+                elf = Elf {
+                    number: elf.number + 1,
+                    calories: 0,
+                    items: 0,
+                };
+            }
+        }
+        Ok(())
+    })?;
+    podium.push(elf);
+
+    Ok(sum_top(&podium.into_elves(), count)?)
+}
+
 impl super::Runner for Day {
+    type Parsed<'a> = CalorieLedger;
     type T = i32;
 
-    fn run(input: &str, basic: bool) -> Self::T {
-        if basic {
-            a::main(input)
-        } else {
-            b::main(input)
-        }
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(ledger: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        a::main(ledger)
+    }
+
+    fn part2(ledger: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        b::main(ledger)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn too_few_elves_are_reported_with_how_many_are_needed() {
+        let elf = Elf {
+            number: 1,
+            calories: 5,
+            items: 1,
+        };
+        assert_eq!(sum_top(&[elf], 1), Ok(5));
+        assert_eq!(
+            sum_top(&[], 1),
+            Err(SolveError::unsupported("there must be at least one elf"))
+        );
+        assert_eq!(
+            sum_top(&[elf, elf], 3),
+            Err(SolveError::unsupported("there must be at least 3 elves"))
+        );
     }
 }
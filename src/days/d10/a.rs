@@ -1,9 +1,8 @@
-enum Instr {
-    Noop,
-    Addx(i32),
-}
+use crate::days::SolveError;
+
+use super::Instr;
 
-fn run_program(program: &Vec<Instr>) -> i32 {
+fn run_program(program: &[Instr]) -> i32 {
     let mut x = 1;
 
     let mut x_history: Vec<i32> = Vec::new();
@@ -30,27 +29,6 @@ fn run_program(program: &Vec<Instr>) -> i32 {
     result
 }
 
-pub fn main(input: &str) -> i32 {
-    println!("Hello, world!");
-
-    let mut program: Vec<Instr> = Vec::new();
-
-    for line in input.lines() {
-        if line.is_empty() {
-            break;
-        }
-
-        let parts: Vec<&str> = line.split(' ').collect();
-
-        if parts[0] == "noop" {
-            program.push(Instr::Noop);
-        } else if parts[0] == "addx" {
-            let v = parts[1].parse::<i32>().unwrap();
-            program.push(Instr::Addx(v));
-        } else {
-            panic!("wat?")
-        }
-    }
-
-    run_program(&program)
+pub fn main(program: &[Instr]) -> Result<i32, SolveError> {
+    Ok(run_program(program))
 }
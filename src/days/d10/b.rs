@@ -1,9 +1,8 @@
 use std::fmt::Display;
 
-enum Instr {
-    Noop,
-    Addx(i32),
-}
+use crate::days::SolveError;
+
+use super::Instr;
 
 pub struct Crt {
     pixels: [[bool; 40]; 6],
@@ -23,20 +22,26 @@ impl Display for Crt {
     }
 }
 
-fn make_crt(xs: Vec<i32>) -> Crt {
+fn make_crt(xs: Vec<i32>) -> Result<Crt, SolveError> {
     let mut crt: Crt = Crt {
         pixels: [[false; 40]; 6],
     };
+    if xs.len() > 40 * 6 {
+        return Err(SolveError::unsupported(format!(
+            "the program runs for {} cycles but the CRT only has 240 pixels",
+            xs.len()
+        )));
+    }
 
     for (i, x) in xs.iter().enumerate() {
         let on = x.abs_diff(i as i32 % 40) <= 1;
         crt.pixels[i / 40][i % 40] = on;
     }
 
-    crt
+    Ok(crt)
 }
 
-fn run_program(program: &Vec<Instr>) -> Crt {
+fn run_program(program: &[Instr]) -> Result<Crt, SolveError> {
     let mut x = 1;
 
     let mut x_history: Vec<i32> = Vec::new();
@@ -57,27 +62,6 @@ fn run_program(program: &Vec<Instr>) -> Crt {
     make_crt(x_history)
 }
 
-pub fn main(input: &str) -> Crt {
-    println!("Hello, world!");
-
-    let mut program: Vec<Instr> = Vec::new();
-
-    for line in input.lines() {
-        if line.is_empty() {
-            break;
-        }
-
-        let parts: Vec<&str> = line.split(' ').collect();
-
-        if parts[0] == "noop" {
-            program.push(Instr::Noop);
-        } else if parts[0] == "addx" {
-            let v = parts[1].parse::<i32>().unwrap();
-            program.push(Instr::Addx(v));
-        } else {
-            panic!("wat?")
-        }
-    }
-
-    run_program(&program)
+pub fn main(program: &[Instr]) -> Result<Crt, SolveError> {
+    run_program(program)
 }
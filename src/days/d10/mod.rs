@@ -1,16 +1,58 @@
+use super::SolveError;
+
 mod a;
 mod b;
 
 pub struct Day;
 
-impl super::Runner for Day {
-    type T = String;
+pub enum Instr {
+    Noop,
+    Addx(i32),
+}
+
+fn parse_input(input: &str) -> Result<Vec<Instr>, SolveError> {
+    let mut program: Vec<Instr> = Vec::new();
 
-    fn run(input: &str, basic: bool) -> Self::T {
-        if basic {
-            a::main(input).to_string()
+    for (idx, line) in input.lines().enumerate() {
+        if line.is_empty() {
+            break;
+        }
+
+        let parts: Vec<&str> = line.split(' ').collect();
+
+        if parts[0] == "noop" {
+            program.push(Instr::Noop);
+        } else if parts[0] == "addx" {
+            let v = parts
+                .get(1)
+                .and_then(|v| v.parse::<i32>().ok())
+                .ok_or_else(|| SolveError::parse(idx + 1, 6, "expected an integer"))?;
+            program.push(Instr::Addx(v));
         } else {
-            b::main(input).to_string()
+            return Err(SolveError::parse(
+                idx + 1,
+                1,
+                format!("unknown instruction: {}", parts[0]),
+            ));
         }
     }
+
+    Ok(program)
+}
+
+impl super::Runner for Day {
+    type Parsed<'a> = Vec<Instr>;
+    type T = String;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(program: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        a::main(program).map(|n| n.to_string())
+    }
+
+    fn part2(program: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        b::main(program).map(|crt| crt.to_string())
+    }
 }
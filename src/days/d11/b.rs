@@ -1,66 +1,8 @@
 use num::integer::lcm;
 
-#[derive(Clone, Copy)]
-enum RightOperand {
-    Num(i32),
-    Old,
-}
-
-#[derive(Clone, Copy)]
-enum Operation {
-    Add(RightOperand),
-    Mul(RightOperand),
-}
-
-struct Monkey {
-    items: Vec<i32>,
-    operation: Operation,
-    divisibility: i32,
-    yes_dest: usize,
-    no_dest: usize,
-    inspections: u32,
-}
-
-fn parse_monkey(lines: &[String]) -> Monkey {
-    let items: Vec<i32> = lines[1][(lines[1].find(':').unwrap() + 2)..]
-        .split(", ")
-        .map(|a| a.parse::<i32>().unwrap())
-        .collect();
+use crate::days::SolveError;
 
-    let operation_str_parts: Vec<&str> = lines[2][(lines[2].find(':').unwrap() + 2)..]
-        .split(' ')
-        .collect();
-    let right_operand = match operation_str_parts[4] {
-        "old" => RightOperand::Old,
-        s => RightOperand::Num(s.parse::<i32>().unwrap()),
-    };
-    let operation: Operation = match operation_str_parts[3] {
-        "+" => Operation::Add(right_operand),
-        "*" => Operation::Mul(right_operand),
-        _ => panic!("unsupported operation"),
-    };
-
-    let divisibility = lines[3][(lines[3].find("by").unwrap() + 3)..]
-        .parse::<i32>()
-        .unwrap();
-
-    let yes_dest = lines[4][(lines[4].find("monkey").unwrap() + 7)..]
-        .parse::<usize>()
-        .unwrap();
-
-    let no_dest = lines[5][(lines[5].find("monkey").unwrap() + 7)..]
-        .parse::<usize>()
-        .unwrap();
-
-    Monkey {
-        items,
-        operation,
-        divisibility,
-        yes_dest,
-        no_dest,
-        inspections: 0,
-    }
-}
+use super::parser::{Monkey, Operation, RightOperand};
 
 fn evaluate_operation(old: i32, operation: Operation) -> i64 {
     match operation {
@@ -75,7 +17,7 @@ fn evaluate_operation(old: i32, operation: Operation) -> i64 {
     }
 }
 
-fn simulate_monkey_round(monkeys: &mut Vec<Monkey>, total_mod: i64) {
+fn simulate_monkey_round(monkeys: &mut [Monkey], total_mod: i64) {
     for i in 0..monkeys.len() {
         let q = monkeys[i].items.clone();
         monkeys[i].items.clear();
@@ -95,45 +37,22 @@ fn simulate_monkey_round(monkeys: &mut Vec<Monkey>, total_mod: i64) {
     }
 }
 
-pub fn main(input: &str) -> u64 {
-    println!("Hello, world!");
-
-    let mut last_monkey_lines: Vec<String> = Vec::new();
-
-    let mut monkeys: Vec<Monkey> = Vec::new();
-
-    for line in input.lines() {
-        if line.is_empty() {
-            if last_monkey_lines.is_empty() {
-                break;
-            } else {
-                monkeys.push(parse_monkey(&last_monkey_lines));
-                last_monkey_lines.clear();
-            }
-        } else {
-            last_monkey_lines.push(line.to_string());
-        }
-    }
-
-    if !last_monkey_lines.is_empty() {
-        monkeys.push(parse_monkey(&last_monkey_lines));
-    }
+pub fn main(monkeys: &[Monkey]) -> Result<u64, SolveError> {
+    let mut monkeys = monkeys.to_vec();
 
     let mut total_mod: i32 = 1;
     for m in monkeys.iter() {
         total_mod = lcm(total_mod, m.divisibility);
     }
 
-    println!("mod: {}", total_mod);
-
     for _ in 0..10000 {
         simulate_monkey_round(&mut monkeys, total_mod as i64);
     }
 
-    monkeys.sort_by(|l, r| l.inspections.cmp(&r.inspections));
+    monkeys.sort_by_key(|m| m.inspections);
 
     let next_busiest = monkeys[monkeys.len() - 2].inspections;
     let busiest = monkeys[monkeys.len() - 1].inspections;
 
-    next_busiest as u64 * busiest as u64
+    Ok(next_busiest as u64 * busiest as u64)
 }
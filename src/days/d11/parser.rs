@@ -0,0 +1,188 @@
+use crate::{days::SolveError, parse::Parser};
+
+#[derive(Clone, Copy)]
+pub enum RightOperand {
+    Num(i32),
+    Old,
+}
+
+#[derive(Clone, Copy)]
+pub enum Operation {
+    Add(RightOperand),
+    Mul(RightOperand),
+}
+
+#[derive(Clone)]
+pub struct Monkey {
+    pub items: Vec<i32>,
+    pub operation: Operation,
+    pub divisibility: i32,
+    pub yes_dest: usize,
+    pub no_dest: usize,
+    pub inspections: u32,
+}
+
+/// `first_line` is the index of the line with the monkey's name in the input
+fn parse_monkey(lines: &[String], first_line: usize) -> Result<Monkey, SolveError> {
+    // the lines of a monkey are indented, and a missing one reads as empty
+    let line = |i: usize| {
+        let mut p = Parser::new(lines.get(i).map_or("", String::as_str), first_line + i);
+        p.spaces();
+        p
+    };
+
+    let mut p = line(0);
+    p.literal("Monkey ")?;
+    p.integer::<usize>()?;
+    p.literal(":")?;
+    p.end()?;
+
+    let mut p = line(1);
+    p.literal("Starting items: ")?;
+    let items = p.separated(", ", Parser::integer)?;
+    p.end()?;
+
+    let mut p = line(2);
+    p.literal("Operation: new = old ")?;
+    let operator = p.one_of(&["+", "*"])?;
+    p.literal(" ")?;
+    let right_operand = match p.optional("old") {
+        true => RightOperand::Old,
+        false => RightOperand::Num(p.integer()?),
+    };
+    p.end()?;
+    let operation = match operator {
+        "+" => Operation::Add(right_operand),
+        _ => Operation::Mul(right_operand),
+    };
+
+    let mut p = line(3);
+    p.literal("Test: divisible by ")?;
+    let span = p.span();
+    let divisibility = p.integer()?;
+    if divisibility <= 0 {
+        return Err(span.error("the divisor must be positive"));
+    }
+    p.end()?;
+
+    let destination = |i: usize, condition: &str| -> Result<usize, SolveError> {
+        let mut p = line(i);
+        p.literal(&format!("If {}: throw to monkey ", condition))?;
+        let monkey = p.integer()?;
+        p.end()?;
+        Ok(monkey)
+    };
+    let yes_dest = destination(4, "true")?;
+    let no_dest = destination(5, "false")?;
+
+    Ok(Monkey {
+        items,
+        operation,
+        divisibility,
+        yes_dest,
+        no_dest,
+        inspections: 0,
+    })
+}
+
+/// The monkeys of the input, checked to throw only to each other
+pub fn parse_input(input: &str) -> Result<Vec<Monkey>, SolveError> {
+    let mut last_monkey_lines: Vec<String> = Vec::new();
+
+    let mut last_monkey_start = 0;
+
+    let mut monkeys: Vec<Monkey> = Vec::new();
+
+    for (idx, line) in input.lines().enumerate() {
+        if line.is_empty() {
+            if last_monkey_lines.is_empty() {
+                break;
+            } else {
+                monkeys.push(parse_monkey(&last_monkey_lines, last_monkey_start)?);
+                last_monkey_lines.clear();
+            }
+        } else {
+            if last_monkey_lines.is_empty() {
+                last_monkey_start = idx;
+            }
+            last_monkey_lines.push(line.to_string());
+        }
+    }
+
+    if !last_monkey_lines.is_empty() {
+        monkeys.push(parse_monkey(&last_monkey_lines, last_monkey_start)?);
+    }
+
+    if monkeys.len() < 2 {
+        return Err(SolveError::unsupported("there must be at least two monkeys"));
+    }
+    if let Some(m) = monkeys
+        .iter()
+        .find(|m| m.yes_dest >= monkeys.len() || m.no_dest >= monkeys.len())
+    {
+        return Err(SolveError::unsupported(format!(
+            "a monkey throws to monkey {} but there are only {}",
+            m.yes_dest.max(m.no_dest),
+            monkeys.len()
+        )));
+    }
+
+    Ok(monkeys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONKEY: &str = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 1
+    If false: throw to monkey 0
+
+Monkey 1:
+  Starting items: 54
+  Operation: new = old + old
+  Test: divisible by 19
+    If true: throw to monkey 0
+    If false: throw to monkey 1";
+
+    fn error(from: &str, to: &str) -> Option<String> {
+        parse_input(&MONKEY.replacen(from, to, 1))
+            .err()
+            .map(|e| e.to_string())
+    }
+
+    #[test]
+    fn monkeys_are_parsed() {
+        let monkeys = parse_input(MONKEY).unwrap();
+        assert_eq!(monkeys[0].items, [79, 98]);
+        assert_eq!(monkeys[1].divisibility, 19);
+        assert_eq!((monkeys[1].yes_dest, monkeys[1].no_dest), (0, 1));
+    }
+
+    #[test]
+    fn malformed_monkeys_are_reported_where_they_go_wrong() {
+        assert_eq!(
+            error("79, 98", "79, x"),
+            Some("line 2, column 23: expected an integer".to_string())
+        );
+        assert_eq!(
+            error("old * 19", "old - 19"),
+            Some("line 3, column 24: expected '+' or '*'".to_string())
+        );
+        assert_eq!(
+            error("by 23", "by 0"),
+            Some("line 4, column 22: the divisor must be positive".to_string())
+        );
+        assert_eq!(
+            error("monkey 1\n", "monkey one\n"),
+            Some("line 5, column 30: expected an integer".to_string())
+        );
+        assert_eq!(
+            error("    If false: throw to monkey 0\n", ""),
+            Some("line 6, column 1: expected 'If false: throw to monkey '".to_string())
+        );
+    }
+}
@@ -1,16 +1,89 @@
+use super::SolveError;
+use crate::grid::{Grid, Pos};
+
 mod a;
 mod b;
 
 pub struct Day;
 
+/// The elevations (0 for a to 25 for z) with the marked positions
+pub struct Heightmap {
+    grid: Grid<u8>,
+    start: Option<Pos>,
+    end: Pos,
+}
+
+impl Heightmap {
+    /// The squares next to `pos` that are at most one higher
+    fn steps_from(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        let here = self.grid[pos];
+        self.grid
+            .neighbours4(pos)
+            .filter(move |&next| self.grid[next] <= here + 1)
+    }
+}
+
+fn parse_input(input: &str) -> Result<Heightmap, SolveError> {
+    let marks = Grid::parse(input, |c| match c {
+        'S' | 'E' | 'a'..='z' => Ok(c),
+        _ => Err(format!("invalid elevation: {}", c)),
+    })?;
+    let find = |mark| marks.iter().find(|&(_, &c)| c == mark).map(|(pos, _)| pos);
+
+    let start = find('S');
+    let end =
+        find('E').ok_or_else(|| SolveError::unsupported("the map has no end position 'E'"))?;
+    let grid = marks.map(|&c| match c {
+        'S' => 0,
+        'E' => b'z' - b'a',
+        _ => c as u8 - b'a',
+    });
+
+    Ok(Heightmap { grid, start, end })
+}
+
 impl super::Runner for Day {
+    type Parsed<'a> = Heightmap;
     type T = u32;
 
-    fn run(input: &str, basic: bool) -> Self::T {
-        if basic {
-            a::main(input)
-        } else {
-            b::main(input)
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(map: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        a::main(map)
+    }
+
+    fn part2(map: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        b::main(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::Found;
+
+    fn assert_climbs(map: &Heightmap, route: &Found<Pos, u32>) {
+        assert_eq!(route.path.len() as u32, route.cost + 1);
+        assert_eq!(*route.goal(), map.end);
+        for step in route.path.windows(2) {
+            assert!(map.steps_from(step[0]).any(|next| next == step[1]));
         }
     }
+
+    #[test]
+    fn the_routes_climb_one_square_at_a_time() {
+        let map = parse_input(include_str!("ex1.txt")).unwrap();
+
+        let route = a::route(&map).unwrap();
+        assert_eq!(route.path[0], map.start.unwrap());
+        assert_eq!(route.cost, 31);
+        assert_climbs(&map, &route);
+
+        let route = b::route(&map).unwrap();
+        assert_eq!(map.grid[route.path[0]], 0);
+        assert_eq!(route.cost, 29);
+        assert_climbs(&map, &route);
+    }
 }
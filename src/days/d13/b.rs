@@ -1,17 +1,10 @@
-use std::{
-    cmp::Ordering,
-    fmt::Display,
-    iter::zip,
-    str::Chars,
-};
+use std::{cmp::Ordering, iter::zip};
 
-#[derive(PartialEq, Eq)]
-enum Node {
-    Int(u32),
-    List(Vec<Node>),
-}
+use crate::days::SolveError;
+
+use super::parser::Node;
 
-fn compare(left: &Node, right: &Node) -> Ordering {
+pub fn compare(left: &Node, right: &Node) -> Ordering {
     match (left, right) {
         (Node::Int(l), Node::Int(r)) => l.cmp(r),
         (Node::Int(num), _) => compare(&Node::List(vec![Node::Int(*num)]), right),
@@ -28,102 +21,32 @@ fn compare(left: &Node, right: &Node) -> Ordering {
     }
 }
 
-impl Display for Node {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Node::Int(num) => write!(f, "{}", num),
-            Node::List(elems) => write!(
-                f,
-                "[{}]",
-                elems
-                    .iter()
-                    .map(|e| (*e).to_string())
-                    .collect::<Vec<_>>()
-                    .join(",")
-            ),
-        }
-    }
-}
-
-fn parse_list(line: &mut Chars) -> Vec<Node> {
-    let mut nodes: Vec<Node> = Vec::new();
-    while let (Some(node), last_char) = parse_node(line) {
-        nodes.push(node);
-
-        if let Some(last) = last_char {
-            match last {
-                ',' => continue,
-                ']' => break,
-                _ => panic!("What??"),
-            }
-        }
-    }
-    nodes
-}
-
-fn is_digit(c: char) -> bool {
-    c as u8 >= b'0' && c as u8 <= b'9'
-}
-
-fn parse_node(line: &mut Chars) -> (Option<Node>, Option<char>) {
-    if let Some(first_char) = line.next() {
-        if first_char == '[' {
-            return (Some(Node::List(parse_list(line))), line.next());
-        } else if is_digit(first_char) {
-            let mut number_so_far: u32 = 0;
-            let mut ch: Option<char> = Some(first_char);
-            while let Some(c) = ch {
-                if is_digit(c) {
-                    number_so_far *= 10;
-                    number_so_far += (c as u32) - ('0' as u32);
-                } else if c == ',' || c == ']' {
-                    return (Some(Node::Int(number_so_far)), ch);
-                }
-                ch = line.next();
-            }
-            return (None, None);
-        }
-    }
-
-    (None, None)
-}
-
-pub fn main(input: &str) -> usize {
-    println!("Hello, world!");
-
-    let mut nodes: Vec<Node> = Vec::new();
-
-    let mut last_line_empty = true;
-
-    for l in input.lines() {
-        if l.is_empty() {
-            if last_line_empty {
-                break;
-            }
-            last_line_empty = true;
-        } else {
-            last_line_empty = false;
-            nodes.push(parse_node(&mut l.chars()).0.unwrap());
-        }
-    }
+pub fn main(pairs: &[(Node, Node)]) -> Result<usize, SolveError> {
+    let dividers = [
+        Node::List(vec![Node::List(vec![Node::Int(2)])]),
+        Node::List(vec![Node::List(vec![Node::Int(6)])]),
+    ];
 
-    nodes.push(Node::List(vec![Node::List(vec![Node::Int(2)])]));
-    nodes.push(Node::List(vec![Node::List(vec![Node::Int(6)])]));
+    let mut nodes: Vec<&Node> = pairs
+        .iter()
+        .flat_map(|(l, r)| [l, r])
+        .chain(dividers.iter())
+        .collect();
 
-    nodes.sort_by(compare);
+    nodes.sort_by(|l, r| compare(l, r));
 
     let mut idx2 = 0;
     let mut idx6 = 0;
 
     for (i, node) in nodes.iter().enumerate() {
-        if *node == Node::List(vec![Node::List(vec![Node::Int(2)])]) {
+        if **node == dividers[0] {
             idx2 = i;
         }
 
-        if *node == Node::List(vec![Node::List(vec![Node::Int(6)])]) {
+        if **node == dividers[1] {
             idx6 = i;
         }
     }
 
-    (idx2 + 1) * (idx6 + 1)
+    Ok((idx2 + 1) * (idx6 + 1))
 }
@@ -1,16 +1,85 @@
+use super::SolveError;
+
 mod a;
 mod b;
+mod parser;
 
 pub struct Day;
 
 impl super::Runner for Day {
+    type Parsed<'a> = Vec<(parser::Node, parser::Node)>;
     type T = usize;
 
-    fn run(input: &str, basic: bool) -> Self::T {
-        if basic {
-            a::main(input)
-        } else {
-            b::main(input)
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parser::parse_input(input)
+    }
+
+    fn part1(pairs: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        a::main(pairs)
+    }
+
+    fn part2(pairs: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        b::main(pairs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::{a, b, parser};
+    use crate::gen;
+
+    fn generated_pairs(seed: u64) -> Vec<(parser::Node, parser::Node)> {
+        parser::parse_input(&gen::generate(13, seed, 20).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn both_compares_agree_on_generated_packets() {
+        for seed in 0..10 {
+            let pairs = generated_pairs(seed);
+            let packets: Vec<_> = pairs.iter().flat_map(|(l, r)| [l, r]).collect();
+            for (i, l) in packets.iter().enumerate() {
+                for (j, r) in packets.iter().enumerate() {
+                    assert_eq!(
+                        a::compare(l, r).cmp(&0),
+                        b::compare(l, r),
+                        "packets {} and {} with seed {}",
+                        i,
+                        j,
+                        seed
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn both_parts_agree_with_the_other_compare() {
+        let divider = |n| parser::Node::List(vec![parser::Node::List(vec![parser::Node::Int(n)])]);
+        for seed in 0..10 {
+            let pairs = generated_pairs(seed);
+
+            let ordered_pairs = pairs
+                .iter()
+                .enumerate()
+                .filter(|(_, (l, r))| b::compare(l, r) == Ordering::Less)
+                .map(|(i, _)| i + 1)
+                .sum();
+            assert_eq!(a::main(&pairs), Ok(ordered_pairs), "seed {}", seed);
+
+            // the sort is stable, so each divider goes after the packets smaller than or equal to
+            // it, and [[6]] also goes after [[2]]
+            let before = |n| {
+                let divider = divider(n);
+                pairs
+                    .iter()
+                    .flat_map(|(l, r)| [l, r])
+                    .filter(|p| a::compare(p, &divider) <= 0)
+                    .count()
+            };
+            let decoder_key = (before(2) + 1) * (before(6) + 2);
+            assert_eq!(b::main(&pairs), Ok(decoder_key), "seed {}", seed);
         }
     }
 }
@@ -0,0 +1,116 @@
+use std::{fmt::Display, str::Chars};
+
+use crate::days::SolveError;
+
+#[derive(PartialEq, Eq)]
+pub enum Node {
+    Int(u32),
+    List(Vec<Node>),
+}
+
+impl Display for Node {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Node::Int(num) => write!(f, "{}", num),
+            Node::List(elems) => write!(
+                f,
+                "[{}]",
+                elems
+                    .iter()
+                    .map(|e| (*e).to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+        }
+    }
+}
+
+/// Returns None if the list is malformed
+fn parse_list(line: &mut Chars) -> Option<Vec<Node>> {
+    let mut nodes: Vec<Node> = Vec::new();
+    while let (Some(node), last_char) = parse_node(line) {
+        nodes.push(node);
+
+        if let Some(last) = last_char {
+            match last {
+                ',' => continue,
+                ']' => break,
+                _ => return None,
+            }
+        }
+    }
+    Some(nodes)
+}
+
+fn is_digit(c: char) -> bool {
+    c as u8 >= b'0' && c as u8 <= b'9'
+}
+
+fn parse_node(line: &mut Chars) -> (Option<Node>, Option<char>) {
+    if let Some(first_char) = line.next() {
+        if first_char == '[' {
+            return match parse_list(line) {
+                Some(list) => (Some(Node::List(list)), line.next()),
+                None => (None, None),
+            };
+        } else if is_digit(first_char) {
+            let mut number_so_far: u32 = 0;
+            let mut ch: Option<char> = Some(first_char);
+            while let Some(c) = ch {
+                if is_digit(c) {
+                    number_so_far *= 10;
+                    number_so_far += (c as u32) - ('0' as u32);
+                } else if c == ',' || c == ']' {
+                    return (Some(Node::Int(number_so_far)), ch);
+                }
+                ch = line.next();
+            }
+            return (None, None);
+        }
+    }
+
+    (None, None)
+}
+
+fn parse_pair(lines: &[(usize, String)]) -> Result<(Node, Node), SolveError> {
+    match lines {
+        [(lidx, l), (ridx, r)] => Ok((parse_packet(l, *lidx)?, parse_packet(r, *ridx)?)),
+        _ => Err(SolveError::parse(
+            lines[0].0 + 1,
+            1,
+            "expected a pair of packets",
+        )),
+    }
+}
+
+fn parse_packet(line: &str, idx: usize) -> Result<Node, SolveError> {
+    parse_node(&mut line.chars())
+        .0
+        .ok_or_else(|| SolveError::parse(idx + 1, 1, format!("invalid packet: {}", line)))
+}
+
+/// The pairs of packets, which are separated by empty lines
+pub fn parse_input(input: &str) -> Result<Vec<(Node, Node)>, SolveError> {
+    let mut pairs: Vec<(Node, Node)> = Vec::new();
+
+    let mut nodes: Vec<(usize, String)> = Vec::new();
+
+    for (idx, l) in input.lines().enumerate() {
+        if l.is_empty() {
+            if nodes.is_empty() {
+                break;
+            } else {
+                pairs.push(parse_pair(&nodes)?);
+                nodes.clear();
+            }
+        } else {
+            nodes.push((idx, l.to_string()));
+        }
+    }
+
+    if !nodes.is_empty() {
+        pairs.push(parse_pair(&nodes)?);
+    }
+
+    Ok(pairs)
+}
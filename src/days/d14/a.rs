@@ -1,146 +1,78 @@
-use std::{
-    collections::HashSet,
-    fmt::Display,
+use std::fmt::Display;
+
+use crate::{
+    days::SolveError,
+    grid::{Point, SparseGrid},
 };
 
+use super::Tile;
+
 struct Cave {
-    stones: HashSet<(i32, i32)>,
-    sand: HashSet<(i32, i32)>,
+    tiles: SparseGrid<Tile>,
+    sand: usize,
 }
 
 impl Cave {
-    fn new() -> Self {
+    fn new(rock: SparseGrid<Tile>) -> Self {
         Cave {
-            stones: HashSet::new(),
-            sand: HashSet::new(),
+            tiles: rock,
+            sand: 0,
         }
     }
 
-    fn x_lo(&self) -> Option<i32> {
-        self.stones.iter().min_by_key(|p| p.0).map(|r| r.0)
-    }
-
-    fn x_hi(&self) -> Option<i32> {
-        self.stones.iter().max_by_key(|p| p.0).map(|r| r.0)
-    }
-
-    fn y_lo(&self) -> Option<i32> {
-        self.stones.iter().min_by_key(|p| p.1).map(|r| r.1)
-    }
-
-    fn y_hi(&self) -> Option<i32> {
-        self.stones.iter().max_by_key(|p| p.1).map(|r| r.1)
-    }
-
-    /// Simulates a piece of sand falling from (start_x, start_y).
-    /// Returns Some((x, y)) if the sand stopped at (x, y), and None if the sand fell into the void.
-    fn simulate_one_step(&self, start_x: i32, start_y: i32, floor: i32) -> Option<(i32, i32)> {
-        let mut x = start_x;
-        let mut y = start_y;
-
-        while y < floor {
-            if !self.stones.contains(&(x, y + 1)) && !self.sand.contains(&(x, y + 1)) {
-                y += 1;
-            } else if !self.stones.contains(&(x - 1, y + 1)) && !self.sand.contains(&(x - 1, y + 1))
-            {
-                x -= 1;
-                y += 1;
-            } else if !self.stones.contains(&(x + 1, y + 1)) && !self.sand.contains(&(x + 1, y + 1))
-            {
-                x += 1;
-                y += 1;
-            } else {
-                break;
+    /// Simulates a piece of sand falling from `start`.
+    /// Returns Some(p) if the sand stopped at p, and None if the sand fell into the void.
+    fn simulate_one_step(&self, start: Point, floor: i32) -> Option<Point> {
+        let mut p = start;
+
+        while p.y < floor {
+            let below = [0, -1, 1]
+                .map(|dx| Point::new(p.x + dx, p.y + 1))
+                .into_iter()
+                .find(|&next| !self.tiles.contains(next));
+            match below {
+                Some(next) => p = next,
+                None => break,
             }
         }
 
-        if y < floor {
-            Some((x, y))
+        if p.y < floor {
+            Some(p)
         } else {
             None
         }
     }
 
-    fn simulate(&mut self, start_x: i32, start_y: i32) {
-        let floor = self.y_hi().unwrap();
-        while let Some((x, y)) = self.simulate_one_step(start_x, start_y, floor) {
-            self.sand.insert((x, y));
+    fn simulate(&mut self, start: Point, mut observe: impl FnMut(&SparseGrid<Tile>)) {
+        let floor = self.tiles.bounds().unwrap().bottom;
+        while let Some(p) = self.simulate_one_step(start, floor) {
+            self.tiles.insert(p, Tile::Sand);
+            self.sand += 1;
+            observe(&self.tiles);
         }
     }
 }
 
 impl Display for Cave {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.stones.is_empty() {
-            return std::fmt::Result::Ok(());
-        }
-
-        let x_lo = self.x_lo().unwrap();
-        let x_hi = self.x_hi().unwrap();
-        let y_lo = self.y_lo().unwrap();
-        let y_hi = self.y_hi().unwrap();
-
-        let mut lines: Vec<String> = Vec::new();
-        for y in y_lo..=y_hi {
-            let s = (x_lo..=x_hi)
-                .map(|x| {
-                    if self.stones.contains(&(x, y)) {
-                        '#'
-                    } else if self.sand.contains(&(x, y)) {
-                        'o'
-                    } else {
-                        '.'
-                    }
-                })
-                .collect::<String>();
-            lines.push(s);
-        }
-        write!(f, "{}", lines.join("\n"))
+        write!(f, "{}", self.tiles)
     }
 }
 
-pub fn main(input: &str) -> usize {
-    let mut cave = Cave::new();
-
-    for line in input.lines() {
-        if line.is_empty() {
-            break;
-        }
-
-        let pts: Vec<(i32, i32)> = line
-            .split(" -> ")
-            .map(|part| {
-                let (ls, rs) = part.split_once(',').unwrap();
-                (ls.parse::<i32>().unwrap(), rs.parse::<i32>().unwrap())
-            })
-            .collect();
-
-        for i in 1..pts.len() {
-            let prev = pts[i - 1];
-            let here = pts[i];
-
-            let dx = (here.0 - prev.0).signum();
-            let dy = (here.1 - prev.1).signum();
-
-            let mut x = prev.0;
-            let mut y = prev.1;
+/// Pours the sand, showing `observe` the cave at the start and whenever a grain comes to rest
+fn fill(rock: &SparseGrid<Tile>, mut observe: impl FnMut(&SparseGrid<Tile>)) -> Cave {
+    let mut cave = Cave::new(rock.clone());
 
-            loop {
-                cave.stones.insert((x, y));
-
-                if x == here.0 && y == here.1 {
-                    break;
-                }
-
-                x += dx;
-                y += dy;
-            }
-        }
-    }
+    observe(&cave.tiles);
+    cave.simulate(Point::new(500, 0), observe);
+    cave
+}
 
-    println!("{}", cave);
-    println!();
+/// The rock and the sand at rest once the sand flows into the abyss
+pub fn pour(rock: &SparseGrid<Tile>, observe: impl FnMut(&SparseGrid<Tile>)) -> SparseGrid<Tile> {
+    fill(rock, observe).tiles
+}
 
-    cave.simulate(500, 0);
-    cave.sand.len()
+pub fn main(rock: &SparseGrid<Tile>) -> Result<usize, SolveError> {
+    Ok(fill(rock, |_| {}).sand)
 }
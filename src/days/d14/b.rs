@@ -1,164 +1,85 @@
-use std::{
-    collections::HashSet,
-    fmt::Display,
+use std::fmt::Display;
+
+use crate::{
+    days::SolveError,
+    grid::{Point, SparseGrid},
 };
 
+use super::Tile;
+
 struct Cave {
-    stones: HashSet<(i32, i32)>,
-    sand: HashSet<(i32, i32)>,
+    tiles: SparseGrid<Tile>,
+    sand: usize,
 }
 
 impl Cave {
-    fn new() -> Self {
+    fn new(rock: SparseGrid<Tile>) -> Self {
         Cave {
-            stones: HashSet::new(),
-            sand: HashSet::new(),
+            tiles: rock,
+            sand: 0,
         }
     }
 
-    fn x_lo(&self) -> Option<i32> {
-        let stones = self.stones.iter();
-        let sand = self.sand.iter();
-        stones.chain(sand).map(|r| r.0).min()
-    }
-
-    fn x_hi(&self) -> Option<i32> {
-        let stones = self.stones.iter();
-        let sand = self.sand.iter();
-        stones.chain(sand).map(|r| r.0).max()
-    }
-
-    fn y_lo(&self) -> Option<i32> {
-        let stones = self.stones.iter();
-        let sand = self.sand.iter();
-        stones.chain(sand).map(|r| r.1).min()
-    }
-
-    fn y_hi(&self) -> Option<i32> {
-        let stones = self.stones.iter();
-        let sand = self.sand.iter();
-        stones.chain(sand).map(|r| r.1).max()
-    }
-
     fn add_floor(&mut self) {
-        let x_lo = self.x_lo().unwrap();
-        let x_hi = self.x_hi().unwrap();
-        let y_lo = self.y_lo().unwrap();
-        let y_hi = self.y_hi().unwrap();
+        let b = self.tiles.bounds().unwrap();
+        let depth = b.bottom - b.top;
 
-        for x in (x_lo - (y_hi - y_lo) - 40)..=(x_hi + (y_hi - y_lo) + 40) {
-            self.stones.insert((x, y_hi + 2));
+        for x in (b.left - depth - 40)..=(b.right + depth + 40) {
+            self.tiles.insert(Point::new(x, b.bottom + 2), Tile::Rock);
         }
     }
 
-    fn simulate_one_step(&self, start_x: i32, start_y: i32) -> Option<(i32, i32)> {
-        let mut x = start_x;
-        let mut y = start_y;
+    fn simulate_one_step(&self, start: Point) -> Option<Point> {
+        let mut p = start;
 
-        if self.stones.contains(&(x, y)) || self.sand.contains(&(x, y)) {
+        if self.tiles.contains(p) {
             return None;
         }
 
         loop {
-            if !self.stones.contains(&(x, y + 1)) && !self.sand.contains(&(x, y + 1)) {
-                y += 1;
-            } else if !self.stones.contains(&(x - 1, y + 1)) && !self.sand.contains(&(x - 1, y + 1))
-            {
-                x -= 1;
-                y += 1;
-            } else if !self.stones.contains(&(x + 1, y + 1)) && !self.sand.contains(&(x + 1, y + 1))
-            {
-                x += 1;
-                y += 1;
-            } else {
-                break;
+            let below = [0, -1, 1]
+                .map(|dx| Point::new(p.x + dx, p.y + 1))
+                .into_iter()
+                .find(|&next| !self.tiles.contains(next));
+            match below {
+                Some(next) => p = next,
+                None => break,
             }
         }
 
-        Some((x, y))
+        Some(p)
     }
 
-    fn simulate(&mut self, start_x: i32, start_y: i32) {
-        while let Some((x, y)) = self.simulate_one_step(start_x, start_y) {
-            self.sand.insert((x, y));
+    fn simulate(&mut self, start: Point, mut observe: impl FnMut(&SparseGrid<Tile>)) {
+        while let Some(p) = self.simulate_one_step(start) {
+            self.tiles.insert(p, Tile::Sand);
+            self.sand += 1;
+            observe(&self.tiles);
         }
     }
 }
 
 impl Display for Cave {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.stones.is_empty() {
-            return std::fmt::Result::Ok(());
-        }
-
-        let x_lo = self.x_lo().unwrap();
-        let x_hi = self.x_hi().unwrap();
-        let y_lo = self.y_lo().unwrap();
-        let y_hi = self.y_hi().unwrap();
-
-        let mut lines: Vec<String> = Vec::new();
-        for y in y_lo..=y_hi {
-            let s = (x_lo..=x_hi)
-                .map(|x| {
-                    if self.stones.contains(&(x, y)) {
-                        '#'
-                    } else if self.sand.contains(&(x, y)) {
-                        'o'
-                    } else {
-                        '.'
-                    }
-                })
-                .collect::<String>();
-            lines.push(s);
-        }
-        write!(f, "{}", lines.join("\n"))
+        write!(f, "{}", self.tiles)
     }
 }
 
-pub fn main(input: &str) -> usize {
-    let mut cave = Cave::new();
-
-    for line in input.lines() {
-        if line.is_empty() {
-            break;
-        }
-
-        let pts: Vec<(i32, i32)> = line
-            .split(" -> ")
-            .map(|part| {
-                let (ls, rs) = part.split_once(',').unwrap();
-                (ls.parse::<i32>().unwrap(), rs.parse::<i32>().unwrap())
-            })
-            .collect();
-
-        for i in 1..pts.len() {
-            let prev = pts[i - 1];
-            let here = pts[i];
-
-            let dx = (here.0 - prev.0).signum();
-            let dy = (here.1 - prev.1).signum();
-
-            let mut x = prev.0;
-            let mut y = prev.1;
-
-            loop {
-                cave.stones.insert((x, y));
-
-                if x == here.0 && y == here.1 {
-                    break;
-                }
-
-                x += dx;
-                y += dy;
-            }
-        }
-    }
-
+/// Pours the sand, showing `observe` the cave at the start and whenever a grain comes to rest
+fn fill(rock: &SparseGrid<Tile>, mut observe: impl FnMut(&SparseGrid<Tile>)) -> Cave {
+    let mut cave = Cave::new(rock.clone());
     cave.add_floor();
 
-    println!("{}", cave);
-    println!();
+    observe(&cave.tiles);
+    cave.simulate(Point::new(500, 0), observe);
+    cave
+}
+
+/// The rock, the floor and the sand at rest once the source is blocked
+pub fn pour(rock: &SparseGrid<Tile>, observe: impl FnMut(&SparseGrid<Tile>)) -> SparseGrid<Tile> {
+    fill(rock, observe).tiles
+}
 
-    cave.simulate(500, 0);
-    cave.sand.len()
+pub fn main(rock: &SparseGrid<Tile>) -> Result<usize, SolveError> {
+    Ok(fill(rock, |_| {}).sand)
 }
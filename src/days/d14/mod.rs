@@ -1,16 +1,237 @@
+use std::fmt::Display;
+
+use super::{Part, SolveError};
+use crate::{
+    grid::{Point, SparseGrid},
+    render::{Image, Paint, Recorder, Render, Rgb},
+};
+
 mod a;
 mod b;
 
 pub struct Day;
 
+/// What fills a tile of the cave
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    Rock,
+    Sand,
+}
+
+impl Display for Tile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Tile::Rock => write!(f, "#"),
+            Tile::Sand => write!(f, "o"),
+        }
+    }
+}
+
+impl Paint for Tile {
+    fn colour(&self) -> Rgb {
+        match self {
+            Tile::Rock => Rgb::GREY,
+            Tile::Sand => Rgb::SAND,
+        }
+    }
+}
+
+/// The rock from the scanned paths
+fn parse_input(input: &str) -> Result<SparseGrid<Tile>, SolveError> {
+    let mut rock = SparseGrid::new();
+
+    for (idx, line) in input.lines().enumerate() {
+        if line.is_empty() {
+            break;
+        }
+
+        let mut pts: Vec<(i32, i32)> = Vec::new();
+        let mut col = 0;
+        for part in line.split(" -> ") {
+            let pt = part
+                .split_once(',')
+                .and_then(|(ls, rs)| Some((ls.parse::<i32>().ok()?, rs.parse::<i32>().ok()?)));
+            match pt {
+                Some(pt) => pts.push(pt),
+                None => {
+                    return Err(SolveError::parse(
+                        idx + 1,
+                        col + 1,
+                        format!("expected a point like 498,4, found '{}'", part),
+                    ))
+                }
+            }
+            col += part.len() + " -> ".len();
+        }
+
+        for i in 1..pts.len() {
+            let prev = pts[i - 1];
+            let here = pts[i];
+
+            let dx = (here.0 - prev.0).signum();
+            let dy = (here.1 - prev.1).signum();
+
+            let mut x = prev.0;
+            let mut y = prev.1;
+
+            loop {
+                rock.insert(Point::new(x, y), Tile::Rock);
+
+                if x == here.0 && y == here.1 {
+                    break;
+                }
+
+                x += dx;
+                y += dy;
+            }
+        }
+    }
+
+    if rock.is_empty() {
+        return Err(SolveError::unsupported("the cave has no rock"));
+    }
+
+    Ok(rock)
+}
+
+/// The cave once the sand stops
+pub fn render(input: &str, part: Part) -> Result<Image, SolveError> {
+    let rock = parse_input(input)?;
+    let cave = match part {
+        Part::Basic => a::pour(&rock, |_| {}),
+        Part::Advanced => b::pour(&rock, |_| {}),
+    };
+    Ok(cave.render())
+}
+
+/// The cave whenever a grain of sand comes to rest
+pub fn animate(input: &str, part: Part, recorder: &mut Recorder) -> Result<(), SolveError> {
+    let rock = parse_input(input)?;
+    let mut observe = |cave: &SparseGrid<Tile>| recorder.step(|| cave.frame());
+    let cave = match part {
+        Part::Basic => a::pour(&rock, &mut observe),
+        Part::Advanced => b::pour(&rock, &mut observe),
+    };
+    recorder.end(|| cave.frame());
+    Ok(())
+}
+
 impl super::Runner for Day {
+    type Parsed<'a> = SparseGrid<Tile>;
     type T = usize;
 
-    fn run(input: &str, basic: bool) -> Self::T {
-        if basic {
-            a::main(input)
-        } else {
-            b::main(input)
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(rock: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        a::main(rock)
+    }
+
+    fn part2(rock: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        b::main(rock)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{a, animate, b, parse_input, render};
+    use crate::{
+        days::Part,
+        gen,
+        grid::Pos,
+        render::{Recorder, Rgb},
+    };
+
+    /// The rock as plain coordinates, so the oracles share nothing with the solvers
+    fn stones(input: &str) -> HashSet<(i32, i32)> {
+        let rock = parse_input(input).unwrap();
+        rock.iter().map(|(p, _)| (p.x, p.y)).collect()
+    }
+
+    /// Drops the sand one grain and one step at a time, until a grain falls past the lowest rock
+    fn grains_until_the_void(stones: &HashSet<(i32, i32)>) -> usize {
+        let bottom = stones.iter().map(|p| p.1).max().unwrap();
+        let mut blocked = stones.clone();
+        let mut grains = 0;
+        loop {
+            let (mut x, mut y) = (500, 0);
+            loop {
+                if y >= bottom {
+                    return grains;
+                }
+                match [x, x - 1, x + 1]
+                    .into_iter()
+                    .find(|&nx| !blocked.contains(&(nx, y + 1)))
+                {
+                    Some(nx) => (x, y) = (nx, y + 1),
+                    None => break,
+                }
+            }
+            blocked.insert((x, y));
+            grains += 1;
         }
     }
+
+    /// With a floor, sand comes to rest on every tile above it that can be reached from the
+    /// source, that is every free tile below a tile with sand or next to one diagonally
+    fn grains_above_the_floor(stones: &HashSet<(i32, i32)>) -> usize {
+        let floor = stones.iter().map(|p| p.1).max().unwrap() + 2;
+        let mut sand: HashSet<(i32, i32)> = HashSet::from([(500, 0)]);
+        for y in 1..floor {
+            for x in (500 - y)..=(500 + y) {
+                let fed = (x - 1..=x + 1).any(|fx| sand.contains(&(fx, y - 1)));
+                if fed && !stones.contains(&(x, y)) {
+                    sand.insert((x, y));
+                }
+            }
+        }
+        sand.len()
+    }
+
+    #[test]
+    fn both_caves_agree_with_the_oracles_on_generated_rock() {
+        for seed in 0..5 {
+            let input = gen::generate(14, seed, 10).unwrap();
+            let rock = parse_input(&input).unwrap();
+            let stones = stones(&input);
+            assert_eq!(
+                a::main(&rock),
+                Ok(grains_until_the_void(&stones)),
+                "seed {}",
+                seed
+            );
+            assert_eq!(
+                b::main(&rock),
+                Ok(grains_above_the_floor(&stones)),
+                "seed {}",
+                seed
+            );
+        }
+    }
+
+    #[test]
+    fn the_cave_is_drawn_with_its_sand() {
+        let image = render(include_str!("ex1.txt"), Part::Basic).unwrap();
+        let sand = image.iter().filter(|(_, &c)| c == Rgb::SAND).count();
+        assert_eq!(sand, 24);
+        // the rock spans 494 to 503 across and the sand rises to row 2
+        assert_eq!((image.width(), image.height()), (10, 8));
+        assert_eq!(image[Pos::new(6, 0)], Rgb::SAND);
+    }
+
+    #[test]
+    fn every_grain_is_a_frame() {
+        let mut recorder = Recorder::new(5, 100);
+        animate(include_str!("ex1.txt"), Part::Basic, &mut recorder).unwrap();
+        // the rock alone, then one grain in 5 of the 24, then the last
+        assert_eq!(recorder.steps(), 25);
+        assert_eq!(recorder.frames().len(), 6);
+        // the sand piles up above the rock, so the frames start higher and higher
+        let tops: Vec<_> = recorder.frames().iter().map(|f| f.origin.y).collect();
+        assert_eq!(tops.first(), Some(&4));
+        assert_eq!(tops.last(), Some(&2));
+    }
 }
@@ -1,16 +1,184 @@
+use super::SolveError;
+use crate::parse::Parser;
+
 mod a;
 mod b;
 
 pub struct Day;
 
+#[derive(Clone, Copy)]
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+impl Point {
+    fn dist(&self, other: Point) -> u32 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+}
+
+pub struct SensorReading {
+    sensor: Point,
+    beacon: Point,
+}
+
+impl SensorReading {
+    fn range(&self) -> u32 {
+        self.sensor.dist(self.beacon)
+    }
+}
+
+/// `x=<x>, y=<y>`
+fn parse_point(p: &mut Parser) -> Result<Point, SolveError> {
+    p.literal("x=")?;
+    let x = p.integer()?;
+    p.literal(", y=")?;
+    let y = p.integer()?;
+    Ok(Point { x, y })
+}
+
+/// `Sensor at <point>: closest beacon is at <point>`
+fn parse_line(line: &str, idx: usize) -> Result<SensorReading, SolveError> {
+    let mut p = Parser::new(line, idx);
+    p.literal("Sensor at ")?;
+    let sensor = parse_point(&mut p)?;
+    p.literal(": closest beacon is at ")?;
+    let beacon = parse_point(&mut p)?;
+    p.end()?;
+    Ok(SensorReading { sensor, beacon })
+}
+
+fn parse_input(input: &str) -> Result<Vec<SensorReading>, SolveError> {
+    let mut readings: Vec<SensorReading> = Vec::new();
+
+    for (idx, line) in input.lines().enumerate() {
+        if line.is_empty() {
+            break;
+        }
+
+        readings.push(parse_line(line, idx)?);
+    }
+    if readings.is_empty() {
+        return Err(SolveError::unsupported("there are no sensor readings"));
+    }
+
+    Ok(readings)
+}
+
 impl super::Runner for Day {
+    type Parsed<'a> = Vec<SensorReading>;
     type T = i64;
 
-    fn run(input: &str, basic: bool) -> Self::T {
-        if basic {
-            a::main(input)
-        } else {
-            b::main(input)
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(readings: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        a::main(readings)
+    }
+
+    fn part2(readings: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        b::main(readings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{a, b, parse_input, Point, SensorReading};
+    use crate::gen;
+
+    fn covered(readings: &[SensorReading], point: Point) -> bool {
+        readings.iter().any(|r| r.sensor.dist(point) <= r.range())
+    }
+
+    /// Merges the stretches of row 10 that each sensor covers and takes away the beacons on them
+    fn positions_without_a_beacon(readings: &[SensorReading]) -> i64 {
+        let mut stretches: Vec<(i32, i32)> = readings
+            .iter()
+            .filter_map(|r| {
+                let reach = r.range() as i32 - (r.sensor.y - 10).abs();
+                (reach >= 0).then_some((r.sensor.x - reach, r.sensor.x + reach))
+            })
+            .collect();
+        stretches.sort();
+
+        let mut merged: Vec<(i32, i32)> = Vec::new();
+        for (lo, hi) in stretches {
+            match merged.last_mut() {
+                Some(last) if lo <= last.1 + 1 => last.1 = last.1.max(hi),
+                _ => merged.push((lo, hi)),
+            }
         }
+
+        let mut beacons: Vec<i32> = readings
+            .iter()
+            .filter(|r| r.beacon.y == 10)
+            .map(|r| r.beacon.x)
+            .collect();
+        beacons.sort();
+        beacons.dedup();
+
+        let covered: i64 = merged.iter().map(|(lo, hi)| (hi - lo + 1) as i64).sum();
+        let beacons_on_them = beacons
+            .iter()
+            .filter(|&&x| merged.iter().any(|&(lo, hi)| lo <= x && x <= hi))
+            .count() as i64;
+        covered - beacons_on_them
+    }
+
+    /// Looks at every tile of the 20x20 search area, which must have exactly one uncovered tile
+    fn tuning_frequency(readings: &[SensorReading]) -> i64 {
+        let holes: Vec<Point> = (0..=20)
+            .flat_map(|y| (0..=20).map(move |x| Point { x, y }))
+            .filter(|&p| !covered(readings, p))
+            .collect();
+        assert_eq!(holes.len(), 1);
+        holes[0].x as i64 * 4000000 + holes[0].y as i64
+    }
+
+    #[test]
+    fn both_parts_agree_with_the_oracles() {
+        for seed in 0..10 {
+            let input = gen::generate(15, seed, 14).unwrap();
+            let readings = parse_input(&input).unwrap();
+            assert_eq!(
+                a::main(&readings),
+                Ok(positions_without_a_beacon(&readings)),
+                "seed {}",
+                seed
+            );
+            assert_eq!(
+                b::main(&readings),
+                Ok(tuning_frequency(&readings)),
+                "seed {}",
+                seed
+            );
+        }
+    }
+
+    #[test]
+    fn the_tile_of_a_sensor_on_the_row_cannot_hold_a_beacon() {
+        // the sensor covers x=-2..=2 of row 10, of which only its beacon holds one
+        let readings = parse_input("Sensor at x=0, y=10: closest beacon is at x=2, y=10").unwrap();
+        assert_eq!(a::main(&readings), Ok(4));
+        assert_eq!(positions_without_a_beacon(&readings), 4);
+    }
+
+    #[test]
+    fn malformed_readings_are_reported_where_they_go_wrong() {
+        let error = |line| parse_input(line).err().map(|e| e.to_string());
+        assert_eq!(
+            error("Sensor at x=2, y=18: closest beacon is at x=-2, y=+15"),
+            Some("line 1, column 51: expected an integer".to_string())
+        );
+        assert_eq!(
+            error("Sensor at x=2, y=18: closest beacon at x=-2, y=15"),
+            Some("line 1, column 20: expected ': closest beacon is at '".to_string())
+        );
+        assert_eq!(
+            error("Sensor at x=2 y=18: closest beacon is at x=-2, y=15"),
+            Some("line 1, column 14: expected ', y='".to_string())
+        );
     }
 }
@@ -2,64 +2,50 @@ use std::collections::HashMap;
 
 use bit_set::BitSet;
 
-#[derive(Debug, PartialEq)]
-struct ParseResult {
-    valve_name: String,
-    flow_rate: u32,
-    edges: Vec<String>,
-}
-
-fn parse_line(line: &str) -> Option<ParseResult> {
-    let name = &line[6..8];
+use crate::days::{
+    progress::{Progress, STATES_PER_UPDATE},
+    CancelToken, SolveError,
+};
 
-    let rest1 = &line[23..];
-    let semi_idx = rest1.find(';')?;
-    let flow = rest1[..semi_idx].parse::<u32>().ok()?;
-
-    let edges_idx = match rest1.find("valves") {
-        Some(idx) => idx + 7,
-        None => match rest1.find("valve") {
-            Some(idx) => idx + 6,
-            None => return None,
-        },
-    };
-    let rest2 = &rest1[edges_idx..];
-    let edges: Vec<String> = rest2.split(", ").map(|s| s.to_string()).collect();
-
-    Some(ParseResult {
-        valve_name: name.to_string(),
-        flow_rate: flow,
-        edges,
-    })
-}
-
-struct Valve {
-    flow_rate: u32,
-    edges: Vec<usize>,
-}
+use super::parser::{Graph, Valve};
 
-struct GraphState {
+struct GraphState<'a> {
     valves_open: BitSet<u64>,
     flow_left: u32,
     max_volume_so_far: u32,
+    cancel: &'a CancelToken,
+    progress: &'a dyn Progress,
+    states_explored: u64,
     memoized_results: HashMap<(BitSet<u64>, usize, usize, u32), u32>,
 }
 
 /** My goodness! This is a disgrace! */
 fn find_max_volume(
     graph: &[Valve],
-    graph_state: &mut GraphState,
+    graph_state: &mut GraphState<'_>,
     node_idx: (usize, usize),
     prev_idx: (usize, usize),
     volume_so_far: u32,
     time_left: u32,
 ) -> u32 {
+    graph_state.states_explored += 1;
+    if graph_state
+        .states_explored
+        .is_multiple_of(STATES_PER_UPDATE)
+    {
+        let progress = graph_state.progress;
+        progress.states_explored(graph_state.states_explored);
+        progress.best_so_far(graph_state.max_volume_so_far as u64);
+    }
     if time_left == 0 {
         if volume_so_far > graph_state.max_volume_so_far {
             graph_state.max_volume_so_far = volume_so_far;
         }
         return 0;
     }
+    if graph_state.cancel.is_cancelled() {
+        return 0; // the result is thrown away, only max_volume_so_far is reported
+    }
     if volume_so_far + graph_state.flow_left * (time_left - 1) < graph_state.max_volume_so_far {
         return 0; // this cannot possibly be the best branch, so just give up
     }
@@ -209,116 +195,56 @@ fn find_max_volume(
     max_volume
 }
 
-pub fn find_max_volume_for_input(input: Vec<String>) -> u32 {
-    let mut parsed_lines: Vec<ParseResult> = Vec::new();
-    let mut name_table: HashMap<String, usize> = HashMap::new();
-
-    for line in input {
-        let res = parse_line(line.as_str()).unwrap();
-        name_table.insert(res.valve_name.clone(), parsed_lines.len());
-        parsed_lines.push(res);
-    }
-    let mut graph: Vec<Valve> = Vec::new();
+pub fn find_max_volume_for_graph(
+    graph: &Graph,
+    cancel: &CancelToken,
+    progress: &dyn Progress,
+) -> Result<u32, SolveError> {
     let mut graph_state: GraphState = GraphState {
         valves_open: BitSet::default(),
-        flow_left: 0,
+        flow_left: graph.valves.iter().map(|v| v.flow_rate).sum(),
         max_volume_so_far: 0,
+        cancel,
+        progress,
+        states_explored: 0,
         memoized_results: HashMap::new(),
     };
-    for r in parsed_lines {
-        graph.push(Valve {
-            flow_rate: r.flow_rate,
-            edges: r.edges.iter().map(|name| name_table[name]).collect(),
-        });
-    }
-    let start_node_idx = name_table["AA"];
-    graph_state.flow_left = graph.iter().map(|v| v.flow_rate).sum();
 
-    find_max_volume(
-        &graph,
+    let max_volume = find_max_volume(
+        &graph.valves,
         &mut graph_state,
-        (start_node_idx, start_node_idx),
-        (start_node_idx, start_node_idx),
+        (graph.start, graph.start),
+        (graph.start, graph.start),
         0,
         26,
-    )
-}
-
-pub fn read_input(input: &str) -> Vec<String> {
-    let mut lines: Vec<String> = Vec::new();
-    for line in input.lines() {
-        if line.is_empty() {
-            break;
-        }
-        lines.push(line.to_string());
+    );
+    if cancel.is_cancelled() {
+        return Err(SolveError::timed_out(Some(graph_state.max_volume_so_far)));
     }
-    lines
+    Ok(max_volume)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{find_max_volume_for_input, parse_line, ParseResult};
-
-    #[test]
-    fn parse_line_works_for_single_edge() {
-        let result = parse_line("Valve AA has flow rate=42; tunnel leads to valve DD");
-        assert_eq!(
-            result,
-            Some(ParseResult {
-                valve_name: "AA".to_string(),
-                flow_rate: 42,
-                edges: vec!["DD".to_string()]
-            })
-        );
-    }
-
-    #[test]
-    fn parse_line_works_for_single_digit() {
-        let result = parse_line("Valve AA has flow rate=4; tunnel leads to valve DD");
-        assert_eq!(
-            result,
-            Some(ParseResult {
-                valve_name: "AA".to_string(),
-                flow_rate: 4,
-                edges: vec!["DD".to_string()]
-            })
-        );
-    }
-
-    #[test]
-    fn parse_line_works_for_multiple_edges() {
-        let result = parse_line("Valve AA has flow rate=42; tunnels lead to valves DD, II, BB");
-        assert_eq!(
-            result,
-            Some(ParseResult {
-                valve_name: "AA".to_string(),
-                flow_rate: 42,
-                edges: vec!["DD".to_string(), "II".to_string(), "BB".to_string()]
-            })
-        );
-    }
+    use super::super::parser::parse_input;
+    use super::find_max_volume_for_graph;
+    use crate::days::{CancelToken, NoProgress};
 
     #[test]
     fn example_works() {
-        let input: Vec<String> = include_str!("ex1.txt")
-            .lines()
-            .map(|l| l.to_string())
-            .collect();
+        let graph = parse_input(include_str!("ex1.txt")).unwrap();
 
-        let result = find_max_volume_for_input(input);
+        let result = find_max_volume_for_graph(&graph, &CancelToken::new(), &NoProgress);
 
-        assert_eq!(result, 1707);
+        assert_eq!(result, Ok(1707));
     }
 
     #[test]
     fn big_example_works() {
-        let input: Vec<String> = include_str!("ex2.txt")
-            .lines()
-            .map(|l| l.to_string())
-            .collect();
+        let graph = parse_input(include_str!("ex2.txt")).unwrap();
 
-        let result = find_max_volume_for_input(input);
+        let result = find_max_volume_for_graph(&graph, &CancelToken::new(), &NoProgress);
 
-        assert_eq!(result, 2602);
+        assert_eq!(result, Ok(2602));
     }
 }
@@ -1,16 +1,145 @@
+use super::{CancelToken, NoProgress, Progress, Runner, SolveError};
+
 mod a;
 mod b;
+mod parser;
 
 pub struct Day;
 
-impl super::Runner for Day {
+impl Runner for Day {
+    type Parsed<'a> = parser::Graph;
     type T = u32;
 
-    fn run(input: &str, basic: bool) -> Self::T {
-        if basic {
-            a::find_max_volume_for_input(a::read_input(input))
-        } else {
-            b::find_max_volume_for_input(b::read_input(input))
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parser::parse_input(input)
+    }
+
+    fn part1(graph: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        Self::part1_with(graph, &CancelToken::new(), &NoProgress)
+    }
+
+    fn part2(graph: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        Self::part2_with(graph, &CancelToken::new(), &NoProgress)
+    }
+
+    fn part1_with(
+        graph: &Self::Parsed<'_>,
+        cancel: &CancelToken,
+        progress: &dyn Progress,
+    ) -> Result<Self::T, SolveError> {
+        a::find_max_volume_for_graph(graph, cancel, progress)
+    }
+
+    fn part2_with(
+        graph: &Self::Parsed<'_>,
+        cancel: &CancelToken,
+        progress: &dyn Progress,
+    ) -> Result<Self::T, SolveError> {
+        b::find_max_volume_for_graph(graph, cancel, progress)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{a, b, parser};
+    use crate::days::{CancelToken, NoProgress};
+    use crate::gen;
+
+    /// Tries every move of every minute, remembering the best release from each position, time
+    /// and set of open valves
+    fn best_alone(
+        graph: &parser::Graph,
+        memo: &mut HashMap<(usize, u32, u64), u32>,
+        at: usize,
+        minutes: u32,
+        open: u64,
+    ) -> u32 {
+        if minutes == 0 {
+            return 0;
+        }
+        if let Some(&best) = memo.get(&(at, minutes, open)) {
+            return best;
+        }
+        let valve = &graph.valves[at];
+        let mut best = 0;
+        if valve.flow_rate > 0 && open & 1 << at == 0 {
+            let released = valve.flow_rate * (minutes - 1);
+            best = released + best_alone(graph, memo, at, minutes - 1, open | 1 << at);
+        }
+        for &next in &valve.edges {
+            best = best.max(best_alone(graph, memo, next, minutes - 1, open));
+        }
+        memo.insert((at, minutes, open), best);
+        best
+    }
+
+    /// The moves of one walker in a minute: the open valves after it and what they will release
+    fn moves(graph: &parser::Graph, at: usize, minutes: u32, open: u64) -> Vec<(usize, u64, u32)> {
+        let valve = &graph.valves[at];
+        let mut moves: Vec<_> = valve.edges.iter().map(|&next| (next, open, 0)).collect();
+        if valve.flow_rate > 0 && open & 1 << at == 0 {
+            moves.push((at, open | 1 << at, valve.flow_rate * (minutes - 1)));
+        }
+        moves
+    }
+
+    /// Like [`best_alone`], with every pair of moves of you and the elephant
+    fn best_together(
+        graph: &parser::Graph,
+        memo: &mut HashMap<(usize, usize, u32, u64), u32>,
+        (you, elephant): (usize, usize),
+        minutes: u32,
+        open: u64,
+    ) -> u32 {
+        if minutes == 0 {
+            return 0;
+        }
+        let key = (you.min(elephant), you.max(elephant), minutes, open);
+        if let Some(&best) = memo.get(&key) {
+            return best;
+        }
+        let mut best = 0;
+        for (you, open_after_you, released_by_you) in moves(graph, you, minutes, open) {
+            for (elephant, open_after_both, released_by_elephant) in
+                moves(graph, elephant, minutes, open_after_you)
+            {
+                let released = released_by_you + released_by_elephant;
+                let rest =
+                    best_together(graph, memo, (you, elephant), minutes - 1, open_after_both);
+                best = best.max(released + rest);
+            }
+        }
+        memo.insert(key, best);
+        best
+    }
+
+    #[test]
+    fn both_searches_agree_with_trying_every_move() {
+        for seed in 0..10 {
+            let input = gen::generate(16, seed, 6).unwrap();
+            let graph = parser::parse_input(&input).unwrap();
+            let start = graph.start;
+
+            assert_eq!(
+                a::find_max_volume_for_graph(&graph, &CancelToken::new(), &NoProgress),
+                Ok(best_alone(&graph, &mut HashMap::new(), start, 30, 0)),
+                "seed {}",
+                seed
+            );
+            assert_eq!(
+                b::find_max_volume_for_graph(&graph, &CancelToken::new(), &NoProgress),
+                Ok(best_together(
+                    &graph,
+                    &mut HashMap::new(),
+                    (start, start),
+                    26,
+                    0
+                )),
+                "seed {}",
+                seed
+            );
         }
     }
 }
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+
+use crate::{days::SolveError, parse::Parser};
+
+#[derive(Debug, PartialEq)]
+struct ParseResult {
+    valve_name: String,
+    flow_rate: u32,
+    edges: Vec<String>,
+}
+
+/// `Valve <name> has flow rate=<rate>; tunnels lead to valves <names>`
+fn parse_line(idx: usize, line: &str) -> Result<ParseResult, SolveError> {
+    let name = |p: &mut Parser| {
+        p.token("a valve name", |c| c.is_ascii_uppercase())
+            .map(str::to_string)
+    };
+
+    let mut p = Parser::new(line, idx);
+    p.literal("Valve ")?;
+    let valve_name = name(&mut p)?;
+    p.literal(" has flow rate=")?;
+    let flow_rate = p.integer()?;
+    p.literal("; ")?;
+    p.one_of(&["tunnels lead to valves ", "tunnel leads to valve "])?;
+    let edges = p.separated(", ", name)?;
+    p.end()?;
+
+    Ok(ParseResult {
+        valve_name,
+        flow_rate,
+        edges,
+    })
+}
+
+pub struct Valve {
+    pub flow_rate: u32,
+    pub edges: Vec<usize>,
+}
+
+/// The valves with the indices of the valves their tunnels lead to
+pub struct Graph {
+    pub valves: Vec<Valve>,
+    /// The index of valve AA
+    pub start: usize,
+}
+
+pub fn parse_input(input: &str) -> Result<Graph, SolveError> {
+    let input: Vec<&str> = input.lines().take_while(|l| !l.is_empty()).collect();
+
+    let mut parsed_lines: Vec<ParseResult> = Vec::new();
+    let mut name_table: HashMap<String, usize> = HashMap::new();
+
+    for (idx, line) in input.iter().enumerate() {
+        let res = parse_line(idx, line)?;
+        name_table.insert(res.valve_name.clone(), parsed_lines.len());
+        parsed_lines.push(res);
+    }
+    let mut graph: Vec<Valve> = Vec::new();
+    for (idx, r) in parsed_lines.into_iter().enumerate() {
+        let edges = r
+            .edges
+            .iter()
+            .map(|name| {
+                name_table.get(name).copied().ok_or_else(|| {
+                    let col = input[idx].rfind(name.as_str()).unwrap_or(0);
+                    SolveError::parse(idx + 1, col + 1, format!("unknown valve: {}", name))
+                })
+            })
+            .collect::<Result<Vec<usize>, SolveError>>()?;
+        graph.push(Valve {
+            flow_rate: r.flow_rate,
+            edges,
+        });
+    }
+    let start = *name_table
+        .get("AA")
+        .ok_or_else(|| SolveError::unsupported("there is no valve AA to start from"))?;
+
+    Ok(Graph {
+        valves: graph,
+        start,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_line, ParseResult};
+    use crate::days::SolveError;
+
+    #[test]
+    fn parse_line_works_for_single_edge() {
+        let result = parse_line(0, "Valve AA has flow rate=42; tunnel leads to valve DD");
+        assert_eq!(
+            result,
+            Ok(ParseResult {
+                valve_name: "AA".to_string(),
+                flow_rate: 42,
+                edges: vec!["DD".to_string()]
+            })
+        );
+    }
+
+    #[test]
+    fn parse_line_works_for_single_digit() {
+        let result = parse_line(0, "Valve AA has flow rate=4; tunnel leads to valve DD");
+        assert_eq!(
+            result,
+            Ok(ParseResult {
+                valve_name: "AA".to_string(),
+                flow_rate: 4,
+                edges: vec!["DD".to_string()]
+            })
+        );
+    }
+
+    #[test]
+    fn parse_line_works_for_multiple_edges() {
+        let result = parse_line(
+            0,
+            "Valve AA has flow rate=42; tunnels lead to valves DD, II, BB",
+        );
+        assert_eq!(
+            result,
+            Ok(ParseResult {
+                valve_name: "AA".to_string(),
+                flow_rate: 42,
+                edges: vec!["DD".to_string(), "II".to_string(), "BB".to_string()]
+            })
+        );
+    }
+
+    #[test]
+    fn parse_line_points_at_what_is_malformed() {
+        assert_eq!(
+            parse_line(3, "Valve AA has flow rate=x; tunnel leads to valve DD"),
+            Err(SolveError::parse(4, 24, "expected an integer"))
+        );
+        assert_eq!(
+            parse_line(0, "Valve AA has flow rate=1; tunnels lead to valve DD"),
+            Err(SolveError::parse(
+                1,
+                27,
+                "expected 'tunnels lead to valves ' or 'tunnel leads to valve '"
+            ))
+        );
+        assert_eq!(
+            parse_line(0, "Valve AA has flow rate=1; tunnel leads to valve DD,"),
+            Err(SolveError::parse(1, 51, "unexpected ','"))
+        );
+    }
+}
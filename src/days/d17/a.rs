@@ -1,5 +1,9 @@
 use std::fmt::Display;
 
+use crate::{days::SolveError, grid::Grid};
+
+use super::WindDirection;
+
 struct RepeatingSequence<T> {
     items: Vec<T>,
     index: usize,
@@ -72,23 +76,7 @@ impl Shape {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-enum WindDirection {
-    Left,
-    Right,
-}
-
-fn parse_wind_list(line: String) -> Result<Vec<WindDirection>, String> {
-    line.chars()
-        .map(|c| match c {
-            '<' => Ok(WindDirection::Left),
-            '>' => Ok(WindDirection::Right),
-            _ => Err(format!("invalid wind direction: {}", c)),
-        })
-        .collect::<Result<Vec<_>, _>>()
-}
-
-struct Board {
+pub struct Board {
     rows: Vec<[bool; 7]>, // from the bottom
     wind: RepeatingSequence<WindDirection>,
 }
@@ -105,6 +93,12 @@ impl Board {
         self.rows.len()
     }
 
+    /// The chamber from the top of the tower down
+    pub fn tower(&self) -> Grid<bool> {
+        let rows = self.rows.iter().rev().map(|row| row.to_vec()).collect();
+        Grid::from_ragged_rows(rows, false)
+    }
+
     fn overlaps_with_shape(&self, shape: Shape, x: usize, y: usize) -> bool {
         let w = shape.width();
         let h = shape.height();
@@ -188,12 +182,14 @@ impl Display for Board {
     }
 }
 
-pub fn read_input(input: &str) -> String {
-    input.lines().next().unwrap().to_string()
-}
-
-pub fn find_tower_height(input: String, iterations: u32) -> usize {
-    let wind = RepeatingSequence::new(parse_wind_list(input).unwrap());
+/// Lets the rocks fall, showing `observe` the chamber at the start and whenever a rock comes to
+/// rest
+pub fn stack(
+    wind_list: &[WindDirection],
+    iterations: u32,
+    mut observe: impl FnMut(&Board),
+) -> Board {
+    let wind = RepeatingSequence::new(wind_list.to_vec());
 
     let mut shapes = RepeatingSequence::new(vec![
         Shape::Dash,
@@ -204,20 +200,27 @@ pub fn find_tower_height(input: String, iterations: u32) -> usize {
     ]);
 
     let mut board = Board::new(wind);
+    observe(&board);
 
     for _ in 0..iterations {
         let shape = shapes.next().unwrap();
         board.add_shape(shape);
+        observe(&board);
     }
 
-    board.tower_height()
+    board
+}
+
+pub fn find_tower_height(wind_list: &[WindDirection], iterations: u32) -> Result<usize, SolveError> {
+    Ok(stack(wind_list, iterations, |_| {}).tower_height())
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        find_tower_height, parse_wind_list, Board, RepeatingSequence, Shape, WindDirection,
+        find_tower_height, stack, Board, RepeatingSequence, Shape, WindDirection,
     };
+    use crate::grid::Pos;
 
     fn make_left_wind() -> RepeatingSequence<WindDirection> {
         RepeatingSequence::new(vec![WindDirection::Left])
@@ -246,15 +249,6 @@ mod tests {
         assert_eq!(seq.next(), Some(1));
     }
 
-    #[test]
-    fn parse_wind_list_works() {
-        use super::WindDirection::*;
-        assert_eq!(
-            parse_wind_list("<<<>><".to_string()),
-            Ok(vec![Left, Left, Left, Right, Right, Left])
-        )
-    }
-
     #[test]
     fn an_empty_board_has_height_0() {
         let board = Board::new(make_left_wind());
@@ -312,15 +306,29 @@ mod tests {
         assert_eq!(board.tower_height(), 8);
     }
 
+    #[test]
+    fn the_tower_is_drawn_from_the_top() {
+        let input = super::super::parse_input(include_str!("ex1.txt")).unwrap();
+        let chamber = stack(&input, 1, |_| {}).tower();
+        // the first rock is pushed right, then back left and rests at the bottom
+        assert_eq!((chamber.width(), chamber.height()), (7, 1));
+        assert_eq!(chamber.row(0), [false, false, true, true, true, true, false]);
+
+        let chamber = stack(&input, 2, |_| {}).tower();
+        assert_eq!(chamber.height(), 4);
+        assert!(chamber[Pos::new(3, 0)]);
+        assert!(!chamber[Pos::new(2, 0)]);
+    }
+
     #[test]
     fn example_works() {
-        let input = include_str!("ex1.txt").to_string();
-        assert_eq!(find_tower_height(input, 2022), 3068);
+        let input = super::super::parse_input(include_str!("ex1.txt")).unwrap();
+        assert_eq!(find_tower_height(&input, 2022), Ok(3068));
     }
 
     #[test]
     fn big_example_works() {
-        let input = include_str!("ex2.txt").to_string();
-        assert_eq!(find_tower_height(input, 2022), 3127);
+        let input = super::super::parse_input(include_str!("ex2.txt")).unwrap();
+        assert_eq!(find_tower_height(&input, 2022), Ok(3127));
     }
 }
@@ -3,6 +3,10 @@ use std::{
     fmt::Display,
 };
 
+use crate::{days::SolveError, log::trace};
+
+use super::WindDirection;
+
 struct RepeatingSequence<T> {
     items: Vec<T>,
     index: usize,
@@ -75,22 +79,6 @@ impl Shape {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-enum WindDirection {
-    Left,
-    Right,
-}
-
-fn parse_wind_list(line: String) -> Result<Vec<WindDirection>, String> {
-    line.chars()
-        .map(|c| match c {
-            '<' => Ok(WindDirection::Left),
-            '>' => Ok(WindDirection::Right),
-            _ => Err(format!("invalid wind direction: {}", c)),
-        })
-        .collect::<Result<Vec<_>, _>>()
-}
-
 type CacheKey = ([usize; 7], usize, usize);
 
 struct BoardCacheValue {
@@ -198,7 +186,12 @@ impl Board {
         }
 
         if let Some(key) = cache_key {
-            //println!("{:?}, {}, {}", key, self.rows.len(), self.shape_count);
+            trace!(
+                "cached {:?} at height {} after {} shapes",
+                key,
+                self.rows.len(),
+                self.shape_count
+            );
             // it has not modded yet
             self.cache.insert(
                 key,
@@ -261,12 +254,8 @@ impl Display for Board {
     }
 }
 
-pub fn read_input(input: &str) -> String {
-    input.lines().next().unwrap().to_string()
-}
-
-pub fn find_tower_height(input: String, iterations: u64) -> u64 {
-    let wind = RepeatingSequence::new(parse_wind_list(input).unwrap());
+pub fn find_tower_height(wind_list: &[WindDirection], iterations: u64) -> Result<u64, SolveError> {
+    let wind = RepeatingSequence::new(wind_list.to_vec());
 
     let mut shapes = RepeatingSequence::new(vec![
         Shape::Dash,
@@ -291,13 +280,13 @@ pub fn find_tower_height(input: String, iterations: u64) -> u64 {
         iteration += shapes_added;
     }
 
-    board.tower_height()
+    Ok(board.tower_height())
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        find_tower_height, parse_wind_list, Board, RepeatingSequence, Shape, WindDirection,
+        find_tower_height, Board, RepeatingSequence, Shape, WindDirection,
     };
 
     fn make_left_wind() -> RepeatingSequence<WindDirection> {
@@ -327,15 +316,6 @@ mod tests {
         assert_eq!(seq.next(), Some(1));
     }
 
-    #[test]
-    fn parse_wind_list_works() {
-        use super::WindDirection::*;
-        assert_eq!(
-            parse_wind_list("<<<>><".to_string()),
-            Ok(vec![Left, Left, Left, Right, Right, Left])
-        )
-    }
-
     #[test]
     fn an_empty_board_has_height_0() {
         let board = Board::new(make_left_wind());
@@ -395,13 +375,13 @@ mod tests {
 
     #[test]
     fn example_works() {
-        let input = include_str!("ex1.txt").to_string();
-        assert_eq!(find_tower_height(input, 1000000000000), 1514285714288);
+        let input = super::super::parse_input(include_str!("ex1.txt")).unwrap();
+        assert_eq!(find_tower_height(&input, 1000000000000), Ok(1514285714288));
     }
 
     #[test]
     fn big_example_works() {
-        let input = include_str!("ex2.txt").to_string();
-        assert_eq!(find_tower_height(input, 1000000000000), 1542941176480);
+        let input = super::super::parse_input(include_str!("ex2.txt")).unwrap();
+        assert_eq!(find_tower_height(&input, 1000000000000), Ok(1542941176480));
     }
 }
@@ -1,16 +1,120 @@
+use super::{Part, SolveError};
+use crate::{
+    grid::Point,
+    render::{Frame, Image, Recorder, Rgb},
+};
+
 mod a;
 mod b;
 
 pub struct Day;
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WindDirection {
+    Left,
+    Right,
+}
+
+fn parse_wind_list(line: String) -> Result<Vec<WindDirection>, String> {
+    line.chars()
+        .map(|c| match c {
+            '<' => Ok(WindDirection::Left),
+            '>' => Ok(WindDirection::Right),
+            _ => Err(format!("invalid wind direction: {}", c)),
+        })
+        .collect::<Result<Vec<_>, _>>()
+}
+
+/// The jet pattern, which is the first line of the input
+fn parse_input(input: &str) -> Result<Vec<WindDirection>, SolveError> {
+    let line = input.lines().next().unwrap_or("");
+    let col = line.find(|c| c != '<' && c != '>').unwrap_or(0);
+    let wind_list = parse_wind_list(line.to_string())
+        .map_err(|message| SolveError::parse(1, col + 1, message))?;
+    if wind_list.is_empty() {
+        return Err(SolveError::unsupported("the jet pattern is empty"));
+    }
+    Ok(wind_list)
+}
+
+/// The rocks in grey
+fn picture(board: &a::Board) -> Image {
+    board
+        .tower()
+        .map(|&rock| if rock { Rgb::GREY } else { Rgb::BLACK })
+}
+
+/// The frames grow upwards from the floor of the chamber, which is at 0
+fn frame(board: &a::Board) -> Frame {
+    let image = picture(board);
+    Frame::new(Point::new(0, -(image.height() as i32)), image)
+}
+
+fn too_tall() -> SolveError {
+    SolveError::unsupported("the tower of the advanced part is too tall to draw")
+}
+
+/// The tower of the first part, as the second is much too tall to draw
+pub fn render(input: &str, part: Part) -> Result<Image, SolveError> {
+    let wind_list = parse_input(input)?;
+    match part {
+        Part::Basic => Ok(picture(&a::stack(&wind_list, 2022, |_| {}))),
+        Part::Advanced => Err(too_tall()),
+    }
+}
+
+/// The tower of the first part whenever a rock comes to rest
+pub fn animate(input: &str, part: Part, recorder: &mut Recorder) -> Result<(), SolveError> {
+    let wind_list = parse_input(input)?;
+    if part == Part::Advanced {
+        return Err(too_tall());
+    }
+    let board = a::stack(&wind_list, 2022, |board| recorder.step(|| frame(board)));
+    recorder.end(|| frame(&board));
+    Ok(())
+}
+
 impl super::Runner for Day {
+    type Parsed<'a> = Vec<WindDirection>;
     type T = u64;
 
-    fn run(input: &str, basic: bool) -> Self::T {
-        if basic {
-            a::find_tower_height(a::read_input(input), 2022) as u64
-        } else {
-            b::find_tower_height(b::read_input(input), 1000000000000)
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(wind_list: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        a::find_tower_height(wind_list, 2022).map(|h| h as u64)
+    }
+
+    fn part2(wind_list: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        b::find_tower_height(wind_list, 1000000000000)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_wind_list_works() {
+        use super::WindDirection::*;
+        assert_eq!(
+            parse_wind_list("<<<>><".to_string()),
+            Ok(vec![Left, Left, Left, Right, Right, Left])
+        )
+    }
+
+    #[test]
+    fn the_frames_stand_on_the_floor() {
+        let mut recorder = Recorder::new(1000, 10);
+        animate(include_str!("ex1.txt"), Part::Basic, &mut recorder).unwrap();
+        // the empty chamber, the rocks 1000 and 2000, and the last one
+        assert_eq!(recorder.steps(), 2023);
+        let frames = recorder.frames();
+        assert_eq!(frames.len(), 4);
+        for frame in frames {
+            assert_eq!(frame.origin.y + frame.image.height() as i32, 0);
         }
+        assert_eq!(frames[3].image.height(), 3068);
     }
 }
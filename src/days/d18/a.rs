@@ -1,35 +1,8 @@
 use std::collections::HashSet;
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-struct Point {
-    x: i32,
-    y: i32,
-    z: i32,
-}
+use crate::days::SolveError;
 
-impl Point {
-    fn neighbors(self) -> Vec<Point> {
-        [-1, 1]
-            .iter()
-            .flat_map(|d| {
-                [
-                    Point {
-                        x: self.x + d,
-                        ..self
-                    },
-                    Point {
-                        y: self.y + d,
-                        ..self
-                    },
-                    Point {
-                        z: self.z + d,
-                        ..self
-                    },
-                ]
-            })
-            .collect()
-    }
-}
+use super::Point;
 
 struct Model {
     pixels: HashSet<Point>,
@@ -37,9 +10,9 @@ struct Model {
 
 impl Model {
     fn from_pixels(pixels: &[Point]) -> Model {
-        return Model {
+        Model {
             pixels: HashSet::from_iter(pixels.iter().copied()),
-        };
+        }
     }
 
     fn neighbor_count(&self, p: Point) -> u32 {
@@ -54,89 +27,28 @@ impl Model {
     }
 }
 
-pub fn main(input: &str) -> u32 {
-    let mut lines = Vec::new();
-
-    for line in input.lines() {
-        if line.is_empty() {
-            break;
-        }
-
-        lines.push(line.to_string());
-    }
-
-    run_program(lines)
-}
-
-fn run_program(lines: Vec<String>) -> u32 {
-    let pixels = parse_input(lines);
-
-    let model = Model::from_pixels(&pixels);
+pub fn run_program(pixels: &[Point]) -> Result<u32, SolveError> {
+    let model = Model::from_pixels(pixels);
 
-    model.calculate_surface_area()
-}
-
-fn parse_input(lines: Vec<String>) -> Vec<Point> {
-    let mut points = Vec::new();
-
-    for line in lines {
-        let coords: Vec<i32> = line.split(',').map(|s| s.parse::<i32>().unwrap()).collect();
-        points.push(Point {
-            x: coords[0],
-            y: coords[1],
-            z: coords[2],
-        });
-    }
-
-    points
+    Ok(model.calculate_surface_area())
 }
 
 #[cfg(test)]
 mod tests {
-    use std::vec;
-
-    use super::{parse_input, run_program, Point};
-
-    #[test]
-    fn parse_input_should_work_with_one_line() {
-        let lines = vec!["1,2,3".to_owned()];
-        assert_eq!(parse_input(lines), vec![Point { x: 1, y: 2, z: 3 }]);
-    }
-
-    #[test]
-    fn parse_input_should_work_with_multiple_lines() {
-        let lines = vec![
-            "1,2,3".to_owned(),
-            "1,200,3".to_owned(),
-            "1,2,-3".to_owned(),
-        ];
-        assert_eq!(
-            parse_input(lines),
-            vec![
-                Point { x: 1, y: 2, z: 3 },
-                Point { x: 1, y: 200, z: 3 },
-                Point { x: 1, y: 2, z: -3 }
-            ]
-        );
-    }
+    use super::super::parse_input;
+    use super::run_program;
 
     #[test]
     fn example_works() {
-        let lines = include_str!("ex1.txt")
-            .lines()
-            .map(|s| s.to_string())
-            .collect();
+        let pixels = parse_input(include_str!("ex1.txt")).unwrap();
 
-        assert_eq!(run_program(lines), 64);
+        assert_eq!(run_program(&pixels), Ok(64));
     }
 
     #[test]
     fn big_example_works() {
-        let lines = include_str!("ex2.txt")
-            .lines()
-            .map(|s| s.to_string())
-            .collect();
+        let pixels = parse_input(include_str!("ex2.txt")).unwrap();
 
-        assert_eq!(run_program(lines), 3564);
+        assert_eq!(run_program(&pixels), Ok(3564));
     }
 }
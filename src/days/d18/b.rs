@@ -1,37 +1,8 @@
 use std::collections::HashSet;
 
-use queues::{IsQueue, Queue};
+use crate::{days::SolveError, search::Search};
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-struct Point {
-    x: i32,
-    y: i32,
-    z: i32,
-}
-
-impl Point {
-    fn neighbors(self) -> Vec<Point> {
-        [-1, 1]
-            .iter()
-            .flat_map(|d| {
-                [
-                    Point {
-                        x: self.x + d,
-                        ..self
-                    },
-                    Point {
-                        y: self.y + d,
-                        ..self
-                    },
-                    Point {
-                        z: self.z + d,
-                        ..self
-                    },
-                ]
-            })
-            .collect()
-    }
-}
+use super::Point;
 
 struct Model {
     pixels: HashSet<Point>,
@@ -40,9 +11,10 @@ struct Model {
 
 impl Model {
     fn from_pixels(pixels: &[Point]) -> Model {
+        let pixels = HashSet::from_iter(pixels.iter().copied());
         Model {
-            pixels: HashSet::from_iter(pixels.iter().copied()),
-            exterior: Model::calculate_exterior(pixels),
+            exterior: Model::calculate_exterior(&pixels),
+            pixels,
         }
     }
 
@@ -53,7 +25,11 @@ impl Model {
             .count() as u32
     }
 
-    fn calculate_exterior(pixels: &[Point]) -> HashSet<Point> {
+    /// The air around the droplet, out to one past its sides, that water can flow through
+    fn calculate_exterior(pixels: &HashSet<Point>) -> HashSet<Point> {
+        if pixels.is_empty() {
+            return HashSet::new();
+        }
         let xlo = pixels.iter().map(|p| p.x).min().unwrap() - 1;
         let ylo = pixels.iter().map(|p| p.y).min().unwrap() - 1;
         let zlo = pixels.iter().map(|p| p.z).min().unwrap() - 1;
@@ -61,9 +37,7 @@ impl Model {
         let yhi = pixels.iter().map(|p| p.y).max().unwrap() + 1;
         let zhi = pixels.iter().map(|p| p.z).max().unwrap() + 1;
 
-        let mut exterior = HashSet::<Point>::new();
-        let mut q: Queue<Point> = Queue::new();
-        for (x, y, z) in [
+        let corners = [
             (xlo, ylo, zlo),
             (xhi, ylo, zlo),
             (xlo, yhi, zlo),
@@ -72,23 +46,19 @@ impl Model {
             (xhi, ylo, zhi),
             (xlo, yhi, zhi),
             (xhi, yhi, zhi),
-        ] {
-            let p = Point { x, y, z };
-            exterior.insert(p);
-            q.add(p).unwrap();
-        }
-
-        while let Ok(p) = q.remove() {
-            for n in p.neighbors() {
-                if n.x < xlo || n.x > xhi || n.y < ylo || n.y > yhi || n.z < zlo || n.z > zhi {
-                    continue;
-                }
-                if !pixels.contains(&n) && !exterior.contains(&n) {
-                    exterior.insert(n);
-                    q.add(n).unwrap();
-                }
-            }
-        }
+        ]
+        .map(|(x, y, z)| Point { x, y, z });
+
+        let is_inside_the_box = |n: &Point| {
+            (xlo..=xhi).contains(&n.x) && (ylo..=yhi).contains(&n.y) && (zlo..=zhi).contains(&n.z)
+        };
+        let (exterior, _) = Search::new(HashSet::new())
+            .flood_fill(corners, |p| {
+                p.neighbors()
+                    .into_iter()
+                    .filter(|n| is_inside_the_box(n) && !pixels.contains(n))
+            })
+            .expect("the flood fill is not cancelled");
 
         exterior
     }
@@ -98,89 +68,28 @@ impl Model {
     }
 }
 
-pub fn main(input: &str) -> u32 {
-    let mut lines = Vec::new();
-
-    for line in input.lines() {
-        if line.is_empty() {
-            break;
-        }
-
-        lines.push(line.to_string());
-    }
+pub fn run_program(pixels: &[Point]) -> Result<u32, SolveError> {
+    let model = Model::from_pixels(pixels);
 
-    run_program(lines)
-}
-
-fn run_program(lines: Vec<String>) -> u32 {
-    let pixels = parse_input(lines);
-
-    let model = Model::from_pixels(&pixels);
-
-    model.calculate_surface_area()
-}
-
-fn parse_input(lines: Vec<String>) -> Vec<Point> {
-    let mut points = Vec::new();
-
-    for line in lines {
-        let coords: Vec<i32> = line.split(',').map(|s| s.parse::<i32>().unwrap()).collect();
-        points.push(Point {
-            x: coords[0],
-            y: coords[1],
-            z: coords[2],
-        });
-    }
-
-    points
+    Ok(model.calculate_surface_area())
 }
 
 #[cfg(test)]
 mod tests {
-    use std::vec;
-
-    use super::{parse_input, run_program, Point};
-
-    #[test]
-    fn parse_input_should_work_with_one_line() {
-        let lines = vec!["1,2,3".to_owned()];
-        assert_eq!(parse_input(lines), vec![Point { x: 1, y: 2, z: 3 }]);
-    }
-
-    #[test]
-    fn parse_input_should_work_with_multiple_lines() {
-        let lines = vec![
-            "1,2,3".to_owned(),
-            "1,200,3".to_owned(),
-            "1,2,-3".to_owned(),
-        ];
-        assert_eq!(
-            parse_input(lines),
-            vec![
-                Point { x: 1, y: 2, z: 3 },
-                Point { x: 1, y: 200, z: 3 },
-                Point { x: 1, y: 2, z: -3 }
-            ]
-        );
-    }
+    use super::super::parse_input;
+    use super::run_program;
 
     #[test]
     fn example_works() {
-        let lines = include_str!("ex1.txt")
-            .lines()
-            .map(|s| s.to_string())
-            .collect();
+        let pixels = parse_input(include_str!("ex1.txt")).unwrap();
 
-        assert_eq!(run_program(lines), 58);
+        assert_eq!(run_program(&pixels), Ok(58));
     }
 
     #[test]
     fn big_example_works() {
-        let lines = include_str!("ex2.txt")
-            .lines()
-            .map(|s| s.to_string())
-            .collect();
+        let pixels = parse_input(include_str!("ex2.txt")).unwrap();
 
-        assert_eq!(run_program(lines), 2106);
+        assert_eq!(run_program(&pixels), Ok(2106));
     }
 }
@@ -1,16 +1,114 @@
+use super::SolveError;
+
 mod a;
 mod b;
 
 pub struct Day;
 
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Point {
+    x: i32,
+    y: i32,
+    z: i32,
+}
+
+impl Point {
+    fn neighbors(self) -> Vec<Point> {
+        [-1, 1]
+            .iter()
+            .flat_map(|d| {
+                [
+                    Point {
+                        x: self.x + d,
+                        ..self
+                    },
+                    Point {
+                        y: self.y + d,
+                        ..self
+                    },
+                    Point {
+                        z: self.z + d,
+                        ..self
+                    },
+                ]
+            })
+            .collect()
+    }
+}
+
+fn parse_input(input: &str) -> Result<Vec<Point>, SolveError> {
+    let mut points = Vec::new();
+
+    for (idx, line) in input.lines().enumerate() {
+        if line.is_empty() {
+            break;
+        }
+
+        let mut coords: Vec<i32> = Vec::new();
+        let mut col = 0;
+        for s in line.split(',') {
+            let coord = s.parse::<i32>().map_err(|_| {
+                SolveError::parse(idx + 1, col + 1, format!("invalid coordinate: {}", s))
+            })?;
+            coords.push(coord);
+            col += s.len() + 1;
+        }
+        let [x, y, z] = coords[..] else {
+            return Err(SolveError::parse(idx + 1, 1, "expected three coordinates"));
+        };
+        points.push(Point { x, y, z });
+    }
+
+    Ok(points)
+}
+
 impl super::Runner for Day {
+    type Parsed<'a> = Vec<Point>;
     type T = u32;
 
-    fn run(input: &str, basic: bool) -> Self::T {
-        if basic {
-            a::main(input)
-        } else {
-            b::main(input)
-        }
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(pixels: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        a::run_program(pixels)
+    }
+
+    fn part2(pixels: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        b::run_program(pixels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_input, Point, SolveError};
+
+    #[test]
+    fn parse_input_should_work_with_one_line() {
+        assert_eq!(parse_input("1,2,3"), Ok(vec![Point { x: 1, y: 2, z: 3 }]));
+    }
+
+    #[test]
+    fn parse_input_should_work_with_multiple_lines() {
+        assert_eq!(
+            parse_input("1,2,3\n1,200,3\n1,2,-3"),
+            Ok(vec![
+                Point { x: 1, y: 2, z: 3 },
+                Point { x: 1, y: 200, z: 3 },
+                Point { x: 1, y: 2, z: -3 }
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_input_reports_invalid_coordinates() {
+        assert_eq!(
+            parse_input("1,2,3\n1,x,3"),
+            Err(SolveError::parse(2, 3, "invalid coordinate: x"))
+        );
+        assert_eq!(
+            parse_input("1,2"),
+            Err(SolveError::parse(1, 1, "expected three coordinates"))
+        );
     }
 }
@@ -1,8 +1,13 @@
-use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 
-use blueprint::Blueprint;
+pub use blueprint::Blueprint;
+use parser::BlueprintParseError;
+use rayon::prelude::*;
 use resource::{Resource, ResourceSlice};
-use simulation::SimulationState;
+use simulation::{Search, SimulationState};
+
+use super::{CancelToken, NoProgress, Progress, Runner, SolveError};
+use crate::log::debug;
 
 mod blueprint;
 mod parser;
@@ -11,112 +16,308 @@ mod simulation;
 
 pub struct Day;
 
-impl super::Runner for Day {
+impl Runner for Day {
+    type Parsed<'a> = Vec<Blueprint>;
     type T = u32;
-    
-    fn run(input: &str, basic: bool) -> Self::T {
-        let lines: Vec<_> = input.lines().collect();
-        run_program(&lines, if basic { 24 } else { 32 }, !basic)
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(blueprints: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        Self::part1_with(blueprints, &CancelToken::new(), &NoProgress)
+    }
+
+    fn part2(blueprints: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        Self::part2_with(blueprints, &CancelToken::new(), &NoProgress)
+    }
+
+    fn part1_with(
+        blueprints: &Self::Parsed<'_>,
+        cancel: &CancelToken,
+        progress: &dyn Progress,
+    ) -> Result<Self::T, SolveError> {
+        run_program(blueprints, 24, true, cancel, progress)
+    }
+
+    fn part2_with(
+        blueprints: &Self::Parsed<'_>,
+        cancel: &CancelToken,
+        progress: &dyn Progress,
+    ) -> Result<Self::T, SolveError> {
+        run_program(blueprints, 32, false, cancel, progress)
     }
 }
 
 const MAX_DEPTH: usize = 32;
 
-pub fn run_program(lines: &[&str], steps: u8, perform_sum: bool) -> u32 {
+impl BlueprintParseError {
+    fn into_solve_error(self, lines: &[&str]) -> SolveError {
+        match self {
+            BlueprintParseError::MissingRecipe {
+                blueprint,
+                resource,
+            } => {
+                let header = format!("Blueprint {}:", blueprint);
+                let line = lines.iter().position(|l| l.contains(&header)).unwrap_or(0);
+                SolveError::parse(
+                    line + 1,
+                    1,
+                    format!(
+                        "blueprint {} has no valid recipe for the {:?} robot",
+                        blueprint, resource
+                    ),
+                )
+            }
+        }
+    }
+}
+
+pub fn parse_input(input: &str) -> Result<Vec<Blueprint>, SolveError> {
+    let lines: Vec<_> = input.lines().collect();
+    parser::parse_blueprints(&lines).map_err(|e| e.into_solve_error(&lines))
+}
+
+fn max_geodes(blueprint: &Blueprint, steps: u8, search: &mut Search) -> u8 {
+    let mut next_states: [_; MAX_DEPTH] = Default::default();
+    simulation::simulate(
+        blueprint,
+        SimulationState {
+            resources: ResourceSlice::new(),
+            robots: ResourceSlice::new().with(Resource::Ore, 1),
+            steps_left: steps,
+        },
+        search,
+        &mut next_states,
+        0,
+    )
+}
+
+/// Sums the quality levels of all blueprints, or multiplies the geodes of the first three.
+///
+/// The blueprints are simulated in parallel, each with its own cache. When a sum is cancelled,
+/// the partial answer counts the geodes found so far, and none for the blueprints that were not
+/// simulated yet. A cancelled product has no partial answer, as a single unfinished blueprint
+/// can make it anything.
+pub fn run_program(
+    blueprints: &[Blueprint],
+    steps: u8,
+    perform_sum: bool,
+    cancel: &CancelToken,
+    progress: &dyn Progress,
+) -> Result<u32, SolveError> {
     assert!(
         steps as usize <= MAX_DEPTH,
         "steps may not be more than {}",
         MAX_DEPTH
     );
 
-    let blueprints: Vec<Blueprint> = parser::parse_blueprints(lines).unwrap();
+    let blueprints = if perform_sum {
+        blueprints
+    } else {
+        &blueprints[..blueprints.len().min(3)]
+    };
 
-    // TODO: use rayon thread pool for the blueprints
+    let done = AtomicUsize::new(0);
+    let states_explored = AtomicU64::new(0);
+    progress.items_done(0, blueprints.len());
+    let geodes: Vec<u32> = blueprints
+        .par_iter()
+        .map(|b| {
+            let mut search = Search::new(cancel, progress, &states_explored);
+            let geodes = max_geodes(b, steps, &mut search);
+            debug!(
+                "blueprint {}: {} geodes after exploring {} states",
+                b.id, geodes, search.states_explored
+            );
+            if !cancel.is_cancelled() {
+                let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+                progress.items_done(done, blueprints.len());
+            }
+            geodes as u32
+        })
+        .collect();
 
     let result = if perform_sum {
         blueprints
             .iter()
-            .map(|b| {
-                let mut cache = HashMap::new();
-                let mut next_states: [_; MAX_DEPTH] = Default::default();
-                let quality = simulation::simulate(
-                    b,
-                    SimulationState {
-                        resources: ResourceSlice::new(),
-                        robots: ResourceSlice::new().with(Resource::Ore, 1),
-                        steps_left: steps,
-                    },
-                    &mut cache,
-                    &mut next_states,
-                    0,
-                );
-                println!("{}: {}", b.id, quality);
-                quality as u32 * b.id as u32
-            })
+            .zip(&geodes)
+            .map(|(b, geodes)| geodes * b.id as u32)
             .sum()
     } else {
-        blueprints
-            .iter()
-            .take(3)
-            .map(|b| {
-                let mut cache = HashMap::new();
-                let mut next_states: [_; MAX_DEPTH] = Default::default();
-                let quality = simulation::simulate(
-                    b,
-                    SimulationState {
-                        resources: ResourceSlice::new(),
-                        robots: ResourceSlice::new().with(Resource::Ore, 1),
-                        steps_left: steps,
-                    },
-                    &mut cache,
-                    &mut next_states,
-                    0,
-                );
-                println!("{}: {}", b.id, quality);
-                quality as u32
-            })
-            .product()
+        geodes.iter().product()
     };
 
-    result
+    if cancel.is_cancelled() {
+        let partial = if perform_sum { Some(result) } else { None };
+        return Err(SolveError::timed_out(partial));
+    }
+    Ok(result)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::run_program;
+    use std::collections::HashMap;
+    use std::sync::atomic::AtomicU64;
+    use std::sync::Mutex;
+
+    use super::blueprint::Recipe;
+    use super::{
+        max_geodes, parse_input, run_program, Blueprint, CancelToken, NoProgress, Progress,
+        Resource, ResourceSlice, Search,
+    };
+    use crate::gen::Rng;
 
     #[test]
     fn example_works_part_1() {
-        let lines = small_example();
+        let blueprints = small_example();
 
-        assert_eq!(run_program(&lines, 24, true), 33);
+        assert_eq!(
+            run_program(&blueprints, 24, true, &CancelToken::new(), &NoProgress),
+            Ok(33)
+        );
     }
 
     #[test]
     #[ignore]
     fn big_example_works_part_1() {
-        let lines = big_example();
+        let blueprints = big_example();
 
-        assert_eq!(run_program(&lines, 24, true), 1725);
+        assert_eq!(
+            run_program(&blueprints, 24, true, &CancelToken::new(), &NoProgress),
+            Ok(1725)
+        );
     }
 
     #[test]
     #[ignore]
     fn big_example_works_part_2() {
-        let lines = big_example();
+        let blueprints = big_example();
+
+        assert_eq!(
+            run_program(&blueprints, 32, false, &CancelToken::new(), &NoProgress),
+            Ok(15510)
+        );
+    }
+
+    #[test]
+    fn a_cancelled_run_reports_the_answer_so_far() {
+        let blueprints = small_example();
+        let cancel = CancelToken::new();
+        cancel.cancel();
+
+        assert_eq!(
+            run_program(&blueprints, 24, true, &cancel, &NoProgress),
+            Err(super::SolveError::timed_out(Some(0)))
+        );
+    }
+
+    #[test]
+    fn a_cancelled_product_has_no_partial_answer() {
+        let blueprints = small_example();
+        let cancel = CancelToken::new();
+        cancel.cancel();
 
-        assert_eq!(run_program(&lines, 32, false), 15510);
+        assert_eq!(
+            run_program(&blueprints, 32, false, &cancel, &NoProgress),
+            Err(super::SolveError::timed_out(None::<u32>))
+        );
+    }
+
+    #[test]
+    fn progress_is_reported_for_every_blueprint() {
+        struct Recorder(Mutex<Vec<(usize, usize)>>);
+
+        impl Progress for Recorder {
+            fn items_done(&self, done: usize, total: usize) {
+                self.0.lock().unwrap().push((done, total));
+            }
+        }
+
+        let blueprints = small_example();
+        let recorder = Recorder(Mutex::new(Vec::new()));
+
+        assert_eq!(
+            run_program(&blueprints, 4, true, &CancelToken::new(), &recorder),
+            Ok(0)
+        );
+        let mut updates = recorder.0.lock().unwrap().clone();
+        updates.sort();
+        assert_eq!(updates, vec![(0, 2), (1, 2), (2, 2)]);
+    }
+
+    type State = (ResourceSlice<u8>, ResourceSlice<u8>, u8);
+
+    /// Tries building each robot, or none, in every minute, without pruning anything
+    fn most_geodes(blueprint: &Blueprint, memo: &mut HashMap<State, u8>, state: State) -> u8 {
+        let (resources, robots, steps_left) = state;
+        if steps_left == 0 {
+            return resources[Resource::Geode];
+        }
+        if let Some(&geodes) = memo.get(&state) {
+            return geodes;
+        }
+        let mut geodes = most_geodes(
+            blueprint,
+            memo,
+            (resources + robots, robots, steps_left - 1),
+        );
+        for robot in Resource::all() {
+            let recipe = blueprint.recipes[robot];
+            if recipe.affordable(resources) {
+                let built = robots.with(robot, robots[robot] + 1);
+                let after = (resources - recipe.input + robots, built, steps_left - 1);
+                geodes = geodes.max(most_geodes(blueprint, memo, after));
+            }
+        }
+        memo.insert(state, geodes);
+        geodes
+    }
+
+    /// A blueprint with robots cheap enough to crack geodes within a few minutes
+    fn cheap_blueprint(rng: &mut Rng, id: u8) -> Blueprint {
+        let mut costs = [0; 6];
+        costs.iter_mut().for_each(|c| *c = rng.range(1, 3) as u8);
+        Blueprint {
+            id,
+            recipes: ResourceSlice::populate(|r| match r {
+                Resource::Ore => Recipe::new(costs[0], 0, 0),
+                Resource::Clay => Recipe::new(costs[1], 0, 0),
+                Resource::Obsidian => Recipe::new(costs[2], costs[3], 0),
+                Resource::Geode => Recipe::new(costs[4], 0, costs[5]),
+            }),
+        }
+    }
+
+    #[test]
+    fn the_search_agrees_with_trying_every_robot() {
+        // few enough minutes that trying everything stays quick in debug builds
+        const STEPS: u8 = 12;
+        let mut rng = Rng::new(19);
+        let cancel = CancelToken::new();
+        let states_explored = AtomicU64::new(0);
+        for id in 1..=10 {
+            let blueprint = cheap_blueprint(&mut rng, id);
+            let start = (
+                ResourceSlice::new(),
+                ResourceSlice::new().with(Resource::Ore, 1),
+                STEPS,
+            );
+            let mut search = Search::new(&cancel, &NoProgress, &states_explored);
+            assert_eq!(
+                max_geodes(&blueprint, STEPS, &mut search),
+                most_geodes(&blueprint, &mut HashMap::new(), start),
+                "{:?}",
+                blueprint
+            );
+        }
     }
 
-    fn small_example() -> Vec<&'static str> {
-        include_str!("ex1.txt")
-            .split('\n')
-            .collect()
+    fn small_example() -> Vec<Blueprint> {
+        parse_input(include_str!("ex1.txt")).unwrap()
     }
 
-    fn big_example() -> Vec<&'static str> {
-        include_str!("ex2.txt")
-            .split('\n')
-            .collect()
+    fn big_example() -> Vec<Blueprint> {
+        parse_input(include_str!("ex2.txt")).unwrap()
     }
 }
@@ -5,7 +5,7 @@ use super::{
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum BlueprintParseError {
-    MissingRecipe(Resource),
+    MissingRecipe { blueprint: u8, resource: Resource },
 }
 
 pub fn parse_blueprints(lines: &[&str]) -> Result<Vec<Blueprint>, BlueprintParseError> {
@@ -22,15 +22,19 @@ pub fn parse_blueprints(lines: &[&str]) -> Result<Vec<Blueprint>, BlueprintParse
         if pieces.len() < 4 {
             continue;
         }
-        use BlueprintParseError::*;
         if let Some((_, a)) = pieces[0].split_once(':') {
-            let ore = parse_recipe(a).ok_or(MissingRecipe(Resource::Ore))?;
-            let clay = parse_recipe(pieces[1]).ok_or(MissingRecipe(Resource::Clay))?;
-            let obsidian = parse_recipe(pieces[2]).ok_or(MissingRecipe(Resource::Obsidian))?;
-            let geode = parse_recipe(pieces[3]).ok_or(MissingRecipe(Resource::Geode))?;
+            let id = (blueprints.len() + 1) as u8;
+            let missing = |resource| BlueprintParseError::MissingRecipe {
+                blueprint: id,
+                resource,
+            };
+            let ore = parse_recipe(a).ok_or(missing(Resource::Ore))?;
+            let clay = parse_recipe(pieces[1]).ok_or(missing(Resource::Clay))?;
+            let obsidian = parse_recipe(pieces[2]).ok_or(missing(Resource::Obsidian))?;
+            let geode = parse_recipe(pieces[3]).ok_or(missing(Resource::Geode))?;
 
             blueprints.push(Blueprint {
-                id: (blueprints.len() + 1) as u8,
+                id,
                 recipes: ResourceSlice::populate(|r| match r {
                     Resource::Ore => ore,
                     Resource::Clay => clay,
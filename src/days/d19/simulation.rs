@@ -1,5 +1,11 @@
 use std::collections::HashMap;
 use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::days::{
+    progress::{Progress, STATES_PER_UPDATE},
+    CancelToken,
+};
 
 use super::{
     blueprint::Blueprint,
@@ -47,19 +53,55 @@ impl SimulationState {
 
 const CACHE_LIMIT: u8 = 4;
 
+/// The search for the best result of one blueprint, which may run next to the others
+pub struct Search<'a> {
+    pub cache: HashMap<SimulationState, u8>,
+    pub cancel: &'a CancelToken,
+    pub progress: &'a dyn Progress,
+    pub states_explored: u64,
+    /// The states explored by all the searches of the run
+    pub total_states_explored: &'a AtomicU64,
+}
+
+impl<'a> Search<'a> {
+    pub fn new(
+        cancel: &'a CancelToken,
+        progress: &'a dyn Progress,
+        total_states_explored: &'a AtomicU64,
+    ) -> Self {
+        Search {
+            cache: HashMap::new(),
+            cancel,
+            progress,
+            states_explored: 0,
+            total_states_explored,
+        }
+    }
+}
+
 pub fn simulate(
     blueprint: &Blueprint,
     state: SimulationState,
-    cache: &mut HashMap<SimulationState, u8>,
+    search: &mut Search,
     next_states: &mut [[SimulationState; 4]],
     best_so_far: u8,
 ) -> u8 {
+    search.states_explored += 1;
+    if search.states_explored.is_multiple_of(STATES_PER_UPDATE) {
+        let total = search
+            .total_states_explored
+            .fetch_add(STATES_PER_UPDATE, Ordering::Relaxed);
+        search.progress.states_explored(total + STATES_PER_UPDATE);
+    }
     if state.steps_left == 0 {
         return state.resources[Resource::Geode];
     }
+    if search.cancel.is_cancelled() {
+        return best_so_far;
+    }
 
     if state.steps_left > CACHE_LIMIT {
-        if let Some(&value) = cache.get(&state) {
+        if let Some(&value) = search.cache.get(&state) {
             return value;
         }
     }
@@ -78,7 +120,7 @@ pub fn simulate(
             let result = simulate(
                 blueprint,
                 new_state.clone(),
-                cache,
+                search,
                 next_states,
                 best_result,
             );
@@ -93,8 +135,9 @@ pub fn simulate(
     // maybe start building a new robot, and queue one robot of production
     // for each item in the queue add it to the stock
 
-    if state.steps_left > CACHE_LIMIT {
-        cache.insert(state.clone(), best_result);
+    // a cancelled search may have missed better results, so it must not be cached
+    if state.steps_left > CACHE_LIMIT && !search.cancel.is_cancelled() {
+        search.cache.insert(state.clone(), best_result);
     }
 
     best_result
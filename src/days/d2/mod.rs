@@ -1,16 +1,64 @@
+use std::io::BufRead;
+
+use super::{
+    stream::{for_each_line, StreamError},
+    Part, SolveError,
+};
+
 mod a;
 mod b;
+mod rules;
+mod strategy;
+
+pub use rules::{Column, Game, Outcome, Piece, Rules, Scoring, Symbols};
+pub use strategy::{
+    best_response, counter_strategy, equilibrium, expected_score, expected_score_against,
+    CounterStrategy, Equilibrium, Mixed,
+};
 
 pub struct Day;
 
+fn parse_input(input: &str, rules: &Rules) -> Result<Vec<(Piece, Column)>, SolveError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, l)| rules.parse_line(idx, l))
+        .collect()
+}
+
+/// The total score of a strategy guide written and played with other rules than the puzzle's
+pub fn total_score(input: &str, rules: &Rules, part: Part) -> Result<i32, SolveError> {
+    let rounds = parse_input(input, rules)?;
+    Ok(match part {
+        Part::Basic => a::main(rules, &rounds),
+        Part::Advanced => b::main(rules, &rounds),
+    })
+}
+
+/// Solves a part one round at a time
+pub fn run_reader<R: BufRead>(reader: R, part: Part) -> Result<i32, StreamError> {
+    let rules = Rules::classic();
+    let mut total = 0;
+    for_each_line(reader, |idx, l| {
+        total += rules.round_score(rules.parse_line(idx, l)?, part);
+        Ok(())
+    })?;
+    Ok(total)
+}
+
 impl super::Runner for Day {
+    type Parsed<'a> = Vec<(Piece, Column)>;
     type T = i32;
 
-    fn run(input: &str, basic: bool) -> Self::T {
-        if basic {
-            a::main(input)
-        } else {
-            b::main(input)
-        }
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parse_input(input, &Rules::classic())
+    }
+
+    fn part1(rounds: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        Ok(a::main(&Rules::classic(), rounds))
+    }
+
+    fn part2(rounds: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        Ok(b::main(&Rules::classic(), rounds))
     }
 }
@@ -0,0 +1,476 @@
+//! The rules of rock paper scissors and of the games like it, where any two different weapons
+//! are one beating the other, like rock paper scissors lizard Spock.
+//!
+//! A [`Game`] tells which weapon beats which, a [`Scoring`] how many points a round is worth and
+//! [`Symbols`] how the strategy guide writes the weapons and the outcomes. [`Rules`] puts the
+//! three together, and reads and scores the rounds of a guide both ways the parts read it.
+
+use std::fmt::{self, Display};
+
+use crate::days::{Part, SolveError};
+
+/// A weapon of a game, numbered from 0 in the order of the game
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+pub struct Piece(pub usize);
+
+impl Piece {
+    pub const ROCK: Piece = Piece(0);
+    pub const PAPER: Piece = Piece(1);
+    pub const SCISSORS: Piece = Piece(2);
+}
+
+/// How a round ends for the player, in the order of [`Scoring::outcomes`]
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+impl Outcome {
+    pub const ALL: [Outcome; 3] = [Outcome::Lose, Outcome::Draw, Outcome::Win];
+}
+
+/// The second column of the strategy guide, as the index of its symbol in [`Symbols::second`],
+/// which the two parts read differently
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Column(pub usize);
+
+/// Which weapon beats which
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    names: Vec<String>,
+    /// Whether the first weapon beats the second one, by their numbers
+    beats: Vec<Vec<bool>>,
+}
+
+impl Game {
+    /// A game where each pair of `beats` is a weapon beating another one. Any two different
+    /// weapons must be one beating the other, and every weapon must both beat and lose to
+    /// another one, so that a round can always be won and lost.
+    pub fn new(names: &[&str], beats: &[(Piece, Piece)]) -> Result<Self, SolveError> {
+        let n = names.len();
+        let mut game = Game {
+            names: names.iter().map(|name| name.to_string()).collect(),
+            beats: vec![vec![false; n]; n],
+        };
+        for &(winner, loser) in beats {
+            if winner.0 >= n || loser.0 >= n || winner == loser {
+                return Err(SolveError::unsupported(format!(
+                    "{} cannot beat {} in a game of {} weapons",
+                    winner.0, loser.0, n
+                )));
+            }
+            game.beats[winner.0][loser.0] = true;
+        }
+
+        for a in game.pieces() {
+            for b in game.pieces().filter(|&b| b > a) {
+                if game.beats(a, b) == game.beats(b, a) {
+                    return Err(SolveError::unsupported(format!(
+                        "exactly one of {} and {} must beat the other",
+                        game.name(a),
+                        game.name(b)
+                    )));
+                }
+            }
+            let wins = game.pieces().filter(|&b| game.beats(a, b)).count();
+            if wins == 0 || wins == n - 1 {
+                return Err(SolveError::unsupported(format!(
+                    "{} must beat a weapon and lose to another one",
+                    game.name(a)
+                )));
+            }
+        }
+        Ok(game)
+    }
+
+    /// The game where each weapon beats the half of the others just before it, going round,
+    /// like paper beating rock which beats scissors which beats paper. There must be an odd
+    /// number of at least three weapons for the halves to be even.
+    pub fn cyclic(names: &[&str]) -> Result<Self, SolveError> {
+        let n = names.len();
+        if n < 3 || n.is_multiple_of(2) {
+            return Err(SolveError::unsupported(format!(
+                "a cyclic game has an odd number of at least three weapons, not {}",
+                n
+            )));
+        }
+        let beats: Vec<_> = (0..n)
+            .flat_map(|winner| (1..=n / 2).map(move |k| (winner, (winner + n - k) % n)))
+            .map(|(winner, loser)| (Piece(winner), Piece(loser)))
+            .collect();
+        Game::new(names, &beats)
+    }
+
+    pub fn rock_paper_scissors() -> Self {
+        Game::cyclic(&["rock", "paper", "scissors"]).expect("three weapons make a cyclic game")
+    }
+
+    /// Rock paper scissors lizard Spock, in the order that makes it cyclic
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        Game::cyclic(&["rock", "Spock", "paper", "lizard", "scissors"])
+            .expect("five weapons make a cyclic game")
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn pieces(&self) -> impl Iterator<Item = Piece> {
+        (0..self.len()).map(Piece)
+    }
+
+    pub fn name(&self, piece: Piece) -> &str {
+        &self.names[piece.0]
+    }
+
+    pub fn beats(&self, a: Piece, b: Piece) -> bool {
+        self.beats[a.0][b.0]
+    }
+
+    /// How a round ends for the player playing `me`
+    pub fn outcome(&self, me: Piece, opponent: Piece) -> Outcome {
+        if self.beats(me, opponent) {
+            Outcome::Win
+        } else if self.beats(opponent, me) {
+            Outcome::Lose
+        } else {
+            Outcome::Draw
+        }
+    }
+}
+
+/// The points of a round: those of the weapon played plus those of the outcome
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scoring {
+    /// The points of every weapon, by its number
+    pub shapes: Vec<i32>,
+    /// The points of losing, drawing and winning
+    pub outcomes: [i32; 3],
+}
+
+impl Scoring {
+    /// The scoring of the puzzle: 1 for the first weapon, 2 for the second one and so on, and
+    /// 0, 3 or 6 for losing, drawing or winning
+    pub fn standard(game: &Game) -> Self {
+        Scoring {
+            shapes: (1..=game.len() as i32).collect(),
+            outcomes: [0, 3, 6],
+        }
+    }
+
+    pub fn score(&self, me: Piece, outcome: Outcome) -> i32 {
+        self.shapes[me.0] + self.outcomes[outcome as usize]
+    }
+}
+
+/// How the strategy guide writes the rounds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbols {
+    /// The symbols of the first column, with the weapon of the opponent each stands for
+    pub opponent: Vec<(String, Piece)>,
+    /// The symbols of the second column, with the weapon part 1 reads and the outcome part 2
+    /// reads for each
+    pub second: Vec<(String, Piece, Outcome)>,
+}
+
+impl Symbols {
+    /// A, B and C for the opponent, and X, Y and Z for the player, in the order of the game
+    pub fn standard() -> Self {
+        let symbol = |s: &str| s.to_string();
+        Symbols {
+            opponent: vec![
+                (symbol("A"), Piece::ROCK),
+                (symbol("B"), Piece::PAPER),
+                (symbol("C"), Piece::SCISSORS),
+            ],
+            second: vec![
+                (symbol("X"), Piece::ROCK, Outcome::Lose),
+                (symbol("Y"), Piece::PAPER, Outcome::Draw),
+                (symbol("Z"), Piece::SCISSORS, Outcome::Win),
+            ],
+        }
+    }
+
+    pub fn shape(&self, column: Column) -> Piece {
+        self.second[column.0].1
+    }
+
+    pub fn outcome(&self, column: Column) -> Outcome {
+        self.second[column.0].2
+    }
+}
+
+/// Symbols listed like "A, B or C"
+struct Alternatives<'a>(Vec<&'a str>);
+
+impl Display for Alternatives<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0.split_last() {
+            Some((last, [])) => write!(f, "{}", last),
+            Some((last, others)) => write!(f, "{} or {}", others.join(", "), last),
+            None => write!(f, "nothing"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    game: Game,
+    scoring: Scoring,
+    symbols: Symbols,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules::classic()
+    }
+}
+
+impl Rules {
+    /// Checks that the scoring and the symbols are those of weapons of the game, and that no
+    /// symbol stands for two things
+    pub fn new(game: Game, scoring: Scoring, symbols: Symbols) -> Result<Self, SolveError> {
+        if scoring.shapes.len() != game.len() {
+            return Err(SolveError::unsupported(format!(
+                "the scoring has points for {} weapons but the game has {}",
+                scoring.shapes.len(),
+                game.len()
+            )));
+        }
+
+        let pieces = symbols.opponent.iter().map(|(s, piece)| (s, *piece));
+        let second = symbols.second.iter().map(|(s, piece, _)| (s, *piece));
+        for (column, mut symbols) in [(1, pieces.collect::<Vec<_>>()), (2, second.collect())] {
+            if let Some(&(s, piece)) = symbols.iter().find(|(_, piece)| piece.0 >= game.len()) {
+                return Err(SolveError::unsupported(format!(
+                    "{} stands for weapon {} but the game has {}",
+                    s,
+                    piece.0,
+                    game.len()
+                )));
+            }
+            if let Some((s, _)) = symbols.iter().find(|(s, _)| !is_symbol(s)) {
+                return Err(SolveError::unsupported(format!(
+                    "'{}' cannot be a symbol: it must be a word without spaces",
+                    s
+                )));
+            }
+            symbols.sort();
+            if let Some(pair) = symbols.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+                return Err(SolveError::unsupported(format!(
+                    "{} stands for two things in column {}",
+                    pair[0].0, column
+                )));
+            }
+        }
+
+        Ok(Rules {
+            game,
+            scoring,
+            symbols,
+        })
+    }
+
+    /// The rules of the puzzle: rock paper scissors with the standard scoring and symbols
+    pub fn classic() -> Self {
+        let game = Game::rock_paper_scissors();
+        let scoring = Scoring::standard(&game);
+        Rules::new(game, scoring, Symbols::standard()).expect("the classic rules are valid")
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn scoring(&self) -> &Scoring {
+        &self.scoring
+    }
+
+    pub fn symbols(&self) -> &Symbols {
+        &self.symbols
+    }
+
+    /// The points of the player playing `me`
+    pub fn score(&self, me: Piece, opponent: Piece) -> i32 {
+        self.scoring.score(me, self.game.outcome(me, opponent))
+    }
+
+    /// The weapon that ends a round the way the player needs, scoring the most points when there
+    /// are several, and the first of them when they score as much
+    pub fn response(&self, opponent: Piece, outcome: Outcome) -> Piece {
+        self.game
+            .pieces()
+            .filter(|&me| self.game.outcome(me, opponent) == outcome)
+            .min_by_key(|&me| (-self.scoring.shapes[me.0], me))
+            .expect("every weapon beats another one and loses to another one")
+    }
+
+    /// The points of a round of the guide, whose second column is the weapon to play for the
+    /// basic part and the outcome needed for the advanced one
+    pub fn round_score(&self, (opponent, column): (Piece, Column), part: Part) -> i32 {
+        let me = match part {
+            Part::Basic => self.symbols.shape(column),
+            Part::Advanced => self.response(opponent, self.symbols.outcome(column)),
+        };
+        self.score(me, opponent)
+    }
+
+    /// The round on a line of the strategy guide
+    pub fn parse_line(&self, idx: usize, l: &str) -> Result<(Piece, Column), SolveError> {
+        let parts: Vec<&str> = l.split_ascii_whitespace().collect();
+        let [first, second] = parts[..] else {
+            return Err(SolveError::parse(
+                idx + 1,
+                1,
+                "expected two symbols separated by a space",
+            ));
+        };
+
+        let Some(&(_, opponent)) = self.symbols.opponent.iter().find(|(s, _)| s == first) else {
+            let expected = self.symbols.opponent.iter().map(|(s, _)| s.as_str());
+            return Err(SolveError::parse(
+                idx + 1,
+                l.find(first).unwrap_or(0) + 1,
+                format!(
+                    "expected {}, found {}",
+                    Alternatives(expected.collect()),
+                    first
+                ),
+            ));
+        };
+
+        let Some(column) = self.symbols.second.iter().position(|(s, ..)| s == second) else {
+            let expected = self.symbols.second.iter().map(|(s, ..)| s.as_str());
+            return Err(SolveError::parse(
+                idx + 1,
+                l.rfind(second).unwrap_or(0) + 1,
+                format!(
+                    "expected {}, found {}",
+                    Alternatives(expected.collect()),
+                    second
+                ),
+            ));
+        };
+
+        Ok((opponent, Column(column)))
+    }
+}
+
+fn is_symbol(s: &str) -> bool {
+    !s.is_empty() && !s.contains(|c: char| c.is_whitespace())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(game: &Game, piece: Piece) -> Vec<&str> {
+        game.pieces()
+            .filter(|&other| game.beats(piece, other))
+            .map(|other| game.name(other))
+            .collect()
+    }
+
+    #[test]
+    fn cyclic_games_have_the_usual_rules() {
+        let rps = Game::rock_paper_scissors();
+        assert_eq!(names(&rps, Piece::ROCK), ["scissors"]);
+        assert_eq!(names(&rps, Piece::PAPER), ["rock"]);
+        assert_eq!(names(&rps, Piece::SCISSORS), ["paper"]);
+        assert_eq!(rps.outcome(Piece::ROCK, Piece::ROCK), Outcome::Draw);
+
+        let rpsls = Game::rock_paper_scissors_lizard_spock();
+        let piece = |name| rpsls.pieces().find(|&p| rpsls.name(p) == name).unwrap();
+        assert_eq!(names(&rpsls, piece("rock")), ["lizard", "scissors"]);
+        assert_eq!(names(&rpsls, piece("Spock")), ["rock", "scissors"]);
+        assert_eq!(names(&rpsls, piece("paper")), ["rock", "Spock"]);
+        assert_eq!(names(&rpsls, piece("lizard")), ["Spock", "paper"]);
+        assert_eq!(names(&rpsls, piece("scissors")), ["paper", "lizard"]);
+    }
+
+    #[test]
+    fn games_must_have_a_winner_for_every_pair() {
+        assert!(Game::cyclic(&["a", "b", "c", "d"]).is_err());
+        assert!(Game::cyclic(&["a"]).is_err());
+        let (a, b, c) = (Piece(0), Piece(1), Piece(2));
+        assert!(Game::new(&["a", "b", "c"], &[(a, b), (b, c)]).is_err());
+        assert!(Game::new(&["a", "b", "c"], &[(a, b), (b, c), (a, c)]).is_err());
+        assert!(Game::new(&["a", "b", "c"], &[(a, b), (b, c), (c, a)]).is_ok());
+        assert!(Game::new(&["a", "b"], &[(a, Piece(7))]).is_err());
+    }
+
+    #[test]
+    fn both_parts_read_the_guide_with_the_same_rules() {
+        let rules = Rules::classic();
+        let rounds: Vec<_> = ["A Y", "B X", "C Z"]
+            .iter()
+            .enumerate()
+            .map(|(idx, l)| rules.parse_line(idx, l).unwrap())
+            .collect();
+        let total = |part| -> i32 { rounds.iter().map(|&r| rules.round_score(r, part)).sum() };
+        assert_eq!(total(Part::Basic), 15);
+        assert_eq!(total(Part::Advanced), 12);
+    }
+
+    #[test]
+    fn games_symbols_and_scores_can_be_configured() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let opponent = ["R", "K", "P", "L", "S"].iter().enumerate();
+        let second = [
+            ("1", Outcome::Lose),
+            ("2", Outcome::Lose),
+            ("3", Outcome::Draw),
+            ("4", Outcome::Win),
+            ("5", Outcome::Win),
+        ];
+        let symbols = Symbols {
+            opponent: opponent.map(|(i, s)| (s.to_string(), Piece(i))).collect(),
+            second: (second.iter().enumerate())
+                .map(|(i, &(s, outcome))| (s.to_string(), Piece(i), outcome))
+                .collect(),
+        };
+        let scoring = Scoring {
+            shapes: vec![1, 5, 2, 4, 3],
+            outcomes: [0, 3, 6],
+        };
+        let rules = Rules::new(game, scoring, symbols).unwrap();
+
+        // Spock against scissors
+        let round = rules.parse_line(0, "S 2").unwrap();
+        assert_eq!(round, (Piece(4), Column(1)));
+        assert_eq!(rules.round_score(round, Part::Basic), 11);
+        // against scissors, Spock and rock win, and Spock is worth more
+        let round = rules.parse_line(0, "S 5").unwrap();
+        assert_eq!(rules.round_score(round, Part::Advanced), 11);
+        assert_eq!(
+            rules.parse_line(6, "S 6"),
+            Err(SolveError::parse(7, 3, "expected 1, 2, 3, 4 or 5, found 6"))
+        );
+    }
+
+    #[test]
+    fn rules_must_agree_with_their_game() {
+        let game = Game::rock_paper_scissors();
+        let scoring = Scoring::standard(&Game::rock_paper_scissors_lizard_spock());
+        assert!(Rules::new(game.clone(), scoring, Symbols::standard()).is_err());
+
+        let mut symbols = Symbols::standard();
+        symbols.opponent[1].0 = "A".to_string();
+        let scoring = Scoring::standard(&game);
+        assert!(Rules::new(game.clone(), scoring.clone(), symbols).is_err());
+
+        let mut symbols = Symbols::standard();
+        symbols.second[2].1 = Piece(3);
+        assert!(Rules::new(game.clone(), scoring.clone(), symbols).is_err());
+
+        let mut symbols = Symbols::standard();
+        symbols.second[2].0 = "Z Z".to_string();
+        assert!(Rules::new(game, scoring, symbols).is_err());
+    }
+}
@@ -0,0 +1,265 @@
+//! Playing against the opponent of a strategy guide rather than as the guide says: the best
+//! response to each of its weapons, how much a mixed strategy scores against the weapons it
+//! plays, and the equilibrium of the game when the opponent tries to keep the score low.
+
+use super::{Game, Piece, Rules};
+
+/// Smaller differences than this are rounding errors of the simplex
+const EPSILON: f64 = 1e-9;
+
+/// A weapon for every round, with the total score they make
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CounterStrategy {
+    pub responses: Vec<Piece>,
+    pub score: i32,
+}
+
+/// The weapon scoring the most against `opponent`, the first of them when they score as much
+pub fn best_response(rules: &Rules, opponent: Piece) -> Piece {
+    rules
+        .game()
+        .pieces()
+        .max_by_key(|&me| (rules.score(me, opponent), std::cmp::Reverse(me)))
+        .expect("a game has weapons")
+}
+
+/// The best response to every weapon the opponent plays, which is the most any sequence of
+/// weapons can score against them
+pub fn counter_strategy(rules: &Rules, opponents: &[Piece]) -> CounterStrategy {
+    let responses: Vec<_> = opponents
+        .iter()
+        .map(|&opponent| best_response(rules, opponent))
+        .collect();
+    let score = responses
+        .iter()
+        .zip(opponents)
+        .map(|(&me, &opponent)| rules.score(me, opponent))
+        .sum();
+    CounterStrategy { responses, score }
+}
+
+/// How likely each weapon of a game is to be played, by its number
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mixed(pub Vec<f64>);
+
+impl Mixed {
+    pub fn pure(game: &Game, piece: Piece) -> Self {
+        let mut probabilities = vec![0.0; game.len()];
+        probabilities[piece.0] = 1.0;
+        Mixed(probabilities)
+    }
+
+    pub fn uniform(game: &Game) -> Self {
+        Mixed(vec![1.0 / game.len() as f64; game.len()])
+    }
+
+    /// How often each weapon is played in `pieces`, or `None` if there are none
+    pub fn empirical(game: &Game, pieces: &[Piece]) -> Option<Self> {
+        if pieces.is_empty() {
+            return None;
+        }
+        let mut probabilities = vec![0.0; game.len()];
+        for piece in pieces {
+            probabilities[piece.0] += 1.0;
+        }
+        for p in &mut probabilities {
+            *p /= pieces.len() as f64;
+        }
+        Some(Mixed(probabilities))
+    }
+
+    pub fn probability(&self, piece: Piece) -> f64 {
+        self.0[piece.0]
+    }
+}
+
+/// The score of a round played with `me` against `opponent`, on average
+pub fn expected_score(rules: &Rules, me: &Mixed, opponent: &Mixed) -> f64 {
+    let game = rules.game();
+    game.pieces()
+        .flat_map(|a| game.pieces().map(move |b| (a, b)))
+        .map(|(a, b)| me.probability(a) * opponent.probability(b) * rules.score(a, b) as f64)
+        .sum()
+}
+
+/// The score of every round of the guide on average, with `me` against the weapons the
+/// opponent played as often as they played them, or `None` if the guide has no rounds
+pub fn expected_score_against(rules: &Rules, me: &Mixed, opponents: &[Piece]) -> Option<f64> {
+    let opponent = Mixed::empirical(rules.game(), opponents)?;
+    Some(expected_score(rules, me, &opponent))
+}
+
+/// The strategies where neither player gains by changing theirs alone, when the opponent plays
+/// to keep the score low rather than to score themselves
+#[derive(Debug, Clone, PartialEq)]
+pub struct Equilibrium {
+    /// The strategy scoring the most whatever the opponent plays
+    pub me: Mixed,
+    /// The strategy keeping the score the lowest whatever the player plays
+    pub opponent: Mixed,
+    /// The score of a round on average when both play their strategy
+    pub value: f64,
+}
+
+/// Solves the game as a zero-sum game on the score of the player, with the simplex method.
+///
+/// With the scores shifted to be positive, the opponent's strategy `q` keeps the score of every
+/// weapon of the player under the value `v`, so `z = q / v` maximizes the sum of `z` under
+/// `A z <= 1`. That sum is `1 / v`, and the dual values of the constraints are the player's
+/// strategy divided by `v`.
+pub fn equilibrium(rules: &Rules) -> Equilibrium {
+    let game = rules.game();
+    let n = game.len();
+    let lowest = game
+        .pieces()
+        .flat_map(|a| game.pieces().map(move |b| rules.score(a, b)))
+        .min()
+        .expect("a game has weapons");
+    let shift = 1 - lowest;
+
+    // a row per weapon of the player: its scores, its slack and 1, then the objective
+    let width = 2 * n + 1;
+    let mut tableau = vec![vec![0.0; width]; n + 1];
+    for me in game.pieces() {
+        let row = &mut tableau[me.0];
+        for opponent in game.pieces() {
+            row[opponent.0] = (rules.score(me, opponent) + shift) as f64;
+        }
+        row[n + me.0] = 1.0;
+        row[2 * n] = 1.0;
+    }
+    tableau[n][..n].fill(-1.0);
+    // the column in the basis of each row, starting with the slacks
+    let mut basis: Vec<usize> = (n..2 * n).collect();
+
+    // Bland's rule, entering and leaving by the lowest index, so that the simplex never cycles
+    while let Some(entering) = (0..2 * n).find(|&col| tableau[n][col] < -EPSILON) {
+        let leaving = (0..n)
+            .filter(|&row| tableau[row][entering] > EPSILON)
+            .min_by(|&a, &b| {
+                let ratio = |row: usize| tableau[row][2 * n] / tableau[row][entering];
+                (ratio(a).total_cmp(&ratio(b))).then(basis[a].cmp(&basis[b]))
+            })
+            .expect("the scores are positive, so the sum is bounded");
+        pivot(&mut tableau, leaving, entering);
+        basis[leaving] = entering;
+    }
+
+    let total = tableau[n][2 * n];
+    let mut opponent = vec![0.0; n];
+    for (row, &col) in basis.iter().enumerate() {
+        if col < n {
+            opponent[col] = tableau[row][2 * n] / total;
+        }
+    }
+    let me = (0..n).map(|i| tableau[n][n + i] / total).collect();
+    Equilibrium {
+        me: Mixed(me),
+        opponent: Mixed(opponent),
+        value: 1.0 / total - shift as f64,
+    }
+}
+
+fn pivot(tableau: &mut [Vec<f64>], row: usize, col: usize) {
+    let divisor = tableau[row][col];
+    for x in &mut tableau[row] {
+        *x /= divisor;
+    }
+    let pivot_row = tableau[row].clone();
+    for (i, other) in tableau.iter_mut().enumerate() {
+        let factor = other[col];
+        if i == row || factor == 0.0 {
+            continue;
+        }
+        for (x, p) in other.iter_mut().zip(&pivot_row) {
+            *x -= factor * p;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::d2::{Scoring, Symbols};
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-6, "{} is not {}", a, b);
+    }
+
+    #[test]
+    fn each_round_is_won_with_the_highest_scoring_weapon() {
+        let rules = Rules::classic();
+        let opponents = [Piece::ROCK, Piece::PAPER, Piece::SCISSORS];
+        let counter = counter_strategy(&rules, &opponents);
+        assert_eq!(
+            counter.responses,
+            [Piece::PAPER, Piece::SCISSORS, Piece::ROCK]
+        );
+        assert_eq!(counter.score, 8 + 9 + 7);
+        assert_eq!(counter_strategy(&rules, &[]).score, 0);
+    }
+
+    #[test]
+    fn mixed_strategies_are_scored_against_what_the_opponent_played() {
+        let rules = Rules::classic();
+        let game = rules.game();
+        let opponents = [Piece::ROCK, Piece::ROCK, Piece::PAPER, Piece::SCISSORS];
+        assert_eq!(
+            Mixed::empirical(game, &opponents),
+            Some(Mixed(vec![0.5, 0.25, 0.25]))
+        );
+        let paper = Mixed::pure(game, Piece::PAPER);
+        let score = expected_score_against(&rules, &paper, &opponents).unwrap();
+        assert_close(score, 0.5 * 8.0 + 0.25 * 5.0 + 0.25 * 2.0);
+        let score = expected_score_against(&rules, &Mixed::uniform(game), &opponents).unwrap();
+        assert_close(score, 0.5 * 5.0 + 0.25 * 5.0 + 0.25 * 5.0);
+        assert_eq!(expected_score_against(&rules, &paper, &[]), None);
+    }
+
+    #[test]
+    fn a_game_without_weapon_scores_is_played_uniformly() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let scoring = Scoring {
+            shapes: vec![0; 5],
+            outcomes: [0, 3, 6],
+        };
+        let mut symbols = Symbols::standard();
+        symbols.opponent.truncate(1);
+        symbols.second.truncate(1);
+        let rules = Rules::new(game, scoring, symbols).unwrap();
+
+        let equilibrium = equilibrium(&rules);
+        assert_close(equilibrium.value, 3.0);
+        for p in equilibrium.me.0.iter().chain(&equilibrium.opponent.0) {
+            assert_close(*p, 0.2);
+        }
+    }
+
+    #[test]
+    fn neither_player_gains_by_leaving_the_equilibrium() {
+        let rules = Rules::classic();
+        let game = rules.game();
+        let equilibrium = equilibrium(&rules);
+        // scissors score the most, so the opponent rarely plays paper, which they beat
+        assert_close(equilibrium.value, 5.0);
+        for (q, expected) in equilibrium.opponent.0.iter().zip([4.0, 1.0, 4.0]) {
+            assert_close(*q, expected / 9.0);
+        }
+        for (p, q) in equilibrium.me.0.iter().zip(&equilibrium.opponent.0) {
+            assert!(*p >= -EPSILON && *q >= -EPSILON);
+        }
+        assert_close(equilibrium.me.0.iter().sum(), 1.0);
+        assert_close(equilibrium.opponent.0.iter().sum(), 1.0);
+        assert_close(
+            expected_score(&rules, &equilibrium.me, &equilibrium.opponent),
+            equilibrium.value,
+        );
+        for piece in game.pieces() {
+            let pure = Mixed::pure(game, piece);
+            let theirs = expected_score(&rules, &equilibrium.me, &pure);
+            let mine = expected_score(&rules, &pure, &equilibrium.opponent);
+            assert!(theirs >= equilibrium.value - 1e-6);
+            assert!(mine <= equilibrium.value + 1e-6);
+        }
+    }
+}
@@ -1,55 +1,113 @@
 mod crypto;
 
-use std::num::ParseIntError;
+use super::{Runner, SolveError};
 
-fn parse_input(input: &str) -> Result<Vec<i64>, ParseIntError> {
-    input.split('\n').map(|l| l.parse::<i64>()).collect()
+fn parse_input(input: &str) -> Result<Vec<i64>, SolveError> {
+    let seq = input
+        .lines()
+        .enumerate()
+        .map(|(idx, l)| {
+            l.parse::<i64>()
+                .map_err(|e| SolveError::parse(idx + 1, 1, format!("invalid number '{}': {}", l, e)))
+        })
+        .collect::<Result<Vec<i64>, SolveError>>()?;
+    if seq.len() < 2 {
+        return Err(SolveError::unsupported("the file must contain at least two numbers"));
+    }
+    if !seq.contains(&0) {
+        return Err(SolveError::unsupported("the file must contain a 0"));
+    }
+    Ok(seq)
 }
 
-pub fn run_example(input: &str, part2: bool) -> i64 {
-    let seq = parse_input(input).unwrap();
-
-    if part2 {
-        crypto::decrypt(seq, 811589153, 10)
-    } else {
-        crypto::decrypt(seq, 1, 1)
-    }
+pub fn run_example(input: &str, part2: bool) -> Result<i64, SolveError> {
+    Day::run(input, !part2)
 }
 
 pub struct Day;
 
-impl super::Runner for Day {
+impl Runner for Day {
+    type Parsed<'a> = Vec<i64>;
     type T = i64;
-    
-    fn run(input: &str, basic: bool) -> Self::T {
-        run_example(input, !basic)
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(seq: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        Ok(crypto::decrypt(seq.clone(), 1, 1))
+    }
+
+    fn part2(seq: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        Ok(crypto::decrypt(seq.clone(), 811589153, 10))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::gen;
 
     const EXAMPLE_1: &str = include_str!("ex1.txt");
     const EXAMPLE_2: &str = include_str!("ex2.txt");
     
     #[test]
     fn part_1_small() {
-        assert_eq!(run_example(EXAMPLE_1, false), 3);
+        assert_eq!(run_example(EXAMPLE_1, false), Ok(3));
     }
 
     #[test]
     fn part_1_big() {
-        assert_eq!(run_example(EXAMPLE_2, false), 11037);
+        assert_eq!(run_example(EXAMPLE_2, false), Ok(11037));
     }
 
     #[test]
     fn part_2_small() {
-        assert_eq!(run_example(EXAMPLE_1, true), 1623178306);
+        assert_eq!(run_example(EXAMPLE_1, true), Ok(1623178306));
     }
 
     #[test]
     fn part_2_big() {
-        assert_eq!(run_example(EXAMPLE_2, true), 3033720253914);
+        assert_eq!(run_example(EXAMPLE_2, true), Ok(3033720253914));
+    }
+
+    /// Moves each number by taking it out of the list and putting it back in its new place
+    fn mix_by_reinserting(seq: &[i64], decryption_key: i64, rounds: u32) -> i64 {
+        let mut list: Vec<(usize, i64)> =
+            seq.iter().map(|n| n * decryption_key).enumerate().collect();
+        for _ in 0..rounds {
+            for i in 0..seq.len() {
+                let from = list.iter().position(|&(j, _)| j == i).unwrap();
+                let (_, n) = list.remove(from);
+                let to = (from as i64 + n).rem_euclid(list.len() as i64) as usize;
+                list.insert(to, (i, n));
+            }
+        }
+
+        let zero = list.iter().position(|&(_, n)| n == 0).unwrap();
+        [1000, 2000, 3000]
+            .iter()
+            .map(|offset| list[(zero + offset) % list.len()].1)
+            .sum()
+    }
+
+    #[test]
+    fn mixing_agrees_with_reinserting_every_number() {
+        for seed in 0..10 {
+            let input = gen::generate(20, seed, 50).unwrap();
+            let seq = parse_input(&input).unwrap();
+            assert_eq!(
+                Day::part1(&seq),
+                Ok(mix_by_reinserting(&seq, 1, 1)),
+                "seed {}",
+                seed
+            );
+            assert_eq!(
+                Day::part2(&seq),
+                Ok(mix_by_reinserting(&seq, 811589153, 10)),
+                "seed {}",
+                seed
+            );
+        }
     }
 }
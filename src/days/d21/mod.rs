@@ -1,7 +1,9 @@
 use std::collections::HashMap;
 
-#[derive(Debug, PartialEq)]
-enum ParsedOp {
+use super::{Runner, SolveError};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParsedOp {
     Human,
     Const(i64),
     Add(String, String),
@@ -131,62 +133,100 @@ impl Op {
     }
 }
 
-fn parse_input(input: &str) -> HashMap<&str, ParsedOp> {
-    let lines: Vec<_> = input
-        .split('\n')
-        .map(|line| line.split_once(": ").unwrap())
-        .collect();
+fn parse_input(input: &str) -> Result<HashMap<&str, ParsedOp>, SolveError> {
     let mut named_ops: HashMap<&str, ParsedOp> = HashMap::new();
-    for &(name, v) in lines.iter() {
+    for (idx, line) in input.lines().enumerate() {
+        let (name, v) = line
+            .split_once(": ")
+            .ok_or_else(|| SolveError::parse(idx + 1, 1, "expected '<name>: <job>'"))?;
+        let col = name.len() + 3;
         let parsed_op = match v.parse::<i64>() {
             Ok(c) => ParsedOp::Const(c),
             _ => {
                 let parts: Vec<_> = v.split(' ').collect();
-                match parts[1] {
-                    "+" => ParsedOp::Add(parts[0].to_string(), parts[2].to_string()),
-                    "-" => ParsedOp::Sub(parts[0].to_string(), parts[2].to_string()),
-                    "*" => ParsedOp::Mul(parts[0].to_string(), parts[2].to_string()),
-                    "/" => ParsedOp::Div(parts[0].to_string(), parts[2].to_string()),
-                    _ => panic!("unsupported operator: {}", parts[1]),
+                let [l, operator, r] = parts[..] else {
+                    return Err(SolveError::parse(
+                        idx + 1,
+                        col,
+                        "expected a number or an operation like 'abcd + efgh'",
+                    ));
+                };
+                let (l, r) = (l.to_string(), r.to_string());
+                match operator {
+                    "+" => ParsedOp::Add(l, r),
+                    "-" => ParsedOp::Sub(l, r),
+                    "*" => ParsedOp::Mul(l, r),
+                    "/" => ParsedOp::Div(l, r),
+                    _ => {
+                        return Err(SolveError::parse(
+                            idx + 1,
+                            col + l.len() + 1,
+                            format!("unsupported operator: {}", operator),
+                        ))
+                    }
                 }
             }
         };
         named_ops.insert(name, parsed_op);
     }
 
-    named_ops
+    Ok(named_ops)
+}
+
+pub fn run_program(input: &str, part2: bool) -> Result<i64, SolveError> {
+    Day::run(input, !part2)
 }
 
-pub fn run_program(input: &str, part2: bool) -> i64 {
-    let mut ops_map = parse_input(input);
+fn solve(ops_map: &HashMap<&str, ParsedOp>, part2: bool) -> Result<i64, SolveError> {
+    if !ops_map.contains_key("root") {
+        return Err(SolveError::unsupported("there is no monkey named root"));
+    }
+    let mut ops_map = ops_map.clone();
+    let unknown_monkey =
+        || SolveError::unsupported("root depends on a monkey that is not in the list");
     if part2 {
         ops_map.insert("humn", ParsedOp::Human);
-        ops_map.insert("root", match ops_map.get("root").unwrap() {
-            ParsedOp::Add(l, r) => ParsedOp::Sub(l.clone(), r.clone()),
-            ParsedOp::Sub(l, r) => ParsedOp::Sub(l.clone(), r.clone()),
-            ParsedOp::Mul(l, r) => ParsedOp::Sub(l.clone(), r.clone()),
-            ParsedOp::Div(l, r) => ParsedOp::Sub(l.clone(), r.clone()),
-            _ => panic!("root does not have a binary operation"),
-        });
-        let op = ParsedOp::create_op("root", &ops_map).unwrap();
-        op.find_human_value_if_zero().unwrap()
+        let root = match ops_map.get("root") {
+            Some(ParsedOp::Add(l, r))
+            | Some(ParsedOp::Sub(l, r))
+            | Some(ParsedOp::Mul(l, r))
+            | Some(ParsedOp::Div(l, r)) => ParsedOp::Sub(l.clone(), r.clone()),
+            Some(_) => {
+                return Err(SolveError::unsupported(
+                    "root does not have a binary operation",
+                ))
+            }
+            None => return Err(SolveError::unsupported("there is no monkey named root")),
+        };
+        ops_map.insert("root", root);
+        let op = ParsedOp::create_op("root", &ops_map).ok_or_else(unknown_monkey)?;
+        op.find_human_value_if_zero().ok_or(SolveError::NoSolution)
     } else {
-        let op = ParsedOp::create_op("root", &ops_map).unwrap();
+        let op = ParsedOp::create_op("root", &ops_map).ok_or_else(unknown_monkey)?;
         if let Some(ParsedOp::Const(c)) = ops_map.get("humn") {
-            op.evaluate(*c)
+            Ok(op.evaluate(*c))
         } else {
-            panic!("the human was not a constant!");
+            Err(SolveError::unsupported("humn does not yell a number"))
         }
     }
 }
 
 pub struct Day;
 
-impl super::Runner for Day {
+impl Runner for Day {
+    type Parsed<'a> = HashMap<&'a str, ParsedOp>;
     type T = i64;
-    
-    fn run(input: &str, basic: bool) -> Self::T {
-        run_program(input, !basic)
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(ops_map: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        solve(ops_map, false)
+    }
+
+    fn part2(ops_map: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        solve(ops_map, true)
     }
 }
 
@@ -194,7 +234,7 @@ impl super::Runner for Day {
 mod tests {
     use std::collections::HashMap;
 
-    use super::{parse_input, ParsedOp, run_program};
+    use super::{parse_input, ParsedOp, run_program, SolveError};
 
     fn load_example(big: bool) -> &'static str {
         if big {
@@ -206,39 +246,59 @@ mod tests {
     
     #[test]
     fn parse_input_works_for_single_lines() {
-        assert_eq!(parse_input("root: 123"), HashMap::from([("root", ParsedOp::Const(123))]));
-        assert_eq!(parse_input("root: abcd + efgh"), HashMap::from([("root", ParsedOp::Add("abcd".to_string(), "efgh".to_string()))]));
-        assert_eq!(parse_input("root: abcd - efgh"), HashMap::from([("root", ParsedOp::Sub("abcd".to_string(), "efgh".to_string()))]));
-        assert_eq!(parse_input("root: abcd * efgh"), HashMap::from([("root", ParsedOp::Mul("abcd".to_string(), "efgh".to_string()))]));
-        assert_eq!(parse_input("root: abcd / efgh"), HashMap::from([("root", ParsedOp::Div("abcd".to_string(), "efgh".to_string()))]));
+        assert_eq!(parse_input("root: 123"), Ok(HashMap::from([("root", ParsedOp::Const(123))])));
+        assert_eq!(parse_input("root: abcd + efgh"), Ok(HashMap::from([("root", ParsedOp::Add("abcd".to_string(), "efgh".to_string()))])));
+        assert_eq!(parse_input("root: abcd - efgh"), Ok(HashMap::from([("root", ParsedOp::Sub("abcd".to_string(), "efgh".to_string()))])));
+        assert_eq!(parse_input("root: abcd * efgh"), Ok(HashMap::from([("root", ParsedOp::Mul("abcd".to_string(), "efgh".to_string()))])));
+        assert_eq!(parse_input("root: abcd / efgh"), Ok(HashMap::from([("root", ParsedOp::Div("abcd".to_string(), "efgh".to_string()))])));
     }
 
     #[test]
     fn parse_input_works_for_multiple_lines() {
-        assert_eq!(parse_input("a: 123\nb: a + a\nc: a * b"), HashMap::from([
+        assert_eq!(parse_input("a: 123\nb: a + a\nc: a * b"), Ok(HashMap::from([
             ("a", ParsedOp::Const(123)),
             ("b", ParsedOp::Add("a".to_string(), "a".to_string())),
             ("c", ParsedOp::Mul("a".to_string(), "b".to_string()))
-        ]));
+        ])));
+    }
+
+    #[test]
+    fn parse_input_rejects_unknown_operators() {
+        assert_eq!(
+            parse_input("a: 123\nroot: abcd % efgh"),
+            Err(SolveError::parse(2, 12, "unsupported operator: %"))
+        );
+        assert_eq!(
+            parse_input("root 123"),
+            Err(SolveError::parse(1, 1, "expected '<name>: <job>'"))
+        );
+    }
+
+    #[test]
+    fn both_parts_report_a_missing_root_the_same_way() {
+        let no_root = Err(SolveError::unsupported("there is no monkey named root"));
+        assert_eq!(run_program("", false), no_root);
+        assert_eq!(run_program("", true), no_root);
+        assert_eq!(run_program("humn: 5", false), no_root);
     }
 
     #[test]
     fn small_example_works_for_part_1() {
-        assert_eq!(run_program(load_example(false), false), 152);
+        assert_eq!(run_program(load_example(false), false), Ok(152));
     }
 
     #[test]
     fn big_example_works_for_part_1() {
-        assert_eq!(run_program(load_example(true), false), 194501589693264);
+        assert_eq!(run_program(load_example(true), false), Ok(194501589693264));
     }
 
     #[test]
     fn small_example_works_for_part_2() {
-        assert_eq!(run_program(load_example(false), true), 301);
+        assert_eq!(run_program(load_example(false), true), Ok(301));
     }
 
     #[test]
     fn big_example_works_for_part_2() {
-        assert_eq!(run_program(load_example(true), true), 3887609741189);
+        assert_eq!(run_program(load_example(true), true), Ok(3887609741189));
     }
 }
@@ -1,6 +1,12 @@
 use instruction::{parse_instructions, Instruction};
 use player::*;
 
+use super::{Part, Runner, SolveError};
+use crate::{
+    grid::{Grid, Pos},
+    render::{Image, Paint, Render, Rgb},
+};
+
 mod instruction;
 mod player;
 
@@ -11,35 +17,52 @@ enum SpaceContent {
     Stone,
 }
 
-struct Board {
-    rows: Vec<Vec<SpaceContent>>,
+impl Paint for SpaceContent {
+    fn colour(&self) -> Rgb {
+        match self {
+            SpaceContent::Empty => Rgb::BLACK,
+            SpaceContent::Ground => Rgb::WHITE,
+            SpaceContent::Stone => Rgb::GREY,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Board {
+    grid: Grid<SpaceContent>,
     layout: [[bool; 4]; 4],
     size: u32,
     is_cube: bool,
 }
 
-fn calc_square_size(rows: &[Vec<SpaceContent>]) -> u32 {
-    let h = rows.len();
-    let w = rows.iter().map(|r| r.len()).max().unwrap();
+fn calc_square_size(grid: &Grid<SpaceContent>) -> Result<u32, SolveError> {
+    let h = grid.height();
+    let w = grid.width();
 
-    if w % 3 == 0 {
+    let size = if w.is_multiple_of(3) {
         (h / 4) as u32
-    } else if h % 3 == 0 {
+    } else if h.is_multiple_of(3) {
         (w / 4) as u32
     } else {
-        panic!("Not a valid layout");
+        0
+    };
+    if size == 0 {
+        return Err(SolveError::unsupported(format!(
+            "a {}x{} map is not a valid layout",
+            w, h
+        )));
     }
+    Ok(size)
 }
 
-fn calc_layout(rows: &[Vec<SpaceContent>], size: u32) -> [[bool; 4]; 4] {
+fn calc_layout(grid: &Grid<SpaceContent>, size: u32) -> [[bool; 4]; 4] {
     let mut layout: [[bool; 4]; 4] = Default::default();
 
     #[allow(clippy::needless_range_loop)]
     for y in 0..4 {
         for x in 0..4 {
-            let ry = y * size as usize;
-            let rx = x * size as usize;
-            if ry < rows.len() && rx < rows[ry].len() && rows[ry][rx] != SpaceContent::Empty {
+            let corner = Pos::new(x * size as usize, y * size as usize);
+            if grid.get(corner).is_some_and(|&c| c != SpaceContent::Empty) {
                 layout[y][x] = true;
             }
         }
@@ -48,36 +71,45 @@ fn calc_layout(rows: &[Vec<SpaceContent>], size: u32) -> [[bool; 4]; 4] {
 }
 
 impl Board {
-    fn new(rows: Vec<Vec<SpaceContent>>, is_cube: bool) -> Self {
-        let size = calc_square_size(&rows);
-        let layout = calc_layout(&rows, size);
+    fn new(grid: Grid<SpaceContent>) -> Result<Self, SolveError> {
+        let size = calc_square_size(&grid)?;
+        let layout = calc_layout(&grid, size);
 
-        Board {
-            rows,
+        Ok(Board {
+            grid,
             layout,
             size,
+            is_cube: false,
+        })
+    }
+
+    /// The same board, either flat or folded into a cube
+    fn folded(&self, is_cube: bool) -> Board {
+        Board {
             is_cube,
+            ..self.clone()
         }
     }
 
     fn lookup(&self, x: i32, y: i32) -> SpaceContent {
-        let is_off_the_board = y < 0
-            || x < 0
-            || y >= self.rows.len() as i32
-            || x >= self.rows[y as usize].len() as i32;
-        if is_off_the_board {
-            SpaceContent::Empty
-        } else {
-            self.rows[y as usize][x as usize]
+        match self.grid.pos_at(x, y) {
+            Some(pos) => self.grid[pos],
+            None => SpaceContent::Empty,
         }
     }
 
-    fn walk(&self, start: Player, steps: Vec<Instruction>) -> Player {
+    /// Follows the path, showing `visit` every tile it moves onto
+    fn walk(
+        &self,
+        start: Player,
+        steps: &[Instruction],
+        mut visit: impl FnMut(Player),
+    ) -> Result<Player, SolveError> {
         let mut here = start;
-        for step in steps {
-            here = self.perform(here, step);
+        for &step in steps {
+            here = self.perform(here, step, &mut visit)?;
         }
-        here
+        Ok(here)
     }
 
     fn region(&self, x: i32, y: i32) -> (i32, i32, bool) {
@@ -90,7 +122,7 @@ impl Board {
         (rx, ry, is_non_empty)
     }
 
-    fn move_one_step(&self, here: Player) -> Player {
+    fn move_one_step(&self, here: Player) -> Result<Player, SolveError> {
         let next = here.after_move();
         let region_next = self.region(next.x, next.y);
         let needs_to_wrap = !region_next.2;
@@ -98,11 +130,13 @@ impl Board {
         let next = if needs_to_wrap { self.wrap(here) } else { next };
 
         match self.lookup(next.x, next.y) {
-            SpaceContent::Ground => next,
-            SpaceContent::Stone => here,
-            SpaceContent::Empty => {
-                panic!("The wrap did'nt work! hx: {}, hy: {}, nx: {}, ny: {}", here.x, here.y, next.x, next.y);
-            }
+            SpaceContent::Ground => Ok(next),
+            SpaceContent::Stone => Ok(here),
+            // only the cube layouts of the two examples are known to wrap_cube
+            SpaceContent::Empty => Err(SolveError::unsupported(format!(
+                "cannot wrap around the cube from ({}, {})",
+                here.x, here.y
+            ))),
         }
     }
 
@@ -191,23 +225,29 @@ impl Board {
         Player { dir: here.dir, ..p }
     }
 
-    fn perform(&self, start: Player, step: Instruction) -> Player {
+    fn perform(
+        &self,
+        start: Player,
+        step: Instruction,
+        visit: &mut impl FnMut(Player),
+    ) -> Result<Player, SolveError> {
         match step {
             Instruction::Move(d) => {
                 let mut here = start;
                 for _ in 0..d {
-                    here = self.move_one_step(here);
+                    here = self.move_one_step(here)?;
+                    visit(here);
                 }
-                here
+                Ok(here)
             }
-            Instruction::TurnLeft => start.after_ccw_turn(),
-            Instruction::TurnRight => start.after_cw_turn(),
+            Instruction::TurnLeft => Ok(start.after_ccw_turn()),
+            Instruction::TurnRight => Ok(start.after_cw_turn()),
         }
     }
 }
 
 impl Board {
-    fn parse(lines: Vec<&str>, cube: bool) -> Self {
+    fn parse(lines: Vec<&str>) -> Result<Self, SolveError> {
         let rows = lines
             .into_iter()
             .map(|l| {
@@ -220,7 +260,8 @@ impl Board {
                     .collect()
             })
             .collect();
-        Board::new(rows, cube)
+        // the rows leave out the blanks after the last tile
+        Board::new(Grid::from_ragged_rows(rows, SpaceContent::Empty))
     }
 }
 
@@ -234,45 +275,109 @@ fn score(pos: Player) -> u32 {
     (pos.y + 1) as u32 * 1000 + (pos.x + 1) as u32 * 4 + dir_num
 }
 
-fn parse_input(input: &[&str], cube: bool) -> (Board, Vec<Instruction>) {
+/// The map with the path to follow and the column where the path starts
+pub struct Notes {
+    board: Board,
+    instructions: Vec<Instruction>,
+    start_x: usize,
+}
+
+fn parse_input(input: &str) -> Result<Notes, SolveError> {
+    let start_x = input
+        .lines()
+        .next()
+        .and_then(|l| l.find('.'))
+        .ok_or_else(|| SolveError::parse(1, 1, "the first row has no open tile"))?;
+
     let mut lines: Vec<&str> = Vec::new();
     let mut board_done = false;
-    let mut steps: Option<&str> = None;
+    let mut steps: Option<(usize, &str)> = None;
 
-    for line in input.iter() {
+    for (idx, line) in input.lines().enumerate() {
         if line.is_empty() {
             board_done = true;
         } else if !board_done {
             lines.push(line);
         } else {
-            steps = Some(line);
+            steps = Some((idx, line));
         }
     }
 
-    let board = Board::parse(lines, cube);
-    let instructions = parse_instructions(steps.unwrap());
+    let (idx, steps) = steps.ok_or_else(|| {
+        SolveError::parse(
+            input.lines().count() + 1,
+            1,
+            "expected the path description after the map",
+        )
+    })?;
+    if let Some((col, c)) = steps
+        .char_indices()
+        .find(|&(_, c)| !c.is_ascii_digit() && c != 'L' && c != 'R')
+    {
+        return Err(SolveError::parse(
+            idx + 1,
+            col + 1,
+            format!("invalid instruction: {}", c),
+        ));
+    }
+
+    let board = Board::parse(lines)?;
+    let instructions = parse_instructions(steps);
+
+    Ok(Notes {
+        board,
+        instructions,
+        start_x,
+    })
+}
+
+fn solve(notes: &Notes, cube: bool) -> Result<u32, SolveError> {
+    let board = notes.board.folded(cube);
 
-    (board, instructions)
+    let start_pos = Player::new(notes.start_x as i32, 0, Direction::Right);
+    let end_pos = board.walk(start_pos, &notes.instructions, |_| {})?;
+
+    Ok(score(end_pos))
 }
 
-pub fn run_program(input: &str, cube: bool) -> u32 {
-    let lines: Vec<_> = input.lines().collect();
-    let start_x = lines[0].find(|c| c == '.').unwrap();
-    let (board, instructions) = parse_input(&lines, cube);
+/// The map with the tiles walked over in red, and the one where the path ends in green
+pub fn render(input: &str, part: Part) -> Result<Image, SolveError> {
+    let notes = parse_input(input)?;
+    let board = notes.board.folded(part == Part::Advanced);
 
-    let start_pos = Player::new(start_x as i32, 0, Direction::Right);
-    let end_pos = board.walk(start_pos, instructions);
+    let mut image = board.grid.render();
+    let mut mark = |p: Player, colour| {
+        if let Some(pos) = image.pos_at(p.x, p.y) {
+            image[pos] = colour;
+        }
+    };
+    let start_pos = Player::new(notes.start_x as i32, 0, Direction::Right);
+    mark(start_pos, Rgb::RED);
+    let end_pos = board.walk(start_pos, &notes.instructions, |p| mark(p, Rgb::RED))?;
+    mark(end_pos, Rgb::GREEN);
+    Ok(image)
+}
 
-    score(end_pos)
+pub fn run_program(input: &str, cube: bool) -> Result<u32, SolveError> {
+    Day::run(input, !cube)
 }
 
 pub struct Day;
 
-impl super::Runner for Day {
+impl Runner for Day {
+    type Parsed<'a> = Notes;
     type T = u32;
-    
-    fn run(input: &str, basic: bool) -> Self::T {
-        run_program(input, !basic)
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(notes: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        solve(notes, false)
+    }
+
+    fn part2(notes: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        solve(notes, true)
     }
 }
 
@@ -296,8 +401,7 @@ mod tests {
 
     #[test]
     fn calc_square_size_is_correct_ex1() {
-        let input: Vec<_> = include_str!("ex1.txt").split('\n').collect();
-        let (board, _) = parse_input(&input, false);
+        let board = parse_input(EXAMPLE_1).unwrap().board;
         assert_eq!(board.size, 4);
         assert_eq!(
             board.layout,
@@ -312,8 +416,7 @@ mod tests {
 
     #[test]
     fn calc_square_size_is_correct_ex2() {
-        let input: Vec<_> = include_str!("ex2.txt").split('\n').collect();
-        let (board, _) = parse_input(&input, false);
+        let board = parse_input(EXAMPLE_2).unwrap().board;
         assert_eq!(board.size, 50);
         assert_eq!(
             board.layout,
@@ -326,23 +429,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn the_path_is_drawn_on_the_map() {
+        let image = render(EXAMPLE_1, Part::Basic).unwrap();
+        assert_eq!((image.width(), image.height()), (16, 12));
+        // the path starts at the top left of the map and ends on row 6, column 8
+        assert_eq!(image[Pos::new(8, 0)], Rgb::RED);
+        assert_eq!(image[Pos::new(7, 5)], Rgb::GREEN);
+        assert_eq!(image[Pos::new(0, 0)], Rgb::BLACK);
+    }
+
     #[test]
     fn small_example_should_work_part_1() {
-        assert_eq!(run_program(EXAMPLE_1, false), 6032);
+        assert_eq!(run_program(EXAMPLE_1, false), Ok(6032));
     }
 
     #[test]
     fn big_example_should_work_part_1() {
-        assert_eq!(run_program(EXAMPLE_2, false), 117102);
+        assert_eq!(run_program(EXAMPLE_2, false), Ok(117102));
     }
 
     #[test]
     fn small_example_should_work_part_2() {
-        assert_eq!(run_program(EXAMPLE_1, true), 5031);
+        assert_eq!(run_program(EXAMPLE_1, true), Ok(5031));
     }
 
     #[test]
     fn big_example_should_work_part_2() {
-        assert_eq!(run_program(EXAMPLE_2, true), 135297);
+        assert_eq!(run_program(EXAMPLE_2, true), Ok(135297));
     }
 }
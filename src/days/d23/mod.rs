@@ -1,25 +1,24 @@
-use std::{collections::HashMap, fmt::Display, ops::Add};
+use std::{collections::HashMap, fmt::Display};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Point {
-    x: i32,
-    y: i32,
-}
+use super::{Part, SolveError};
+use crate::{
+    grid::{Bounds, Point, SparseGrid},
+    render::{Image, Paint, Recorder, Render, Rgb},
+};
+
+/// Marks a tile taken by an elf
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Elf;
 
-impl Point {
-    const fn new(x: i32, y: i32) -> Self {
-        Self { x, y }
+impl Display for Elf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#")
     }
 }
 
-impl Add for Point {
-    type Output = Point;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self::Output {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
+impl Paint for Elf {
+    fn colour(&self) -> Rgb {
+        Rgb::GREEN
     }
 }
 
@@ -30,18 +29,7 @@ struct Board {
 
 impl Display for Board {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let b = self.bounds();
-        for y in b.top..=b.bottom {
-            for x in b.left..=b.right {
-                if self.elfs.contains(&Point::new(x, y)) {
-                    write!(f, "#")?;
-                } else {
-                    write!(f, ".")?;
-                }
-            }
-            writeln!(f)?;
-        }
-        Ok(())
+        write!(f, "{}", self.occupied())
     }
 }
 
@@ -51,20 +39,6 @@ impl From<Vec<Point>> for Board {
     }
 }
 
-fn neighbors(p: Point) -> [Point; 8] {
-    let mut pts = [Point::new(0, 0); 8];
-    let mut index = 0;
-    for dy in -1..=1 {
-        for dx in -1..=1 {
-            if dx != 0 || dy != 0 {
-                pts[index] = Point::new(p.x + dx, p.y + dy);
-                index += 1;
-            }
-        }
-    }
-    pts
-}
-
 fn dirs_to_check(time: u32, retry: u32) -> [Point; 3] {
     static DIRECTIONS_TO_CHECK: [[Point; 3]; 4] = [
         [Point::new(1, -1), Point::new(0, -1), Point::new(-1, -1)],
@@ -75,19 +49,17 @@ fn dirs_to_check(time: u32, retry: u32) -> [Point; 3] {
     DIRECTIONS_TO_CHECK[((time + retry) % 4) as usize]
 }
 
-struct Bounds {
-    top: i32,
-    left: i32,
-    bottom: i32,
-    right: i32,
-}
-
 impl Board {
+    fn occupied(&self) -> SparseGrid<Elf> {
+        self.elfs.iter().map(|&elf| (elf, Elf)).collect()
+    }
+
     fn simulate_round(&self, time: u32) -> Board {
+        let occupied = self.occupied();
         let mut proposed_moves: Vec<(Point, Point)> = Vec::new();
         let mut next_try = Vec::new();
         for &elf in self.elfs.iter() {
-            if neighbors(elf).iter().any(|n| self.elfs.contains(n)) {
+            if elf.neighbours8().iter().any(|&n| occupied.contains(n)) {
                 next_try.push(elf);
             } else {
                 proposed_moves.push((elf, elf));
@@ -102,7 +74,7 @@ impl Board {
             for e in elfs {
                 let mut taken = false;
                 for d in dirs {
-                    if self.elfs.contains(&(e + d)) {
+                    if occupied.contains(e + d) {
                         taken = true;
                         break;
                     }
@@ -135,74 +107,116 @@ impl Board {
         Board { elfs: moved_elfs }
     }
 
-    fn bounds(&self) -> Bounds {
-        let min_x = self.elfs.iter().map(|e| e.x).min().unwrap();
-        let min_y = self.elfs.iter().map(|e| e.y).min().unwrap();
-        let max_x = self.elfs.iter().map(|e| e.x).max().unwrap();
-        let max_y = self.elfs.iter().map(|e| e.y).max().unwrap();
-
-        Bounds {
-            top: min_y,
-            left: min_x,
-            bottom: max_y,
-            right: max_x,
-        }
+    fn bounds(&self) -> Option<Bounds> {
+        self.occupied().bounds()
     }
 
     fn calc_result(&self) -> u32 {
-        if self.elfs.is_empty() {
-            return 0;
+        match self.bounds() {
+            Some(b) => b.width() * b.height() - self.elfs.len() as u32,
+            None => 0,
         }
-
-        let b = self.bounds();
-
-        ((b.bottom - b.top + 1) * (b.right - b.left + 1)) as u32 - self.elfs.len() as u32
     }
 }
 
-fn parse_input(lines: &[&str]) -> Vec<Point> {
+fn parse_input(lines: &[&str]) -> Result<Vec<Point>, SolveError> {
     let mut elfs = Vec::new();
     for (y, &line) in lines.iter().enumerate() {
         for (x, c) in line.chars().enumerate() {
-            if c == '#' {
-                elfs.push(Point::new(x as i32, y as i32));
+            match c {
+                '#' => elfs.push(Point::new(x as i32, y as i32)),
+                '.' => {}
+                _ => {
+                    return Err(SolveError::parse(
+                        y + 1,
+                        x + 1,
+                        format!("expected '#' or '.', found '{}'", c),
+                    ))
+                }
             }
         }
     }
-    elfs
+    Ok(elfs)
+}
+
+pub fn run_program(lines: &[&str], part1: bool) -> Result<u32, SolveError> {
+    let elfs = parse_input(lines)?;
+    Ok(simulate(&elfs, part1))
 }
 
-pub fn run_program(lines: &[&str], part1: bool) -> u32 {
-    let elfs = parse_input(lines);
-    let mut board = Board::from(elfs);
+/// Lets the elves spread out for 10 rounds, or until none of them moves, and gives back where
+/// they end with the number of rounds. `observe` is shown the elves at the start and after each
+/// round.
+fn spread(elfs: &[Point], part1: bool, mut observe: impl FnMut(&Board)) -> (Board, u32) {
+    let mut board = Board::from(elfs.to_vec());
+    observe(&board);
     if part1 {
         for t in 0..10 {
             board = board.simulate_round(t);
+            observe(&board);
         }
-        board.calc_result()
+        (board, 10)
     } else {
         let mut board_before = board.clone();
         let mut t = 0;
         loop {
             board = board_before.simulate_round(t);
+            observe(&board);
             if board == board_before {
                 break;
             }
             board_before = board;
             t += 1;
         }
-        t+1
+        (board, t + 1)
+    }
+}
+
+fn simulate(elfs: &[Point], part1: bool) -> u32 {
+    let (board, rounds) = spread(elfs, part1, |_| {});
+    if part1 {
+        board.calc_result()
+    } else {
+        rounds
     }
 }
 
+/// Where the elves end
+pub fn render(input: &str, part: Part) -> Result<Image, SolveError> {
+    let lines: Vec<_> = input.lines().collect();
+    let elfs = parse_input(&lines)?;
+    let (board, _) = spread(&elfs, part == Part::Basic, |_| {});
+    Ok(board.occupied().render())
+}
+
+/// The elves after every round
+pub fn animate(input: &str, part: Part, recorder: &mut Recorder) -> Result<(), SolveError> {
+    let lines: Vec<_> = input.lines().collect();
+    let elfs = parse_input(&lines)?;
+    let (board, _) = spread(&elfs, part == Part::Basic, |board| {
+        recorder.step(|| board.occupied().frame())
+    });
+    recorder.end(|| board.occupied().frame());
+    Ok(())
+}
+
 pub struct Day;
 
 impl super::Runner for Day {
+    type Parsed<'a> = Vec<Point>;
     type T = u32;
-    
-    fn run(input: &str, basic: bool) -> Self::T {
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
         let lines: Vec<_> = input.lines().collect();
-        run_program(&lines, basic)
+        parse_input(&lines)
+    }
+
+    fn part1(elfs: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        Ok(simulate(elfs, true))
+    }
+
+    fn part2(elfs: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        Ok(simulate(elfs, false))
     }
 }
 
@@ -216,7 +230,7 @@ mod tests {
     #[test]
     fn parse_input_works() {
         let elfs = parse_input(&["..#", ".#."]);
-        assert_eq!(elfs, vec![Point::new(2, 0), Point::new(1, 1)]);
+        assert_eq!(elfs, Ok(vec![Point::new(2, 0), Point::new(1, 1)]));
     }
 
     #[test]
@@ -237,27 +251,47 @@ mod tests {
         assert_eq!(Board::from(elfs).calc_result(), 9 - 2);
     }
 
+    #[test]
+    fn the_elves_are_drawn_where_they_end() {
+        let image = render(SMALL_EXAMPLE, Part::Basic).unwrap();
+        let elves = image.iter().filter(|(_, &c)| c == Rgb::GREEN).count();
+        assert_eq!(elves, 22);
+        // the 110 empty tiles are drawn black
+        assert_eq!(image.width() * image.height(), 110 + 22);
+    }
+
+    #[test]
+    fn every_round_is_a_frame() {
+        let mut recorder = Recorder::new(1, 100);
+        animate(SMALL_EXAMPLE, Part::Advanced, &mut recorder).unwrap();
+        // the start and the 20 rounds, of which the last moves no elf
+        assert_eq!(recorder.frames().len(), 21);
+        let frames = recorder.frames();
+        assert_eq!(frames[19].image, frames[20].image);
+        assert_ne!(frames[0].image, frames[20].image);
+    }
+
     #[test]
     fn small_example_works_part_1() {
         let lines: Vec<_> = SMALL_EXAMPLE.split('\n').collect();
-        assert_eq!(run_program(&lines, true), 110);
+        assert_eq!(run_program(&lines, true), Ok(110));
     }
 
     #[test]
     fn big_example_works_part_1() {
         let lines: Vec<_> = BIG_EXAMPLE.split('\n').collect();
-        assert_eq!(run_program(&lines, true), 3788);
+        assert_eq!(run_program(&lines, true), Ok(3788));
     }
 
     #[test]
     fn small_example_works_part_2() {
         let lines: Vec<_> = SMALL_EXAMPLE.split('\n').collect();
-        assert_eq!(run_program(&lines, false), 20);
+        assert_eq!(run_program(&lines, false), Ok(20));
     }
 
     #[test]
     fn big_example_works_part_2() {
         let lines: Vec<_> = BIG_EXAMPLE.split('\n').collect();
-        assert_eq!(run_program(&lines, false), 921);
+        assert_eq!(run_program(&lines, false), Ok(921));
     }
 }
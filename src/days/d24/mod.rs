@@ -1,6 +1,13 @@
-use std::{collections::HashSet, fmt::Display};
+use std::fmt::Display;
 
-use queues::{IsQueue, Queue};
+use num::integer::lcm;
+
+use super::{CancelToken, NoProgress, Part, Progress, Runner, SolveError};
+use crate::{
+    grid::{Grid, Pos},
+    render::{Image, Recorder, Render, Rgb},
+    search::{Search, VisitedBy},
+};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
 enum Dir {
@@ -26,6 +33,25 @@ impl Dir {
             _ => 0,
         }
     }
+
+    fn from_arrow(c: char) -> Option<Dir> {
+        match c {
+            '<' => Some(Dir::Left),
+            '>' => Some(Dir::Right),
+            '^' => Some(Dir::Up),
+            'v' => Some(Dir::Down),
+            _ => None,
+        }
+    }
+
+    fn arrow(self) -> char {
+        match self {
+            Dir::Up => '^',
+            Dir::Left => '<',
+            Dir::Down => 'v',
+            Dir::Right => '>',
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
@@ -62,80 +88,74 @@ impl Wind {
 }
 
 #[derive(Clone)]
-struct Board {
+pub struct Board {
     width: u32,
     height: u32,
     winds: Vec<Wind>,
+    /// Whether any wind is on a tile
+    windy: Grid<bool>,
 }
 
 impl Display for Board {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let c = if y == 0 {
-                    if x == 1 {
-                        '.'
-                    } else {
-                        '#'
-                    }
-                } else if y == self.height - 1 {
-                    if x == self.width - 2 {
-                        '.'
-                    } else {
-                        '#'
-                    }
-                } else if x == 0 || x == self.width - 1 {
-                    '#'
-                } else {
-                    let w: Vec<_> = self.winds.iter().filter(|w| w.x == x && w.y == y).collect();
-                    let count = w.len();
-                    if count == 0 {
-                        '.'
-                    } else if count == 1 {
-                        match w[0].dir {
-                            Dir::Up => '^',
-                            Dir::Left => '<',
-                            Dir::Down => 'v',
-                            Dir::Right => '>',
-                        }
-                    } else if count < 10 {
-                        (b'0' + count as u8) as char
-                    } else {
-                        'X'
-                    }
-                };
-                write!(f, "{}", c)?;
-            }
-            writeln!(f)?;
-        }
-        Ok(())
+        write!(f, "{}", self.tiles())
     }
 }
 
 impl Board {
-    fn with_sorted_wind(self) -> Self {
-        let mut w = self.winds;
-        w.sort();
-        Board {
-            width: self.width,
-            height: self.height,
-            winds: w,
+    /// The map as in the input
+    fn tiles(&self) -> Grid<char> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let mut map = Grid::new(width, height, '.');
+        for pos in map.positions() {
+            let is_wall = pos.x == 0 || pos.x == width - 1 || pos.y == 0 || pos.y == height - 1;
+            let is_opening = pos == Pos::new(1, 0) || pos == Pos::new(width - 2, height - 1);
+            if is_wall && !is_opening {
+                map[pos] = '#';
+            }
         }
+        // a single wind shows its direction, and several how many they are
+        for w in &self.winds {
+            let pos = Pos::new(w.x as usize, w.y as usize);
+            map[pos] = match map[pos] {
+                '.' => w.dir.arrow(),
+                '^' | '<' | 'v' | '>' => '2',
+                '9' | 'X' => 'X',
+                count => (count as u8 + 1) as char,
+            };
+        }
+        map
     }
 
-    fn move_wind(&self) -> Board {
+    fn new(width: u32, height: u32, winds: Vec<Wind>) -> Board {
+        let mut windy = Grid::new(width as usize, height as usize, false);
+        for w in &winds {
+            windy[Pos::new(w.x as usize, w.y as usize)] = true;
+        }
         Board {
-            winds: self
-                .winds
-                .iter()
-                .map(|w| w.moved_forward().wrapped(self.width, self.height))
-                .collect(),
-            ..*self
+            width,
+            height,
+            winds,
+            windy,
         }
     }
 
+    fn move_wind(&self) -> Board {
+        let winds = self
+            .winds
+            .iter()
+            .map(|w| w.moved_forward().wrapped(self.width, self.height))
+            .collect();
+        Board::new(self.width, self.height, winds)
+    }
+
+    /// After how many minutes the winds are back where they started
+    fn period(&self) -> u32 {
+        lcm(self.width - 2, self.height - 2)
+    }
+
     fn is_windy(&self, x: u32, y: u32) -> bool {
-        self.winds.iter().any(|w| w.x == x && w.y == y)
+        self.windy[Pos::new(x as usize, y as usize)]
     }
 
     fn can_move(&self, x: u32, y: u32, dir: Option<Dir>) -> bool {
@@ -153,136 +173,252 @@ impl Board {
     }
 }
 
-fn parse_input(lines: &[&str]) -> Board {
-    let height = lines.len();
-    let width = lines[0].len();
-    let mut winds = Vec::new();
-
-    for (y, line) in lines.iter().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            let dir = match c {
-                '<' => Some(Dir::Left),
-                '>' => Some(Dir::Right),
-                '^' => Some(Dir::Up),
-                'v' => Some(Dir::Down),
-                _ => None,
-            };
-
-            if let Some(dir) = dir {
-                winds.push(Wind {
-                    x: x as u32,
-                    y: y as u32,
-                    dir,
-                });
-            }
-        }
-    }
+/// The valley with the column of the opening in its bottom wall
+pub struct Valley {
+    board: Board,
+    end_x: u32,
+}
 
-    Board {
-        width: width as u32,
-        height: height as u32,
-        winds,
+fn parse_input(input: &str) -> Result<Valley, SolveError> {
+    let map = Grid::parse(input, |c| match c {
+        '#' | '.' => Ok(c),
+        _ if Dir::from_arrow(c).is_some() => Ok(c),
+        _ => Err(format!("unexpected character: {}", c)),
+    })?;
+    if map.height() < 3 || map.width() < 3 {
+        return Err(SolveError::unsupported(
+            "the valley must be at least 3x3 including its walls",
+        ));
     }
-}
 
-#[derive(Clone)]
-struct BfsState {
-    board: Board,
-    x: u32,
-    y: u32,
-    steps: u32,
+    let winds = map
+        .iter()
+        .filter_map(|(pos, &c)| {
+            Some(Wind {
+                x: pos.x as u32,
+                y: pos.y as u32,
+                dir: Dir::from_arrow(c)?,
+            })
+        })
+        .collect();
+
+    let end_x = map
+        .row(map.height() - 1)
+        .iter()
+        .position(|&c| c == '.')
+        .ok_or_else(|| SolveError::parse(map.height(), 1, "the bottom wall has no opening"))?
+        as u32;
+
+    Ok(Valley {
+        board: Board::new(map.width() as u32, map.height() as u32, winds),
+        end_x,
+    })
 }
 
-#[derive(PartialEq, Eq, Hash)]
-struct BfsKey {
-    winds: Vec<Wind>,
-    x: u32,
-    y: u32,
+/// Where the expedition is at the end of a minute
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Step {
+    pub minute: u32,
+    pub x: u32,
+    pub y: u32,
 }
 
-impl BfsState {
-    fn new(board: Board, x: u32, y: u32, steps: u32) -> Self {
-        BfsState { board, x, y, steps }
-    }
+/// The boards of the minutes the search has reached, up to the period of the winds
+struct Forecast {
+    boards: Vec<Board>,
+    period: u32,
 }
 
-fn calc(board: Board, start_x: u32, start_y: u32, end_x: u32, end_y: u32) -> (Board, u32) {
-    let mut seen = HashSet::<BfsKey>::new();
-
-    let mut q: Queue<BfsState> = Queue::new();
-    q.add(BfsState::new(board, start_x, start_y, 0)).unwrap();
-    loop {
-        let s = q.remove().unwrap();
-
-        if s.y == end_y && s.x == end_x {
-            return (s.board.clone(), s.steps);
-        }
-
-        let key = BfsKey {
-            winds: s.board.winds.clone(),
-            x: s.x,
-            y: s.y,
-        };
-        if !seen.insert(key) {
-            continue;
+impl Forecast {
+    fn new(board: Board) -> Self {
+        Forecast {
+            period: board.period(),
+            boards: vec![board],
         }
+    }
 
-        let b = s.board.move_wind().with_sorted_wind(); // sort to make 'seen' more efficient
-        if b.can_move(s.x, s.y, Some(Dir::Up)) {
-            q.add(BfsState::new(b.clone(), s.x, s.y - 1, s.steps + 1))
-                .unwrap();
-        }
-        if b.can_move(s.x, s.y, Some(Dir::Down)) {
-            q.add(BfsState::new(b.clone(), s.x, s.y + 1, s.steps + 1))
-                .unwrap();
-        }
-        if b.can_move(s.x, s.y, Some(Dir::Left)) {
-            q.add(BfsState::new(b.clone(), s.x - 1, s.y, s.steps + 1))
-                .unwrap();
-        }
-        if b.can_move(s.x, s.y, Some(Dir::Right)) {
-            q.add(BfsState::new(b.clone(), s.x + 1, s.y, s.steps + 1))
-                .unwrap();
-        }
-        if b.can_move(s.x, s.y, None) {
-            q.add(BfsState::new(b.clone(), s.x, s.y, s.steps + 1))
-                .unwrap();
+    fn at(&mut self, minute: u32) -> &Board {
+        let index = (minute % self.period) as usize;
+        while self.boards.len() <= index {
+            let next = self.boards.last().unwrap().move_wind();
+            self.boards.push(next);
         }
+        &self.boards[index]
     }
 }
 
-fn run_program(lines: &[&str], part1: bool) -> u32 {
-    let board = parse_input(lines);
+/// Every way to spend a minute: a step in a direction or waiting
+const MOVES: [Option<Dir>; 5] = [
+    Some(Dir::Up),
+    Some(Dir::Down),
+    Some(Dir::Left),
+    Some(Dir::Right),
+    None,
+];
+
+/// The quickest way from `start` to the tile at `end_x` and `end_y`
+fn calc(
+    forecast: &mut Forecast,
+    start: Step,
+    end_x: u32,
+    end_y: u32,
+    cancel: &CancelToken,
+    explored: &mut u64,
+) -> Result<Vec<Step>, SolveError> {
+    // the same tile at the same point of the cycle of the winds is the same state
+    let period = forecast.period;
+    let visited = VisitedBy::new(|s: &Step| (s.minute % period, s.x, s.y));
+
+    let found = Search::new(visited).cancel(cancel).bfs(
+        [start],
+        |s| {
+            let b = forecast.at(s.minute + 1);
+            MOVES
+                .into_iter()
+                .filter(|&dir| b.can_move(s.x, s.y, dir))
+                .map(|dir| Step {
+                    minute: s.minute + 1,
+                    x: (s.x as i32 + dir.map_or(0, Dir::dx)) as u32,
+                    y: (s.y as i32 + dir.map_or(0, Dir::dy)) as u32,
+                })
+                .collect::<Vec<_>>()
+        },
+        |s| s.x == end_x && s.y == end_y,
+    )?;
+    *explored += found.stats.explored;
+    Ok(found.path)
+}
+
+/// The route through the valley, and back and through again unless `part1`, one step per
+/// minute from the first
+pub fn route(
+    valley: &Valley,
+    part1: bool,
+    cancel: &CancelToken,
+    progress: &dyn Progress,
+) -> Result<Vec<Step>, SolveError> {
+    let mut forecast = Forecast::new(valley.board.clone());
 
     let start_x = 1;
     let start_y = 0;
-    let end_x = lines[board.height as usize - 1].find('.').unwrap() as u32;
-    let end_y = board.height - 1;
-
-    let mut total = 0;
+    let end_x = valley.end_x;
+    let end_y = valley.board.height - 1;
+
+    let legs: &[(u32, u32)] = if part1 {
+        &[(end_x, end_y)]
+    } else {
+        &[(end_x, end_y), (start_x, start_y), (end_x, end_y)]
+    };
+
+    let mut route = vec![Step {
+        minute: 0,
+        x: start_x,
+        y: start_y,
+    }];
+    let mut explored = 0;
+    for &(x, y) in legs {
+        let start = *route.last().unwrap();
+        let leg = calc(&mut forecast, start, x, y, cancel, &mut explored)?;
+        route.extend(&leg[1..]);
+        progress.states_explored(explored);
+    }
 
-    let (board, steps) = calc(board, start_x, start_y, end_x, end_y);
-    total += steps;
+    Ok(route)
+}
 
-    if !part1 {
-        let (board, steps) = calc(board, end_x, end_y, start_x, start_y);
-        total += steps;
+fn solve(
+    valley: &Valley,
+    part1: bool,
+    cancel: &CancelToken,
+    progress: &dyn Progress,
+) -> Result<u32, SolveError> {
+    let route = route(valley, part1, cancel, progress)?;
+    Ok(route.last().unwrap().minute)
+}
 
-        let (_, steps) = calc(board, start_x, start_y, end_x, end_y);
-        total += steps;
+/// The valley with its winds, the tiles of the route in red and the last of them in green
+fn picture(board: &Board, route: &[Step]) -> Image {
+    let mut image = board.tiles().map(|&c| match c {
+        '#' => Rgb::GREY,
+        '.' => Rgb::BLACK,
+        _ => Rgb::BLUE,
+    });
+    for step in route {
+        image[Pos::new(step.x as usize, step.y as usize)] = Rgb::RED;
     }
+    if let Some(here) = route.last() {
+        image[Pos::new(here.x as usize, here.y as usize)] = Rgb::GREEN;
+    }
+    image
+}
 
-    total
+/// The valley when the expedition arrives
+pub fn render(input: &str, part: Part) -> Result<Image, SolveError> {
+    let valley = parse_input(input)?;
+    let route = route(
+        &valley,
+        part == Part::Basic,
+        &CancelToken::new(),
+        &NoProgress,
+    )?;
+    let arrival = route.last().unwrap().minute;
+
+    let mut forecast = Forecast::new(valley.board.clone());
+    Ok(picture(forecast.at(arrival), &route))
+}
+
+/// The valley every minute of the route, as the winds blow
+pub fn animate(input: &str, part: Part, recorder: &mut Recorder) -> Result<(), SolveError> {
+    let valley = parse_input(input)?;
+    let route = route(
+        &valley,
+        part == Part::Basic,
+        &CancelToken::new(),
+        &NoProgress,
+    )?;
+
+    let mut forecast = Forecast::new(valley.board.clone());
+    for (i, step) in route.iter().enumerate() {
+        let board = forecast.at(step.minute);
+        recorder.step(|| picture(board, &route[..=i]).frame());
+    }
+    let board = forecast.at(route.last().unwrap().minute);
+    recorder.end(|| picture(board, &route).frame());
+    Ok(())
 }
 
 pub struct Day;
 
-impl super::Runner for Day {
+impl Runner for Day {
+    type Parsed<'a> = Valley;
     type T = u32;
 
-    fn run(input: &str, basic: bool) -> Self::T {
-        let lines: Vec<_> = input.lines().collect();
-        run_program(&lines, basic)
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(valley: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        Self::part1_with(valley, &CancelToken::new(), &NoProgress)
+    }
+
+    fn part2(valley: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        Self::part2_with(valley, &CancelToken::new(), &NoProgress)
+    }
+
+    fn part1_with(
+        valley: &Self::Parsed<'_>,
+        cancel: &CancelToken,
+        progress: &dyn Progress,
+    ) -> Result<Self::T, SolveError> {
+        solve(valley, true, cancel, progress)
+    }
+
+    fn part2_with(
+        valley: &Self::Parsed<'_>,
+        cancel: &CancelToken,
+        progress: &dyn Progress,
+    ) -> Result<Self::T, SolveError> {
+        solve(valley, false, cancel, progress)
     }
 }
 
@@ -293,27 +429,65 @@ mod tests {
     const SMALL_EXAMPLE: &str = include_str!("ex1.txt");
     const BIG_EXAMPLE: &str = include_str!("ex2.txt");
 
+    #[test]
+    fn the_board_prints_as_its_input() {
+        let valley = parse_input(SMALL_EXAMPLE).unwrap();
+        assert_eq!(valley.board.to_string().trim_end(), SMALL_EXAMPLE);
+    }
+
+    #[test]
+    fn the_route_dodges_the_winds() {
+        let valley = parse_input(SMALL_EXAMPLE).unwrap();
+        let route = route(&valley, false, &CancelToken::new(), &NoProgress).unwrap();
+        assert_eq!(route.len(), 54 + 1);
+
+        let mut forecast = Forecast::new(valley.board.clone());
+        for (minute, step) in route.iter().enumerate() {
+            assert_eq!(step.minute, minute as u32);
+            assert!(forecast.at(step.minute).can_move(step.x, step.y, None));
+        }
+        for pair in route.windows(2) {
+            assert!(pair[0].x.abs_diff(pair[1].x) + pair[0].y.abs_diff(pair[1].y) <= 1);
+        }
+    }
+
+    #[test]
+    fn the_route_is_drawn_on_the_valley() {
+        let image = render(SMALL_EXAMPLE, Part::Basic).unwrap();
+        assert_eq!((image.width(), image.height()), (8, 6));
+        assert_eq!(image[Pos::new(1, 0)], Rgb::RED);
+        assert_eq!(image[Pos::new(6, 5)], Rgb::GREEN);
+        assert_eq!(image[Pos::new(0, 0)], Rgb::GREY);
+    }
+
+    #[test]
+    fn every_minute_is_a_frame() {
+        let mut recorder = Recorder::new(2, 100);
+        animate(SMALL_EXAMPLE, Part::Basic, &mut recorder).unwrap();
+        // the start and the 18 minutes, one in two of them and the last
+        assert_eq!(recorder.steps(), 19);
+        assert_eq!(recorder.frames().len(), 10);
+        let last = &recorder.frames()[9].image;
+        assert_eq!(last, &render(SMALL_EXAMPLE, Part::Basic).unwrap());
+    }
+
     #[test]
     fn part_1_small() {
-        let lines: Vec<_> = SMALL_EXAMPLE.split('\n').collect();
-        assert_eq!(run_program(&lines, true), 18);
+        assert_eq!(Day::run(SMALL_EXAMPLE, true), Ok(18));
     }
 
     #[test]
     fn part_1_big() {
-        let lines: Vec<_> = BIG_EXAMPLE.split('\n').collect();
-        assert_eq!(run_program(&lines, true), 225);
+        assert_eq!(Day::run(BIG_EXAMPLE, true), Ok(225));
     }
 
     #[test]
     fn part_2_small() {
-        let lines: Vec<_> = SMALL_EXAMPLE.split('\n').collect();
-        assert_eq!(run_program(&lines, false), 54);
+        assert_eq!(Day::run(SMALL_EXAMPLE, false), Ok(54));
     }
 
     #[test]
     fn part_2_big() {
-        let lines: Vec<_> = BIG_EXAMPLE.split('\n').collect();
-        assert_eq!(run_program(&lines, false), 711);
+        assert_eq!(Day::run(BIG_EXAMPLE, false), Ok(711));
     }
 }
@@ -1,6 +1,11 @@
-use std::{collections::VecDeque, fmt::Display, iter::Sum};
+use std::{collections::VecDeque, fmt::Display, io::BufRead, iter::Sum};
 
-#[derive(Debug, PartialEq, Eq)]
+use super::{
+    stream::{for_each_line, StreamError},
+    Part, SolveError,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Snafu(i64);
 
 impl TryFrom<&str> for Snafu {
@@ -62,32 +67,64 @@ impl Sum for Snafu {
     }
 }
 
-fn run_program(lines: &[&str]) -> String {
-    let res: Snafu = lines
+fn parse_line(idx: usize, line: &str) -> Result<Snafu, SolveError> {
+    Snafu::try_from(line).map_err(|message| {
+        let col = line.find(|c| !"210-=".contains(c)).unwrap_or(0);
+        SolveError::parse(idx + 1, col + 1, message)
+    })
+}
+
+fn parse_input(lines: &[&str]) -> Result<Vec<Snafu>, SolveError> {
+    lines
         .iter()
-        .map(|&line| Snafu::try_from(line).unwrap())
-        .sum();
-    format!("{}", res)
+        .enumerate()
+        .map(|(idx, &line)| parse_line(idx, line))
+        .collect()
+}
+
+/// Sums the numbers one line at a time
+pub fn run_reader<R: BufRead>(reader: R, part: Part) -> Result<String, StreamError> {
+    let mut sum = Snafu(0);
+    for_each_line(reader, |idx, line| {
+        let n = parse_line(idx, line)?;
+        sum = Snafu(sum.0.checked_add(n.0).ok_or_else(|| {
+            SolveError::unsupported(format!(
+                "the sum no longer fits in 64 bits after line {}",
+                idx + 1
+            ))
+        })?);
+        Ok(())
+    })?;
+    match part {
+        Part::Basic => Ok(format!("{}", sum)),
+        Part::Advanced => Ok("No part 2 for this day".to_string()),
+    }
 }
 
 pub struct Day;
 
 impl super::Runner for Day {
+    type Parsed<'a> = Vec<Snafu>;
     type T = String;
 
-    fn run(input: &str, basic: bool) -> Self::T {
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
         let lines: Vec<_> = input.lines().collect();
-        if basic {
-            run_program(&lines)
-        } else {
-            "No part 2 for this day".to_string()
-        }
+        parse_input(&lines)
+    }
+
+    fn part1(numbers: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        Ok(format!("{}", numbers.iter().copied().sum::<Snafu>()))
+    }
+
+    fn part2(_: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        Ok("No part 2 for this day".to_string())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::days::Runner;
 
     const SMALL_EXAMPLE: &str = include_str!("ex1.txt");
     const BIG_EXAMPLE: &str = include_str!("ex2.txt");
@@ -118,14 +155,33 @@ mod tests {
 
     #[test]
     fn part_1_small() {
-        let lines: Vec<_> = SMALL_EXAMPLE.split('\n').collect();
-        assert_eq!(run_program(&lines), "2=-1=0");
+        assert_eq!(Day::run(SMALL_EXAMPLE, true), Ok("2=-1=0".to_string()));
     }
 
     #[test]
     fn part_1_big() {
-        let lines: Vec<_> = BIG_EXAMPLE.split('\n').collect();
-        assert_eq!(run_program(&lines), "2=0=02-0----2-=02-10");
+        assert_eq!(Day::run(BIG_EXAMPLE, true), Ok("2=0=02-0----2-=02-10".to_string()));
+    }
+
+    #[test]
+    fn invalid_digits_are_reported_with_their_location() {
+        assert_eq!(
+            Day::run("1=\n12x0", true),
+            Err(SolveError::parse(2, 3, "invalid input"))
+        );
+    }
+
+    #[test]
+    fn a_streamed_sum_too_large_for_64_bits_is_reported() {
+        // each line is the largest number of 27 digits, about 3.7e18
+        let input = format!("{0}\n{0}\n{0}\n", "2".repeat(27));
+        let Err(StreamError::Solve(error)) = run_reader(input.as_bytes(), Part::Basic) else {
+            panic!("expected a solve error");
+        };
+        assert_eq!(
+            error,
+            SolveError::unsupported("the sum no longer fits in 64 bits after line 3")
+        );
     }
 
     // Note: there was no part 2 on this day since it was the last day
@@ -1,40 +1,39 @@
-pub fn main(input: &str) -> usize{
-    println!("Hello, world!");
+use crate::days::SolveError;
 
-    let mut total = 0;
+pub fn main(rucksacks: &[&str]) -> Result<usize, SolveError> {
+    Ok(rucksacks.iter().map(|l| priority(l)).sum())
+}
 
-    for l in input.lines() {
-        let first = &l[..(l.len() / 2)];
-        let second = &l[(l.len() / 2)..];
+/// The priority of the item in both compartments of a rucksack
+pub fn priority(l: &str) -> usize {
+    let first = &l[..(l.len() / 2)];
+    let second = &l[(l.len() / 2)..];
 
-        let mut fbits: [bool; 52] = [false; 52];
-        let mut sbits: [bool; 52] = [false; 52];
+    let mut fbits: [bool; 52] = [false; 52];
+    let mut sbits: [bool; 52] = [false; 52];
 
-        for c in first.chars() {
-            let d = match c {
-                'a'..='z' => (c as u32) - ('a' as u32),
-                'A'..='Z' => (c as u32) - ('A' as u32) + 26,
-                _ => panic!(),
-            };
-            fbits[d as usize] = true;
-        }
-        for c in second.chars() {
-            let d = match c {
-                'a'..='z' => (c as u32) - ('a' as u32),
-                'A'..='Z' => (c as u32) - ('A' as u32) + 26,
-                _ => panic!(),
-            };
-            sbits[d as usize] = true;
-        }
-        let mut dup_idx = 0;
-        for i in 0..52 {
-            if fbits[i] && sbits[i] {
-                dup_idx = i;
-            }
+    for c in first.chars() {
+        let d = match c {
+            'a'..='z' => (c as u32) - ('a' as u32),
+            'A'..='Z' => (c as u32) - ('A' as u32) + 26,
+            _ => unreachable!("checked by check_items"),
+        };
+        fbits[d as usize] = true;
+    }
+    for c in second.chars() {
+        let d = match c {
+            'a'..='z' => (c as u32) - ('a' as u32),
+            'A'..='Z' => (c as u32) - ('A' as u32) + 26,
+            _ => unreachable!("checked by check_items"),
+        };
+        sbits[d as usize] = true;
+    }
+    let mut dup_idx = 0;
+    for i in 0..52 {
+        if fbits[i] && sbits[i] {
+            dup_idx = i;
         }
-
-        total += dup_idx + 1;
     }
 
-    total
+    dup_idx + 1
 }
@@ -1,17 +1,19 @@
-fn occurances(s: &str) -> [bool; 52] {
+use crate::days::SolveError;
+
+pub fn occurances(s: &str) -> [bool; 52] {
     let mut fbits: [bool; 52] = [false; 52];
     for c in s.chars() {
         let d = match c {
             'a'..='z' => (c as u32) - ('a' as u32),
             'A'..='Z' => (c as u32) - ('A' as u32) + 26,
-            _ => panic!(),
+            _ => unreachable!("checked by check_items"),
         };
         fbits[d as usize] = true;
     }
     fbits
 }
 
-fn find_same_in_three(a: [bool; 52], b: [bool; 52], c: [bool; 52]) -> usize {
+pub fn find_same_in_three(a: [bool; 52], b: [bool; 52], c: [bool; 52]) -> usize {
     let mut dup_idx = 0;
     for i in 0..52 {
         if a[i] && b[i] && c[i] {
@@ -22,16 +24,18 @@ fn find_same_in_three(a: [bool; 52], b: [bool; 52], c: [bool; 52]) -> usize {
     dup_idx + 1
 }
 
-pub fn main(input: &str) -> usize {
-    println!("Hello, world!");
-
-    let lines: Vec<_> = input.lines().collect();
+pub fn main(rucksacks: &[&str]) -> Result<usize, SolveError> {
+    if !rucksacks.len().is_multiple_of(3) {
+        return Err(SolveError::unsupported(
+            "the number of rucksacks must be a multiple of three",
+        ));
+    }
 
-    let s: usize = lines
+    let s: usize = rucksacks
         .chunks(3)
         .map(|ls| (occurances(ls[0]), occurances(ls[1]), occurances(ls[2])))
         .map(|(a, b, c)| find_same_in_three(a, b, c))
         .sum();
 
-    s
+    Ok(s)
 }
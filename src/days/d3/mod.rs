@@ -1,16 +1,76 @@
+use std::io::BufRead;
+
+use super::{
+    stream::{for_each_line, StreamError},
+    Part, SolveError,
+};
+
 mod a;
 mod b;
 
 pub struct Day;
 
+/// Items are the letters a-z and A-Z
+fn check_items(line: &str, idx: usize) -> Result<(), SolveError> {
+    match line.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+        Some((col, c)) => Err(SolveError::parse(
+            idx + 1,
+            col + 1,
+            format!("invalid item: {}", c),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// The items of every rucksack, one line each
+fn parse_input(input: &str) -> Result<Vec<&str>, SolveError> {
+    let lines: Vec<_> = input.lines().collect();
+    for (idx, l) in lines.iter().enumerate() {
+        check_items(l, idx)?;
+    }
+    Ok(lines)
+}
+
+/// Solves a part one rucksack at a time, keeping only the items of the group being read
+pub fn run_reader<R: BufRead>(reader: R, part: Part) -> Result<usize, StreamError> {
+    let mut total = 0;
+    let mut group = Vec::with_capacity(3);
+    for_each_line(reader, |idx, l| {
+        check_items(l, idx)?;
+        match part {
+            Part::Basic => total += a::priority(l),
+            Part::Advanced => {
+                group.push(b::occurances(l));
+                if let [first, second, third] = group[..] {
+                    total += b::find_same_in_three(first, second, third);
+                    group.clear();
+                }
+            }
+        }
+        Ok(())
+    })?;
+
+    if !group.is_empty() {
+        return Err(
+            SolveError::unsupported("the number of rucksacks must be a multiple of three").into(),
+        );
+    }
+    Ok(total)
+}
+
 impl super::Runner for Day {
+    type Parsed<'a> = Vec<&'a str>;
     type T = usize;
 
-    fn run(input: &str, basic: bool) -> Self::T {
-        if basic {
-            a::main(input)
-        } else {
-            b::main(input)
-        }
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(rucksacks: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        a::main(rucksacks)
+    }
+
+    fn part2(rucksacks: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        b::main(rucksacks)
     }
 }
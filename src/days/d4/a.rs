@@ -1,7 +1,6 @@
-struct Section {
-    from: i32,
-    to: i32,
-}
+use crate::days::SolveError;
+
+use super::Section;
 
 impl Section {
     fn contains(&self, other: &Section) -> bool {
@@ -9,35 +8,11 @@ impl Section {
     }
 }
 
-fn parse_section(s: &str) -> Option<Section> {
-    match s.split_once('-') {
-        Some((l, r)) => match (l.parse::<i32>(), r.parse::<i32>()) {
-            (Ok(from), Ok(to)) => Some(Section { from, to }),
-            _ => None,
-        },
-        _ => None,
-    }
-}
-
-fn parse_sections(s: &str) -> Option<(Section, Section)> {
-    match s.split_once(',') {
-        Some((l, r)) => match (parse_section(l), parse_section(r)) {
-            (Some(ls), Some(rs)) => Some((ls, rs)),
-            _ => None,
-        },
-        _ => None,
-    }
+pub fn main(pairs: &[(Section, Section)]) -> Result<usize, SolveError> {
+    Ok(pairs.iter().filter(|pair| counts(pair)).count())
 }
 
-pub fn main(input: &str) -> usize {
-    println!("Hello, world!");
-
-    let c = input
-        .lines()
-        .take_while(|l| !l.is_empty())
-        .filter_map(parse_sections)
-        .filter(|(l, r)| l.contains(r) || r.contains(l))
-        .count();
-
-    c
+/// Whether one range of the pair contains the other
+pub fn counts((l, r): &(Section, Section)) -> bool {
+    l.contains(r) || r.contains(l)
 }
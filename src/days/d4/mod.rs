@@ -1,16 +1,85 @@
+use std::io::BufRead;
+
+use super::{
+    stream::{for_each_line, StreamError},
+    Part, SolveError,
+};
+
 mod a;
 mod b;
 
 pub struct Day;
 
+pub struct Section {
+    from: i32,
+    to: i32,
+}
+
+fn parse_section(s: &str) -> Option<Section> {
+    match s.split_once('-') {
+        Some((l, r)) => match (l.parse::<i32>(), r.parse::<i32>()) {
+            (Ok(from), Ok(to)) => Some(Section { from, to }),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn parse_sections(s: &str) -> Option<(Section, Section)> {
+    match s.split_once(',') {
+        Some((l, r)) => match (parse_section(l), parse_section(r)) {
+            (Some(ls), Some(rs)) => Some((ls, rs)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn parse_line(idx: usize, line: &str) -> Result<(Section, Section), SolveError> {
+    parse_sections(line)
+        .ok_or_else(|| SolveError::parse(idx + 1, 1, "expected two section ranges like 2-4,6-8"))
+}
+
+fn parse_input(input: &str) -> Result<Vec<(Section, Section)>, SolveError> {
+    input
+        .lines()
+        .enumerate()
+        .take_while(|(_, l)| !l.is_empty())
+        .map(|(idx, line)| parse_line(idx, line))
+        .collect()
+}
+
+/// Solves a part one pair at a time, up to the first blank line as the whole input is
+pub fn run_reader<R: BufRead>(reader: R, part: Part) -> Result<usize, StreamError> {
+    let counts = match part {
+        Part::Basic => a::counts,
+        Part::Advanced => b::counts,
+    };
+    let mut count = 0;
+    let mut ended = false;
+    for_each_line(reader, |idx, line| {
+        ended |= line.is_empty();
+        if !ended && counts(&parse_line(idx, line)?) {
+            count += 1;
+        }
+        Ok(())
+    })?;
+    Ok(count)
+}
+
 impl super::Runner for Day {
+    type Parsed<'a> = Vec<(Section, Section)>;
     type T = usize;
 
-    fn run(input: &str, basic: bool) -> Self::T {
-        if basic {
-            a::main(input)
-        } else {
-            b::main(input)
-        }
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(pairs: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        a::main(pairs)
+    }
+
+    fn part2(pairs: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        b::main(pairs)
     }
 }
@@ -1,16 +1,119 @@
+use std::collections::VecDeque;
+
+use super::SolveError;
+
 mod a;
 mod b;
 
 pub struct Day;
 
+#[derive(Clone)]
+pub struct Ship {
+    stacks: Vec<VecDeque<char>>,
+}
+
+pub struct MoveInstr {
+    from: u32,
+    to: u32,
+    count: u32,
+}
+
+/// The starting stacks of crates and the rearrangement procedure
+pub struct Procedure {
+    ship: Ship,
+    moves: Vec<MoveInstr>,
+}
+
+impl Ship {
+    /// The crates on top of the stacks, skipping the empty ones
+    fn top_crates(&self) -> String {
+        self.stacks.iter().filter_map(|s| s.back()).collect()
+    }
+}
+
+fn parse_ship(lines: &[&str]) -> Ship {
+    let mut stacks: Vec<VecDeque<char>> = Vec::new();
+    for line in lines.iter().rev() {
+        if !line.starts_with(" 1") {
+            let row: Vec<char> = line.chars().skip(1).step_by(4).collect();
+
+            for i in 0..(row.len()) {
+                if i == stacks.len() {
+                    stacks.push(VecDeque::new());
+                }
+                if row[i] != ' ' {
+                    stacks[i].push_back(row[i]);
+                }
+            }
+        }
+    }
+    Ship { stacks }
+}
+
+fn parse_move_instr(line: &str) -> Option<MoveInstr> {
+    let parts: Vec<_> = line.split(' ').collect();
+    match (
+        parts.get(1)?.parse::<u32>().ok(),
+        parts.get(3)?.parse::<u32>().ok(),
+        parts.get(5)?.parse::<u32>().ok(),
+    ) {
+        (Some(count), Some(from), Some(to)) => Some(MoveInstr {
+            from: from.checked_sub(1)?,
+            to: to.checked_sub(1)?,
+            count,
+        }),
+        _ => None,
+    }
+}
+
+fn parse_input(input: &str) -> Result<Procedure, SolveError> {
+    let initial_ship_lines: Vec<_> = input
+        .lines()
+        .take_while(|l| !l.is_empty())
+        .collect();
+
+    let ship = parse_ship(&initial_ship_lines);
+    let mut moves = Vec::new();
+
+    for (idx, line) in input
+        .lines()
+        .enumerate()
+        .skip(initial_ship_lines.len() + 1)
+        .take_while(|(_, l)| !l.is_empty())
+    {
+        let instr = parse_move_instr(line)
+            .filter(|i| {
+                (i.from as usize) < ship.stacks.len() && (i.to as usize) < ship.stacks.len()
+            })
+            .ok_or_else(|| {
+                SolveError::parse(
+                    idx + 1,
+                    1,
+                    format!(
+                        "expected 'move <count> from <stack> to <stack>' with stacks 1 to {}",
+                        ship.stacks.len()
+                    ),
+                )
+            })?;
+        moves.push(instr);
+    }
+
+    Ok(Procedure { ship, moves })
+}
+
 impl super::Runner for Day {
+    type Parsed<'a> = Procedure;
     type T = String;
 
-    fn run(input: &str, basic: bool) -> Self::T {
-        if basic {
-            a::main(input)
-        } else {
-            b::main(input)
-        }
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(procedure: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        a::main(procedure)
+    }
+
+    fn part2(procedure: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        b::main(procedure)
     }
 }
@@ -1,16 +1,123 @@
+use std::{collections::VecDeque, io::BufRead};
+
+use super::{stream::StreamError, Part, SolveError};
+
 mod a;
 mod b;
 
 pub struct Day;
 
+/// The datastream buffer, which is the first line of the input
+fn parse_input(input: &str) -> Result<&str, SolveError> {
+    let line = input
+        .lines()
+        .next()
+        .ok_or_else(|| SolveError::parse(1, 1, "the input is empty"))?;
+    if let Some((col, c)) = line.char_indices().find(|(_, c)| !c.is_ascii()) {
+        return Err(SolveError::parse(1, col + 1, format!("invalid character: {}", c)));
+    }
+    Ok(line)
+}
+
+/// The last characters of the datastream, counting how many of them are different
+struct Window {
+    len: usize,
+    recent: VecDeque<u8>,
+    counts: [usize; 256],
+    distinct: usize,
+}
+
+impl Window {
+    fn new(len: usize) -> Self {
+        Window {
+            len,
+            recent: VecDeque::with_capacity(len + 1),
+            counts: [0; 256],
+            distinct: 0,
+        }
+    }
+
+    /// Adds the next character, telling whether the last `len` ones are all different
+    fn push(&mut self, c: u8) -> bool {
+        self.recent.push_back(c);
+        let c = c as usize;
+        self.counts[c] += 1;
+        if self.counts[c] == 1 {
+            self.distinct += 1;
+        }
+        if self.recent.len() > self.len {
+            if let Some(old) = self.recent.pop_front() {
+                self.counts[old as usize] -= 1;
+                if self.counts[old as usize] == 0 {
+                    self.distinct -= 1;
+                }
+            }
+        }
+        self.distinct == self.len
+    }
+}
+
+/// How many characters are read when the last `len` of them are all different for the first
+/// time
+fn marker_end(chars: impl IntoIterator<Item = u8>, len: usize) -> Option<usize> {
+    let mut window = Window::new(len);
+    chars
+        .into_iter()
+        .position(|c| window.push(c))
+        .map(|i| i + 1)
+}
+
+/// Solves a part one character at a time, keeping only the last few, so that the datastream
+/// can be longer than memory. It ends at the first line feed or carriage return.
+pub fn run_reader<R: BufRead>(reader: R, part: Part) -> Result<usize, StreamError> {
+    let mut window = Window::new(match part {
+        Part::Basic => 4,
+        Part::Advanced => 14,
+    });
+    let mut marker = None;
+    let mut read = 0;
+    let mut empty = true;
+
+    let mut bytes = reader.bytes();
+    while let Some(byte) = bytes.next() {
+        let byte = byte?;
+        empty = false;
+        if byte == b'\n' || byte == b'\r' {
+            break;
+        }
+        read += 1;
+        if !byte.is_ascii() {
+            // the rest of the character takes at most three more bytes
+            let mut encoded = vec![byte];
+            encoded.extend(bytes.by_ref().take(3).map_while(Result::ok));
+            let c = String::from_utf8_lossy(&encoded).chars().next().unwrap_or('?');
+            return Err(SolveError::parse(1, read, format!("invalid character: {}", c)).into());
+        }
+        // the rest of the line is still checked once the marker is found
+        if marker.is_none() && window.push(byte) {
+            marker = Some(read);
+        }
+    }
+
+    if empty {
+        return Err(SolveError::parse(1, 1, "the input is empty").into());
+    }
+    marker.ok_or(SolveError::NoSolution.into())
+}
+
 impl super::Runner for Day {
+    type Parsed<'a> = &'a str;
     type T = usize;
 
-    fn run(input: &str, basic: bool) -> Self::T {
-        if basic {
-            a::main(input)
-        } else {
-            b::main(input)
-        }
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(line: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        a::main(line)
+    }
+
+    fn part2(line: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        b::main(line)
     }
 }
@@ -1,53 +1,8 @@
-use std::{
-    collections::{HashMap, VecDeque},
-    fmt::{self},
-};
+use std::collections::{HashMap, VecDeque};
 
-enum Command {
-    Cd(String),
-    Ls,
-}
-
-enum ParsedLine {
-    Cmd(Command),
-    Output(Vec<String>),
-}
-
-#[derive(PartialEq, Eq)]
-enum LsOutput {
-    Dir(String),
-    File(i32, String),
-}
-
-impl fmt::Display for LsOutput {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            LsOutput::Dir(name) => write!(f, "Dir({})", name),
-            LsOutput::File(size, name) => {
-                write!(f, "File({}, {})", name, size)
-            }
-        }
-    }
-}
-
-#[derive(PartialEq, Eq)]
-enum ExecutedCommand {
-    Cd(String),
-    Ls(Vec<LsOutput>),
-}
+use crate::days::SolveError;
 
-impl fmt::Display for ExecutedCommand {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            ExecutedCommand::Cd(path) => write!(f, "cd {}", path),
-            ExecutedCommand::Ls(output) => {
-                let output_lines: Vec<String> =
-                    output.iter().map(|line| line.to_string()).collect();
-                write!(f, "ls -> {}", output_lines.join(", "))
-            }
-        }
-    }
-}
+use super::parser::{ExecutedCommand, LsOutput};
 
 /*
 enum FileSystem {
@@ -68,18 +23,29 @@ struct DirectoryData {
 }
 
 impl DirectoryData {
-    fn update_contents(&mut self, path: &[String], contents: HashMap<String, Box<FileSystem>>) {
+    fn update_contents(
+        &mut self,
+        path: &[String],
+        contents: HashMap<String, Box<FileSystem>>,
+    ) -> Result<(), SolveError> {
         if path.is_empty() {
             self.contents = contents;
+            Ok(())
         } else {
             let dirname = &path[0];
             let rest = &path[1..];
             match self.contents.get_mut(dirname) {
                 Some(entry) => match entry.as_mut() {
                     FileSystem::Dir(dir) => dir.update_contents(rest, contents),
-                    _ => panic!("Not a directory: {}", dirname),
+                    _ => Err(SolveError::unsupported(format!(
+                        "not a directory: {}",
+                        dirname
+                    ))),
                 },
-                None => panic!("Could not find: {}", dirname),
+                None => Err(SolveError::unsupported(format!(
+                    "directory {} was entered before it was listed",
+                    dirname
+                ))),
             }
         }
     }
@@ -114,9 +80,11 @@ struct FileData {
     size: i32,
 }
 
-fn infer_file_system(commands: Vec<ExecutedCommand>) -> FileSystem {
-    if commands[0] != ExecutedCommand::Cd("/".to_string()) {
-        panic!("you must start with 'cd /'");
+fn infer_file_system(commands: &[ExecutedCommand]) -> Result<FileSystem, SolveError> {
+    if commands.first() != Some(&ExecutedCommand::Cd("/".to_string())) {
+        Err(SolveError::unsupported(
+            "the terminal output must start with 'cd /'",
+        ))
     } else {
         let mut root = DirectoryData {
             contents: HashMap::new(),
@@ -127,12 +95,12 @@ fn infer_file_system(commands: Vec<ExecutedCommand>) -> FileSystem {
         for command in commands {
             match command {
                 ExecutedCommand::Cd(name) => {
-                    if name == *"/" {
+                    if name == "/" {
                         current_path.clear();
-                    } else if name == *".." {
+                    } else if name == ".." {
                         current_path.pop_back();
                     } else {
-                        current_path.push_back(name);
+                        current_path.push_back(name.clone());
                     }
                 }
                 ExecutedCommand::Ls(listing) => {
@@ -151,11 +119,11 @@ fn infer_file_system(commands: Vec<ExecutedCommand>) -> FileSystem {
                             ),
                         };
                     }
-                    root.update_contents(current_path.as_slices().0, contents)
+                    root.update_contents(current_path.make_contiguous(), contents)?
                 }
             }
         }
-        FileSystem::Dir(root)
+        Ok(FileSystem::Dir(root))
     }
 }
 
@@ -265,85 +233,11 @@ fn fun_name(
 }
 */
 
-fn parse_line(l: &str) -> Option<ParsedLine> {
-    let parts: Vec<String> = l.split(' ').map(|s| s.to_string()).collect();
-    if parts.is_empty() {
-        return None;
-    }
-    if parts[0] == "$" {
-        match parts[1].as_str() {
-            "cd" => Some(ParsedLine::Cmd(Command::Cd(parts[2].clone()))),
-            "ls" => Some(ParsedLine::Cmd(Command::Ls)),
-            _ => None,
-        }
-    } else {
-        Some(ParsedLine::Output(parts))
-    }
-}
-
-fn parse_ls_output_line(words: &[String]) -> LsOutput {
-    if words[0] == "dir" {
-        LsOutput::Dir(words[1].clone())
-    } else {
-        let file_size = words[0].parse::<i32>().unwrap();
-        LsOutput::File(file_size, words[1].clone())
-    }
-}
-
-fn make_executed_command(cmd: &Command, output: &[Vec<String>]) -> ExecutedCommand {
-    match cmd {
-        Command::Cd(path) => ExecutedCommand::Cd(path.clone()),
-        Command::Ls => {
-            let ls_output: Vec<LsOutput> = output
-                .iter()
-                .map(|words| parse_ls_output_line(words))
-                .collect();
-            ExecutedCommand::Ls(ls_output)
-        }
-    }
-}
-
-fn parse_input(input: &str) -> Vec<ExecutedCommand> {
-    let mut commands: Vec<ExecutedCommand> = Vec::new();
-    let mut last_cmd: Option<Command> = None;
-    let mut last_output: Vec<Vec<String>> = Vec::new();
-    
-    for l in input.lines() {
-        if l.is_empty() {
-            break;
-        }
-
-        match parse_line(l) {
-            Some(p) => match p {
-                ParsedLine::Cmd(new_cmd) => {
-                    match &last_cmd {
-                        Some(cmd) => commands.push(make_executed_command(cmd, &last_output)),
-                        None => (),
-                    }
-                    last_cmd = Some(new_cmd);
-                    last_output.clear();
-                }
-                ParsedLine::Output(words) => last_output.push(words),
-            },
-            None => panic!("Invalid input: {}", l),
-        }
-    }
-    match &last_cmd {
-        Some(cmd) => commands.push(make_executed_command(cmd, &last_output)),
-        None => (),
-    }
-    commands
-}
-
-pub fn main(input: &str) -> i32{
-    println!("Hello, world!");
-
-    let commands = parse_input(input);
-
-    let file_system = infer_file_system(commands);
+pub fn main(commands: &[ExecutedCommand]) -> Result<i32, SolveError> {
+    let file_system = infer_file_system(commands)?;
     let root = DirectoryData {
         contents: HashMap::from([("/".to_string(), Box::from(file_system))]),
     };
 
-    root.acc_size(100000).1
+    Ok(root.acc_size(100000).1)
 }
@@ -1,16 +1,24 @@
+use super::SolveError;
+
 mod a;
 mod b;
+mod parser;
 
 pub struct Day;
 
 impl super::Runner for Day {
+    type Parsed<'a> = Vec<parser::ExecutedCommand>;
     type T = i32;
 
-    fn run(input: &str, basic: bool) -> Self::T {
-        if basic {
-            a::main(input)
-        } else {
-            b::main(input)
-        }
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parser::parse_input(input)
+    }
+
+    fn part1(commands: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        a::main(commands)
+    }
+
+    fn part2(commands: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        b::main(commands)
     }
 }
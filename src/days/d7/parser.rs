@@ -0,0 +1,132 @@
+use std::fmt;
+
+use crate::days::SolveError;
+
+enum Command {
+    Cd(String),
+    Ls,
+}
+
+enum ParsedLine {
+    Cmd(Command),
+    Output(Vec<String>),
+}
+
+#[derive(PartialEq, Eq)]
+pub enum LsOutput {
+    Dir(String),
+    File(i32, String),
+}
+
+impl fmt::Display for LsOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LsOutput::Dir(name) => write!(f, "Dir({})", name),
+            LsOutput::File(size, name) => {
+                write!(f, "File({}, {})", name, size)
+            }
+        }
+    }
+}
+
+#[derive(PartialEq, Eq)]
+pub enum ExecutedCommand {
+    Cd(String),
+    Ls(Vec<LsOutput>),
+}
+
+impl fmt::Display for ExecutedCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecutedCommand::Cd(path) => write!(f, "cd {}", path),
+            ExecutedCommand::Ls(output) => {
+                let output_lines: Vec<String> =
+                    output.iter().map(|line| line.to_string()).collect();
+                write!(f, "ls -> {}", output_lines.join(", "))
+            }
+        }
+    }
+}
+
+fn parse_line(l: &str) -> Option<ParsedLine> {
+    let parts: Vec<String> = l.split(' ').map(|s| s.to_string()).collect();
+    if parts.is_empty() {
+        return None;
+    }
+    if parts[0] == "$" {
+        match parts.get(1)?.as_str() {
+            "cd" => Some(ParsedLine::Cmd(Command::Cd(parts.get(2)?.clone()))),
+            "ls" => Some(ParsedLine::Cmd(Command::Ls)),
+            _ => None,
+        }
+    } else {
+        Some(ParsedLine::Output(parts))
+    }
+}
+
+fn parse_ls_output_line(words: &[String]) -> Option<LsOutput> {
+    if words[0] == "dir" {
+        Some(LsOutput::Dir(words.get(1)?.clone()))
+    } else {
+        let file_size = words[0].parse::<i32>().ok()?;
+        Some(LsOutput::File(file_size, words.get(1)?.clone()))
+    }
+}
+
+/// `output` holds the words of every output line together with the index of that line
+fn make_executed_command(
+    cmd: &Command,
+    output: &[(usize, Vec<String>)],
+) -> Result<ExecutedCommand, SolveError> {
+    match cmd {
+        Command::Cd(path) => Ok(ExecutedCommand::Cd(path.clone())),
+        Command::Ls => {
+            let ls_output = output
+                .iter()
+                .map(|(idx, words)| {
+                    parse_ls_output_line(words).ok_or_else(|| {
+                        SolveError::parse(idx + 1, 1, "expected 'dir <name>' or '<size> <name>'")
+                    })
+                })
+                .collect::<Result<Vec<LsOutput>, SolveError>>()?;
+            Ok(ExecutedCommand::Ls(ls_output))
+        }
+    }
+}
+
+/// The commands of the terminal output with the listings of every `ls`
+pub fn parse_input(input: &str) -> Result<Vec<ExecutedCommand>, SolveError> {
+    let mut commands: Vec<ExecutedCommand> = Vec::new();
+    let mut last_cmd: Option<Command> = None;
+    let mut last_output: Vec<(usize, Vec<String>)> = Vec::new();
+
+    for (idx, l) in input.lines().enumerate() {
+        if l.is_empty() {
+            break;
+        }
+
+        match parse_line(l) {
+            Some(p) => match p {
+                ParsedLine::Cmd(new_cmd) => {
+                    if let Some(cmd) = &last_cmd {
+                        commands.push(make_executed_command(cmd, &last_output)?);
+                    }
+                    last_cmd = Some(new_cmd);
+                    last_output.clear();
+                }
+                ParsedLine::Output(words) => last_output.push((idx, words)),
+            },
+            None => {
+                return Err(SolveError::parse(
+                    idx + 1,
+                    1,
+                    "expected '$ cd <dir>' or '$ ls'",
+                ))
+            }
+        }
+    }
+    if let Some(cmd) = &last_cmd {
+        commands.push(make_executed_command(cmd, &last_output)?);
+    }
+    Ok(commands)
+}
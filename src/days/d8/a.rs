@@ -1,69 +1,33 @@
-pub fn main(input: &str) -> u32 {
-    println!("Hello, world!");
-
-    let mut grid: Vec<Vec<i8>> = Vec::new();
-
-    for line in input.lines() {
-        if line.is_empty() {
-            break;
-        }
-
-        grid.push(line.chars().map(|c| c as i8 - '0' as i8).collect());
-    }
-
-    let h = grid.len();
-    let w = grid[0].len();
-
-    let mut visible: Vec<bool> = vec![false; w * h];
-
-    for y in 0..h {
-        let mut top: i8 = -1;
-        for x in 0..w {
-            let here = grid[y][x];
-            if here > top {
-                top = here;
-                visible[y * w + x] = true;
-            }
-        }
-
-        top = -1;
-        for x in (0..w).rev() {
-            let here = grid[y][x];
-            if here > top {
-                top = here;
-                visible[y * w + x] = true;
-            }
+use crate::{
+    days::SolveError,
+    grid::{Grid, Pos},
+};
+
+/// Marks the trees taller than every tree before them in a line, looking from its start
+fn mark_visible<'a>(line: impl Iterator<Item = (usize, &'a i8)>, mut mark: impl FnMut(usize)) {
+    let mut top: i8 = -1;
+    for (i, &here) in line {
+        if here > top {
+            top = here;
+            mark(i);
         }
     }
+}
 
-    for x in 0..w {
-        let mut top: i8 = -1;
-        for y in 0..h {
-            let here = grid[y][x];
-            if here > top {
-                top = here;
-                visible[y * w + x] = true;
-            }
-        }
+pub fn main(grid: &Grid<i8>) -> Result<u32, SolveError> {
+    let mut visible = Grid::new(grid.width(), grid.height(), false);
 
-        top = -1;
-        for y in (0..h).rev() {
-            let here = grid[y][x];
-            if here > top {
-                top = here;
-                visible[y * w + x] = true;
-            }
-        }
+    for y in 0..grid.height() {
+        let row = grid.row(y).iter().enumerate();
+        mark_visible(row.clone(), |x| visible[Pos::new(x, y)] = true);
+        mark_visible(row.rev(), |x| visible[Pos::new(x, y)] = true);
     }
 
-    let mut count = 0;
-    for y in 0..h {
-        for x in 0..w {
-            if visible[y * w + x] {
-                count += 1;
-            }
-        }
+    for x in 0..grid.width() {
+        let column = grid.column(x).enumerate();
+        mark_visible(column.clone(), |y| visible[Pos::new(x, y)] = true);
+        mark_visible(column.rev(), |y| visible[Pos::new(x, y)] = true);
     }
 
-    count
+    Ok(visible.iter().filter(|&(_, &v)| v).count() as u32)
 }
@@ -1,16 +1,37 @@
+use super::SolveError;
+use crate::grid::Grid;
+
 mod a;
 mod b;
 
 pub struct Day;
 
+/// The heights of the trees, row by row
+fn parse_input(input: &str) -> Result<Grid<i8>, SolveError> {
+    let grid = Grid::parse(input, |c| match c.to_digit(10) {
+        Some(height) => Ok(height as i8),
+        None => Err(format!("invalid tree height: {}", c)),
+    })?;
+    if grid.is_empty() {
+        return Err(SolveError::parse(1, 1, "the grid is empty"));
+    }
+
+    Ok(grid)
+}
+
 impl super::Runner for Day {
+    type Parsed<'a> = Grid<i8>;
     type T = u32;
 
-    fn run(input: &str, basic: bool) -> Self::T {
-        if basic {
-            a::main(input)
-        } else {
-            b::main(input)
-        }
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(grid: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        a::main(grid)
+    }
+
+    fn part2(grid: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        b::main(grid)
     }
 }
@@ -1,54 +1,15 @@
-use std::collections::HashSet;
+use crate::{days::SolveError, grid::Point};
 
-enum Dir {
-    Up,
-    Down,
-    Left,
-    Right,
-}
+use super::{Dir, Motion, Rope};
 
-struct Motion {
-    dir: Dir,
-    steps: u32,
-}
+/// Moves the rope, showing `observe` where it is at the start and after each step of its head
+pub fn simulate(motions: &[Motion], mut observe: impl FnMut(&Rope)) -> Rope {
+    let mut rope = Rope::new(2);
 
-#[derive(Clone, PartialEq, Eq, Hash, Debug)]
-struct Pos {
-    x: i32,
-    y: i32,
-}
+    let mut head = Point::new(0, 0);
+    let mut tail = Point::new(0, 0);
 
-pub fn main(input: &str) -> usize {
-    println!("Hello, world!");
-
-    let mut motions: Vec<Motion> = Vec::new();
-
-    for line in input.lines() {
-        if line.is_empty() {
-            break;
-        }
-
-        if let Some((d, s)) = line.split_once(' ') {
-            let dir = match d {
-                "U" => Dir::Up,
-                "D" => Dir::Down,
-                "L" => Dir::Left,
-                "R" => Dir::Right,
-                _ => panic!("Unknown direction"),
-            };
-            motions.push(Motion {
-                dir,
-                steps: s.parse::<u32>().unwrap(),
-            });
-        }
-    }
-
-    let mut tail_history: Vec<Pos> = Vec::new();
-
-    let mut head: Pos = Pos { x: 0, y: 0 };
-    let mut tail: Pos = Pos { x: 0, y: 0 };
-
-    tail_history.push(tail.clone());
+    observe(&rope);
 
     for motion in motions {
         for _ in 0..motion.steps {
@@ -82,16 +43,18 @@ pub fn main(input: &str) -> usize {
                 tail.x += dx;
                 tail.y += dy;
 
-                tail_history.push(tail.clone());
+                rope.visited.insert(tail);
             }
+
+            rope.knots[0] = head;
+            rope.knots[1] = tail;
+            observe(&rope);
         }
     }
 
-    let mut unique_tail_positions: HashSet<Pos> = HashSet::new();
-
-    for pos in tail_history {
-        unique_tail_positions.insert(pos);
-    }
+    rope
+}
 
-    unique_tail_positions.len()
+pub fn main(motions: &[Motion]) -> Result<usize, SolveError> {
+    Ok(simulate(motions, |_| {}).visited.len())
 }
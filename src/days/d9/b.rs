@@ -1,25 +1,8 @@
-use std::collections::HashSet;
+use crate::{days::SolveError, grid::Point};
 
-#[derive(Clone, Copy)]
-enum Dir {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-struct Motion {
-    dir: Dir,
-    steps: u32,
-}
-
-#[derive(Clone, PartialEq, Eq, Hash, Debug, Copy)]
-struct Pos {
-    x: i32,
-    y: i32,
-}
+use super::{Dir, Motion, Rope};
 
-fn move_head(head: &mut Pos, dir: Dir) {
+fn move_head(head: &mut Point, dir: Dir) {
     match dir {
         Dir::Up => head.y -= 1,
         Dir::Down => head.y += 1,
@@ -28,7 +11,7 @@ fn move_head(head: &mut Pos, dir: Dir) {
     };
 }
 
-fn new_tail_pos(head: &Pos, tail: Pos) -> Pos {
+fn new_tail_pos(head: &Point, tail: Point) -> Point {
     let mut dx = head.x - tail.x;
     let mut dy = head.y - tail.y;
 
@@ -49,62 +32,33 @@ fn new_tail_pos(head: &Pos, tail: Pos) -> Pos {
             dy = 0;
         }
 
-        Pos {
-            x: tail.x + dx,
-            y: tail.y + dy,
-        }
+        Point::new(tail.x + dx, tail.y + dy)
     } else {
         tail
     }
 }
 
-pub fn main(input: &str) -> usize {
-    println!("Hello, world!");
-
-    let mut motions: Vec<Motion> = Vec::new();
-
-    for line in input.lines() {
-        if line.is_empty() {
-            break;
-        }
-
-        if let Some((d, s)) = line.split_once(' ') {
-            let dir = match d {
-                "U" => Dir::Up,
-                "D" => Dir::Down,
-                "L" => Dir::Left,
-                "R" => Dir::Right,
-                _ => panic!("Unknown direction"),
-            };
-            motions.push(Motion {
-                dir,
-                steps: s.parse::<u32>().unwrap(),
-            });
-        }
-    }
-
-    let mut knots: [Pos; 10] = [Pos { x: 0, y: 0 }; 10];
-
-    let mut tail_history: Vec<Pos> = Vec::new();
-    tail_history.push(knots[knots.len() - 1]);
+/// Moves the rope, showing `observe` where it is at the start and after each step of its head
+pub fn simulate(motions: &[Motion], mut observe: impl FnMut(&Rope)) -> Rope {
+    let mut rope = Rope::new(10);
+    observe(&rope);
 
     for motion in motions {
         for _ in 0..motion.steps {
-            move_head(&mut knots[0], motion.dir);
+            move_head(&mut rope.knots[0], motion.dir);
 
-            for i in 1..knots.len() {
-                knots[i] = new_tail_pos(&knots[i - 1], knots[i]);
+            for i in 1..rope.knots.len() {
+                rope.knots[i] = new_tail_pos(&rope.knots[i - 1], rope.knots[i]);
             }
 
-            tail_history.push(knots[knots.len() - 1]);
+            rope.visited.insert(rope.knots[rope.knots.len() - 1]);
+            observe(&rope);
         }
     }
 
-    let mut unique_tail_positions: HashSet<Pos> = HashSet::new();
-
-    for pos in tail_history {
-        unique_tail_positions.insert(pos);
-    }
+    rope
+}
 
-    unique_tail_positions.len()
+pub fn main(motions: &[Motion]) -> Result<usize, SolveError> {
+    Ok(simulate(motions, |_| {}).visited.len())
 }
@@ -1,16 +1,143 @@
+use std::collections::HashSet;
+
+use super::{Part, SolveError};
+use crate::{
+    grid::{Point, SparseGrid},
+    render::{Frame, Recorder, Render, Rgb},
+};
+
 mod a;
 mod b;
 
 pub struct Day;
 
+#[derive(Clone, Copy)]
+pub enum Dir {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+pub struct Motion {
+    dir: Dir,
+    steps: u32,
+}
+
+/// The knots of the rope from its head to its tail, and the tiles its tail has visited
+pub struct Rope {
+    pub knots: Vec<Point>,
+    pub visited: HashSet<Point>,
+}
+
+impl Rope {
+    fn new(knots: usize) -> Self {
+        let start = Point::new(0, 0);
+        Rope {
+            knots: vec![start; knots],
+            visited: HashSet::from([start]),
+        }
+    }
+
+    /// The knots in red over the visited tiles in grey, with the head in green
+    fn frame(&self) -> Frame {
+        let mut tiles: SparseGrid<Rgb> = self.visited.iter().map(|&p| (p, Rgb::GREY)).collect();
+        for &knot in &self.knots {
+            tiles.insert(knot, Rgb::RED);
+        }
+        tiles.insert(self.knots[0], Rgb::GREEN);
+        tiles.frame()
+    }
+}
+
+fn parse_input(input: &str) -> Result<Vec<Motion>, SolveError> {
+    let mut motions: Vec<Motion> = Vec::new();
+
+    for (idx, line) in input.lines().enumerate() {
+        if line.is_empty() {
+            break;
+        }
+
+        let Some((d, s)) = line.split_once(' ') else {
+            return Err(SolveError::parse(
+                idx + 1,
+                1,
+                "expected a direction and a number of steps",
+            ));
+        };
+        let dir = match d {
+            "U" => Dir::Up,
+            "D" => Dir::Down,
+            "L" => Dir::Left,
+            "R" => Dir::Right,
+            _ => {
+                return Err(SolveError::parse(
+                    idx + 1,
+                    1,
+                    format!("unknown direction: {}", d),
+                ))
+            }
+        };
+        let steps = s.parse::<u32>().map_err(|_| {
+            SolveError::parse(
+                idx + 1,
+                d.len() + 2,
+                format!("invalid number of steps: {}", s),
+            )
+        })?;
+        motions.push(Motion { dir, steps });
+    }
+
+    Ok(motions)
+}
+
+/// The rope after every step of its head
+pub fn animate(input: &str, part: Part, recorder: &mut Recorder) -> Result<(), SolveError> {
+    let motions = parse_input(input)?;
+    let mut observe = |rope: &Rope| recorder.step(|| rope.frame());
+    let rope = match part {
+        Part::Basic => a::simulate(&motions, &mut observe),
+        Part::Advanced => b::simulate(&motions, &mut observe),
+    };
+    recorder.end(|| rope.frame());
+    Ok(())
+}
+
 impl super::Runner for Day {
+    type Parsed<'a> = Vec<Motion>;
     type T = usize;
 
-    fn run(input: &str, basic: bool) -> Self::T {
-        if basic {
-            a::main(input)
-        } else {
-            b::main(input)
-        }
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(motions: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        a::main(motions)
+    }
+
+    fn part2(motions: &Self::Parsed<'_>) -> Result<Self::T, SolveError> {
+        b::main(motions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_step_of_the_head_is_a_frame() {
+        let mut recorder = Recorder::new(1, 100);
+        animate(include_str!("ex1a.txt"), Part::Basic, &mut recorder).unwrap();
+        // 24 steps after the start
+        assert_eq!(recorder.frames().len(), 25);
+
+        let last = &recorder.frames()[24];
+        let tiles = last.image.iter().filter(|(_, &c)| c != Rgb::BLACK).count();
+        // the tail visited 13 tiles, and the head ends on one of them
+        assert_eq!(tiles, 13);
+        assert_eq!(
+            last.image.iter().filter(|(_, &c)| c == Rgb::GREEN).count(),
+            1
+        );
     }
 }
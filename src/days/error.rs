@@ -0,0 +1,105 @@
+use std::fmt::Display;
+
+use crate::json::JsonObject;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// The input is malformed. Both `line` and `column` start at 1.
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The input is well-formed but has a shape the solver can't handle
+    Unsupported(String),
+    NoSolution,
+    /// The search was cancelled. `partial` is the answer found so far, which may not be the
+    /// best one, where the day can produce one, and `None` otherwise
+    TimedOut {
+        partial: Option<String>,
+    },
+}
+
+impl SolveError {
+    pub fn parse(line: usize, column: usize, message: impl Into<String>) -> SolveError {
+        SolveError::Parse {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    pub fn unsupported(message: impl Into<String>) -> SolveError {
+        SolveError::Unsupported(message.into())
+    }
+
+    pub fn timed_out(partial: Option<impl Display>) -> SolveError {
+        SolveError::TimedOut {
+            partial: partial.map(|answer| answer.to_string()),
+        }
+    }
+
+    /// The error as a JSON object with its `kind`, the details of that kind and a `message`
+    pub fn to_json(&self) -> JsonObject {
+        let obj = JsonObject::new();
+        match self {
+            SolveError::Parse { line, column, .. } => obj
+                .string("kind", "parse")
+                .number("line", line)
+                .number("column", column),
+            SolveError::Unsupported(_) => obj.string("kind", "unsupported"),
+            SolveError::NoSolution => obj.string("kind", "no_solution"),
+            SolveError::TimedOut { partial } => {
+                let obj = obj.string("kind", "timed_out");
+                match partial {
+                    Some(answer) => obj.string("partial", answer),
+                    None => obj.null("partial"),
+                }
+            }
+        }
+        .string("message", &self.to_string())
+    }
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "line {}, column {}: {}", line, column, message),
+            SolveError::Unsupported(message) => write!(f, "unsupported input: {}", message),
+            SolveError::NoSolution => write!(f, "no solution found"),
+            SolveError::TimedOut { partial: None } => write!(f, "timed out"),
+            SolveError::TimedOut {
+                partial: Some(answer),
+            } => write!(f, "timed out, the best answer so far is {}", answer),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_are_displayed_with_their_location() {
+        assert_eq!(
+            SolveError::parse(4, 23, "expected an integer").to_string(),
+            "line 4, column 23: expected an integer"
+        );
+        assert_eq!(
+            SolveError::unsupported("the map has no start").to_string(),
+            "unsupported input: the map has no start"
+        );
+        assert_eq!(SolveError::NoSolution.to_string(), "no solution found");
+        assert_eq!(
+            SolveError::timed_out(Some(1651)).to_string(),
+            "timed out, the best answer so far is 1651"
+        );
+        assert_eq!(SolveError::timed_out(None::<u32>).to_string(), "timed out");
+    }
+}
@@ -1,5 +1,7 @@
 use std::fmt::Display;
 
+use clap::ValueEnum;
+
 pub mod d1;
 pub mod d2;
 pub mod d3;
@@ -26,8 +28,79 @@ pub mod d23;
 pub mod d24;
 pub mod d25;
 
+mod cancel;
+mod error;
+pub mod progress;
+mod registry;
+pub mod stream;
+
+pub use cancel::CancelToken;
+pub use error::SolveError;
+pub use progress::{NoProgress, Progress};
+pub use registry::{
+    day, registry, solve_with_parsed, AnimateFn, DayInfo, ParseFn, ParsedInput, RenderFn, Solution,
+    SolveFn, StreamFn,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+pub enum Part {
+    #[value(alias = "b", alias = "1")]
+    Basic,
+
+    #[value(alias = "a", alias = "2")]
+    Advanced,
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::Basic => f.pad("basic"),
+            Part::Advanced => f.pad("advanced"),
+        }
+    }
+}
+
+/// A day solved in two phases: the input is parsed once and both parts work on the result.
 pub trait Runner {
+    /// The parsed input, which may borrow from the input text
+    type Parsed<'a>;
     type T: Display;
 
-    fn run(input: &str, basic: bool) -> Self::T;
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError>;
+
+    fn part1(parsed: &Self::Parsed<'_>) -> Result<Self::T, SolveError>;
+
+    fn part2(parsed: &Self::Parsed<'_>) -> Result<Self::T, SolveError>;
+
+    /// Solves part 1, reporting to `progress` and stopping with [`SolveError::TimedOut`] once
+    /// `cancel` is cancelled.
+    ///
+    /// Only the days with long-running searches use the token and the progress.
+    fn part1_with(
+        parsed: &Self::Parsed<'_>,
+        _cancel: &CancelToken,
+        _progress: &dyn Progress,
+    ) -> Result<Self::T, SolveError> {
+        Self::part1(parsed)
+    }
+
+    /// Solves part 2, reporting to `progress` and stopping with [`SolveError::TimedOut`] once
+    /// `cancel` is cancelled.
+    fn part2_with(
+        parsed: &Self::Parsed<'_>,
+        _cancel: &CancelToken,
+        _progress: &dyn Progress,
+    ) -> Result<Self::T, SolveError> {
+        Self::part2(parsed)
+    }
+
+    /// Parses the input and solves one of its parts.
+    fn run(input: &str, basic: bool) -> Result<Self::T, SolveError> {
+        let parsed = Self::parse(input)?;
+        if basic {
+            Self::part1(&parsed)
+        } else {
+            Self::part2(&parsed)
+        }
+    }
 }
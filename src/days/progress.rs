@@ -0,0 +1,21 @@
+/// Receives updates from a long-running search, so a slow run can be told apart from a stuck one.
+///
+/// The searches call these from their inner loops, but only every few thousand states.
+pub trait Progress: Sync {
+    /// `done` out of `total` independent pieces of work (like blueprints) are finished
+    fn items_done(&self, _done: usize, _total: usize) {}
+
+    /// The search has explored `states` states since it started
+    fn states_explored(&self, _states: u64) {}
+
+    /// The best answer, or bound on the answer, that the search has found so far
+    fn best_so_far(&self, _best: u64) {}
+}
+
+/// Ignores all updates.
+pub struct NoProgress;
+
+impl Progress for NoProgress {}
+
+/// How many states a search explores between two updates
+pub const STATES_PER_UPDATE: u64 = 1 << 16;
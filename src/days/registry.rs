@@ -0,0 +1,521 @@
+use std::{
+    io::BufRead,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+use super::{stream::StreamError, *};
+use crate::{
+    log::{self, Level, Span},
+    render::{Image, Recorder},
+};
+
+/// The answer to one part as text, with the time spent in each phase.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub answer: String,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+/// Parses the input of a day and solves one of its parts, giving up once the token is cancelled.
+pub type SolveFn = fn(&str, Part, &CancelToken, &dyn Progress) -> Result<Solution, SolveError>;
+
+/// The parsed input of a day, which can solve either part any number of times.
+pub trait ParsedInput {
+    fn solve(
+        &self,
+        part: Part,
+        cancel: &CancelToken,
+        progress: &dyn Progress,
+    ) -> Result<String, SolveError>;
+}
+
+/// Parses the input of a day once, for solving its parts separately (like the benchmarks do).
+pub type ParseFn = for<'a> fn(&'a str) -> Result<Box<dyn ParsedInput + 'a>, SolveError>;
+
+/// Parses the input of a day and draws the state it ends in after solving one of its parts.
+pub type RenderFn = fn(&str, Part) -> Result<Image, SolveError>;
+
+/// Parses the input of a day and shows the recorder every step of the simulation of one of its
+/// parts.
+pub type AnimateFn = fn(&str, Part, &mut Recorder) -> Result<(), SolveError>;
+
+/// Solves one part of a day reading its input one line at a time, in constant memory.
+pub type StreamFn = fn(&mut dyn BufRead, Part) -> Result<String, StreamError>;
+
+#[derive(Debug, Clone, Copy)]
+pub struct DayInfo {
+    pub number: u8,
+    pub title: &'static str,
+    /// What the basic and the advanced part compute, in that order
+    pub parts: [&'static str; 2],
+    /// The name of the Rust type of the answer, like `u32` or `String`
+    pub answer_type: &'static str,
+    pub solve: SolveFn,
+    pub parse: ParseFn,
+    /// For the days whose state is a map that can be drawn
+    pub render: Option<RenderFn>,
+    /// For the days that simulate their state step by step
+    pub animate: Option<AnimateFn>,
+    /// For the days whose input is a list of lines that can be solved one at a time
+    pub stream: Option<StreamFn>,
+}
+
+impl DayInfo {
+    pub fn part_name(&self, part: Part) -> &'static str {
+        match part {
+            Part::Basic => self.parts[0],
+            Part::Advanced => self.parts[1],
+        }
+    }
+
+    fn drawn_by(self, render: RenderFn) -> DayInfo {
+        DayInfo {
+            render: Some(render),
+            ..self
+        }
+    }
+
+    fn animated_by(self, animate: AnimateFn) -> DayInfo {
+        DayInfo {
+            animate: Some(animate),
+            ..self
+        }
+    }
+
+    fn streamed_by(self, stream: StreamFn) -> DayInfo {
+        DayInfo {
+            stream: Some(stream),
+            ..self
+        }
+    }
+}
+
+fn solve<R: Runner>(
+    input: &str,
+    part: Part,
+    cancel: &CancelToken,
+    progress: &dyn Progress,
+) -> Result<Solution, SolveError> {
+    solve_with_parsed::<R>(input, part, cancel, progress).map(|(solution, _)| solution)
+}
+
+/// Solves a part as the [`SolveFn`] of a day does, and also gives back what the input was parsed
+/// into, for the callers that show more of it than the answer
+pub fn solve_with_parsed<'a, R: Runner>(
+    input: &'a str,
+    part: Part,
+    cancel: &CancelToken,
+    progress: &dyn Progress,
+) -> Result<(Solution, R::Parsed<'a>), SolveError> {
+    let target = log::target(std::any::type_name::<R>());
+    let span = Span::enter(Level::Info, target, "parse");
+    let start = Instant::now();
+    let parsed = R::parse(input)?;
+    let parse_time = start.elapsed();
+    drop(span);
+
+    let _span = Span::enter(Level::Info, target, format_args!("solve {}", part));
+    let start = Instant::now();
+    let answer = match part {
+        Part::Basic => R::part1_with(&parsed, cancel, progress),
+        Part::Advanced => R::part2_with(&parsed, cancel, progress),
+    }?;
+    let solve_time = start.elapsed();
+
+    let solution = Solution {
+        answer: answer.to_string(),
+        parse_time,
+        solve_time,
+    };
+    Ok((solution, parsed))
+}
+
+struct Parsed<'a, R: Runner + 'a>(R::Parsed<'a>);
+
+impl<'a, R: Runner + 'a> ParsedInput for Parsed<'a, R> {
+    fn solve(
+        &self,
+        part: Part,
+        cancel: &CancelToken,
+        progress: &dyn Progress,
+    ) -> Result<String, SolveError> {
+        let answer = match part {
+            Part::Basic => R::part1_with(&self.0, cancel, progress),
+            Part::Advanced => R::part2_with(&self.0, cancel, progress),
+        }?;
+        Ok(answer.to_string())
+    }
+}
+
+fn parse<R: Runner + 'static>(input: &str) -> Result<Box<dyn ParsedInput + '_>, SolveError> {
+    Ok(Box::new(Parsed::<R>(R::parse(input)?)))
+}
+
+fn entry<R: Runner + 'static>(
+    _: R,
+    number: u8,
+    title: &'static str,
+    parts: [&'static str; 2],
+) -> DayInfo {
+    let type_name = std::any::type_name::<R::T>();
+    DayInfo {
+        number,
+        title,
+        parts,
+        answer_type: type_name.rsplit("::").next().unwrap_or(type_name),
+        solve: solve::<R>,
+        parse: parse::<R>,
+        render: None,
+        animate: None,
+        stream: None,
+    }
+}
+
+/// All days, ordered by their number.
+pub fn registry() -> &'static [DayInfo] {
+    static REGISTRY: OnceLock<Vec<DayInfo>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        vec![
+            entry(
+                d1::Day,
+                1,
+                "Calorie Counting",
+                [
+                    "calories carried by the top elf",
+                    "calories carried by the top three elves",
+                ],
+            )
+            .streamed_by(|reader, part| Ok(d1::run_reader(reader, part)?.to_string())),
+            entry(
+                d2::Day,
+                2,
+                "Rock Paper Scissors",
+                ["score when XYZ are shapes", "score when XYZ are outcomes"],
+            )
+            .streamed_by(|reader, part| Ok(d2::run_reader(reader, part)?.to_string())),
+            entry(
+                d3::Day,
+                3,
+                "Rucksack Reorganization",
+                [
+                    "priorities of the misplaced items",
+                    "priorities of the badges",
+                ],
+            )
+            .streamed_by(|reader, part| Ok(d3::run_reader(reader, part)?.to_string())),
+            entry(
+                d4::Day,
+                4,
+                "Camp Cleanup",
+                [
+                    "pairs where one range contains the other",
+                    "pairs with overlapping ranges",
+                ],
+            )
+            .streamed_by(|reader, part| Ok(d4::run_reader(reader, part)?.to_string())),
+            entry(
+                d5::Day,
+                5,
+                "Supply Stacks",
+                [
+                    "top crates after the CrateMover 9000",
+                    "top crates after the CrateMover 9001",
+                ],
+            ),
+            entry(
+                d6::Day,
+                6,
+                "Tuning Trouble",
+                [
+                    "end of the first start-of-packet marker",
+                    "end of the first start-of-message marker",
+                ],
+            )
+            .streamed_by(|reader, part| Ok(d6::run_reader(reader, part)?.to_string())),
+            entry(
+                d7::Day,
+                7,
+                "No Space Left On Device",
+                [
+                    "total size of the directories up to 100000",
+                    "size of the smallest directory to delete",
+                ],
+            ),
+            entry(
+                d8::Day,
+                8,
+                "Treetop Tree House",
+                [
+                    "trees visible from outside the grid",
+                    "highest scenic score",
+                ],
+            ),
+            entry(
+                d9::Day,
+                9,
+                "Rope Bridge",
+                [
+                    "positions visited by the tail of a 2-knot rope",
+                    "positions visited by the tail of a 10-knot rope",
+                ],
+            )
+            .animated_by(d9::animate),
+            entry(
+                d10::Day,
+                10,
+                "Cathode-Ray Tube",
+                ["sum of the signal strengths", "image on the CRT"],
+            ),
+            entry(
+                d11::Day,
+                11,
+                "Monkey in the Middle",
+                [
+                    "monkey business after 20 rounds",
+                    "monkey business after 10000 rounds",
+                ],
+            ),
+            entry(
+                d12::Day,
+                12,
+                "Hill Climbing Algorithm",
+                [
+                    "fewest steps from S",
+                    "fewest steps from any square at elevation a",
+                ],
+            ),
+            entry(
+                d13::Day,
+                13,
+                "Distress Signal",
+                [
+                    "sum of the indices of pairs in the right order",
+                    "decoder key",
+                ],
+            ),
+            entry(
+                d14::Day,
+                14,
+                "Regolith Reservoir",
+                [
+                    "sand at rest before it flows into the abyss",
+                    "sand at rest when the source is blocked",
+                ],
+            )
+            .drawn_by(d14::render)
+            .animated_by(d14::animate),
+            entry(
+                d15::Day,
+                15,
+                "Beacon Exclusion Zone",
+                [
+                    "positions in a row that cannot contain a beacon",
+                    "tuning frequency of the distress beacon",
+                ],
+            ),
+            entry(
+                d16::Day,
+                16,
+                "Proboscidea Volcanium",
+                [
+                    "most pressure released alone",
+                    "most pressure released with an elephant",
+                ],
+            ),
+            entry(
+                d17::Day,
+                17,
+                "Pyroclastic Flow",
+                [
+                    "tower height after 2022 rocks",
+                    "tower height after 1000000000000 rocks",
+                ],
+            )
+            .drawn_by(d17::render)
+            .animated_by(d17::animate),
+            entry(
+                d18::Day,
+                18,
+                "Boiling Boulders",
+                ["surface area", "exterior surface area"],
+            ),
+            entry(
+                d19::Day,
+                19,
+                "Not Enough Minerals",
+                [
+                    "sum of the quality levels",
+                    "product of the geodes of the first three blueprints",
+                ],
+            ),
+            entry(
+                d20::Day,
+                20,
+                "Grove Positioning System",
+                [
+                    "sum of the grove coordinates",
+                    "sum of the grove coordinates with the decryption key",
+                ],
+            ),
+            entry(
+                d21::Day,
+                21,
+                "Monkey Math",
+                [
+                    "number yelled by root",
+                    "number to yell to pass root's equality test",
+                ],
+            ),
+            entry(
+                d22::Day,
+                22,
+                "Monkey Map",
+                ["password on the flat map", "password on the cube"],
+            )
+            .drawn_by(d22::render),
+            entry(
+                d23::Day,
+                23,
+                "Unstable Diffusion",
+                [
+                    "empty ground tiles after 10 rounds",
+                    "first round where no elf moves",
+                ],
+            )
+            .drawn_by(d23::render)
+            .animated_by(d23::animate),
+            entry(
+                d24::Day,
+                24,
+                "Blizzard Basin",
+                [
+                    "minutes to reach the goal",
+                    "minutes to reach the goal, go back and reach it again",
+                ],
+            )
+            .drawn_by(d24::render)
+            .animated_by(d24::animate),
+            entry(
+                d25::Day,
+                25,
+                "Full of Hot Air",
+                [
+                    "SNAFU sum of the fuel requirements",
+                    "none (there is no part 2 on the last day)",
+                ],
+            )
+            .streamed_by(|reader, part| Ok(d25::run_reader(reader, part)?.to_string())),
+        ]
+    })
+}
+
+/// Looks up a day by its number.
+pub fn day(number: u8) -> Option<&'static DayInfo> {
+    registry().iter().find(|d| d.number == number)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::input::{example_path, Example, BUILD_INPUTS_DIR};
+
+    #[test]
+    fn registry_lists_every_day_in_order() {
+        let numbers: Vec<u8> = registry().iter().map(|d| d.number).collect();
+        assert_eq!(numbers, (1..=25).collect::<Vec<u8>>());
+        assert!(day(0).is_none());
+        assert!(day(26).is_none());
+    }
+
+    #[test]
+    fn registry_describes_the_answer_type() {
+        assert_eq!(day(1).unwrap().answer_type, "i32");
+        assert_eq!(day(5).unwrap().answer_type, "String");
+        assert_eq!(day(11).unwrap().answer_type, "u64");
+    }
+
+    #[test]
+    fn registry_solves_through_the_type_erased_function() {
+        let d1 = day(1).unwrap();
+        let input = include_str!("d1/ex1.txt");
+        let answer = |part| {
+            (d1.solve)(input, part, &CancelToken::new(), &NoProgress)
+                .map(|solution| solution.answer)
+        };
+        assert_eq!(answer(Part::Basic), Ok("24000".to_string()));
+        assert_eq!(answer(Part::Advanced), Ok("45000".to_string()));
+        assert_eq!(d1.part_name(Part::Basic), "calories carried by the top elf");
+    }
+
+    #[test]
+    fn the_parsed_input_can_be_kept_with_the_solution() {
+        let input = include_str!("d1/ex1.txt");
+        let (solution, ledger) =
+            solve_with_parsed::<d1::Day>(input, Part::Advanced, &CancelToken::new(), &NoProgress)
+                .unwrap();
+        assert_eq!(solution.answer, "45000");
+        assert_eq!(ledger.elves().len(), 5);
+    }
+
+    #[test]
+    fn streamed_days_answer_as_with_the_whole_input() {
+        let inputs_dir = Path::new(BUILD_INPUTS_DIR);
+        let streamed: Vec<_> = registry().iter().filter(|d| d.stream.is_some()).collect();
+        assert_eq!(streamed.len(), 6);
+        for d in streamed {
+            for (example, part) in [Example::Small, Example::Big]
+                .into_iter()
+                .flat_map(|example| [(example, Part::Basic), (example, Part::Advanced)])
+            {
+                let path = example_path(inputs_dir, d.number, example, part == Part::Basic);
+                let input = std::fs::read_to_string(path).unwrap();
+                let whole = (d.solve)(&input, part, &CancelToken::new(), &NoProgress)
+                    .map(|solution| solution.answer);
+                let stream = d.stream.unwrap();
+                let streamed = match stream(&mut input.as_bytes(), part) {
+                    Ok(answer) => Ok(answer),
+                    Err(StreamError::Solve(e)) => Err(e),
+                    Err(StreamError::Read(e)) => panic!("day {}: {}", d.number, e),
+                };
+                assert_eq!(streamed, whole, "day {} {:?} {}", d.number, example, part);
+            }
+        }
+    }
+
+    #[test]
+    fn streamed_days_report_errors_as_with_the_whole_input() {
+        for (number, input, part) in [
+            (1, "1\n\n2", Part::Advanced),
+            (1, "1\nx", Part::Basic),
+            (2, "A Y\nB Q", Part::Basic),
+            (3, "abcd\nab1d", Part::Basic),
+            (3, "abab\ncdcd", Part::Advanced),
+            (4, "2-4,6-8\n2-4", Part::Basic),
+            (6, "", Part::Basic),
+            (6, "abcdé", Part::Basic),
+            (6, "aaaa\r\n", Part::Advanced),
+            (25, "1=\n12x0", Part::Basic),
+        ] {
+            let d = day(number).unwrap();
+            let whole = (d.solve)(input, part, &CancelToken::new(), &NoProgress)
+                .map(|solution| solution.answer);
+            let streamed = match (d.stream.unwrap())(&mut input.as_bytes(), part) {
+                Err(StreamError::Solve(e)) => Err(e),
+                other => panic!("day {}: {:?}", number, other),
+            };
+            assert_eq!(streamed, whole, "day {} {:?}", number, input);
+        }
+    }
+
+    #[test]
+    fn a_parsed_input_solves_both_parts() {
+        let parsed = (day(1).unwrap().parse)(include_str!("d1/ex1.txt")).unwrap();
+        let answer = |part| parsed.solve(part, &CancelToken::new(), &NoProgress);
+        assert_eq!(answer(Part::Basic), Ok("24000".to_string()));
+        assert_eq!(answer(Part::Advanced), Ok("45000".to_string()));
+    }
+}
@@ -0,0 +1,87 @@
+//! Solving the days whose input is a list of lines one line at a time, so that inputs larger
+//! than memory can be solved.
+
+use std::{
+    fmt::Display,
+    io::{self, BufRead},
+};
+
+use super::SolveError;
+
+/// Why a streamed input could not be solved
+#[derive(Debug)]
+pub enum StreamError {
+    Read(io::Error),
+    Solve(SolveError),
+}
+
+impl From<io::Error> for StreamError {
+    fn from(e: io::Error) -> Self {
+        StreamError::Read(e)
+    }
+}
+
+impl From<SolveError> for StreamError {
+    fn from(e: SolveError) -> Self {
+        StreamError::Solve(e)
+    }
+}
+
+impl Display for StreamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamError::Read(e) => write!(f, "{}", e),
+            StreamError::Solve(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Calls `visit` with the index and the text of every line, without its line ending, as
+/// [`str::lines`] would, reusing one buffer for all of them.
+pub fn for_each_line<R: BufRead>(
+    mut reader: R,
+    mut visit: impl FnMut(usize, &str) -> Result<(), SolveError>,
+) -> Result<(), StreamError> {
+    let mut line = String::new();
+    for idx in 0.. {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        let text = match line.strip_suffix('\n') {
+            Some(text) => text.strip_suffix('\r').unwrap_or(text),
+            None => &line,
+        };
+        visit(idx, text)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_are_read_as_str_lines_reads_them() {
+        for input in ["", "a", "a\n", "a\r\nb\n\nc", "\n\n", "a\r"] {
+            let mut lines = Vec::new();
+            for_each_line(input.as_bytes(), |idx, line| {
+                lines.push((idx, line.to_string()));
+                Ok(())
+            })
+            .unwrap();
+            let expected: Vec<_> = input
+                .lines()
+                .enumerate()
+                .map(|(idx, line)| (idx, line.to_string()))
+                .collect();
+            assert_eq!(lines, expected);
+        }
+    }
+
+    #[test]
+    fn invalid_text_is_a_read_error() {
+        let result = for_each_line(&b"ok\n\xff\n"[..], |_, _| Ok(()));
+        assert!(matches!(result, Err(StreamError::Read(_))));
+    }
+}
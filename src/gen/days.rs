@@ -0,0 +1,912 @@
+//! One generator per day. Each one writes an input in the format of the puzzle and, unless its
+//! documentation says otherwise, makes sure the solvers can answer both parts of it whatever the
+//! seed and the size.
+
+use std::{collections::HashSet, fmt::Write};
+
+use super::Rng;
+
+/// Writes `lines` one per line
+fn lines(lines: impl IntoIterator<Item = String>) -> String {
+    let mut s = String::new();
+    for line in lines {
+        s.push_str(&line);
+        s.push('\n');
+    }
+    s
+}
+
+/// A name of at least `len` lowercase letters that is not in `taken`, which it is added to
+fn unique_word(rng: &mut Rng, mut len: usize, taken: &mut HashSet<String>) -> String {
+    loop {
+        let word = rng.word(len);
+        if taken.insert(word.clone()) {
+            return word;
+        }
+        // so running out of short names is not a problem
+        len += 1;
+    }
+}
+
+/// Calorie lists of at least three elves, as part 2 sums the top three
+pub fn d1(rng: &mut Rng, size: usize) -> String {
+    let elves = (0..size.max(3)).map(|_| {
+        let items = rng.range(1, 6);
+        (0..items)
+            .map(|_| rng.range(1000, 60000).to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+    elves.collect::<Vec<_>>().join("\n\n") + "\n"
+}
+
+pub fn d2(rng: &mut Rng, size: usize) -> String {
+    lines((0..size.max(1)).map(|_| {
+        format!(
+            "{} {}",
+            rng.choose(&['A', 'B', 'C']),
+            rng.choose(&['X', 'Y', 'Z'])
+        )
+    }))
+}
+
+/// Every rucksack has exactly one item in both compartments and every group exactly one badge,
+/// because each elf of a group packs from its own third of the other item types.
+pub fn d3(rng: &mut Rng, size: usize) -> String {
+    let mut items: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+    let mut rucksacks = Vec::new();
+    for _ in 0..size.max(1) {
+        rng.shuffle(&mut items);
+        let badge = items[0];
+        for private in items[1..].chunks(17) {
+            let half = rng.range(2, 9) as usize;
+            let shared = private[0];
+            let mut first: Vec<char> = private[1..half].to_vec();
+            let mut second: Vec<char> = private[half..(2 * half - 1)].to_vec();
+            let with_badge = if rng.chance(1, 2) {
+                &mut first
+            } else {
+                &mut second
+            };
+            with_badge[0] = badge;
+            first.push(shared);
+            second.push(shared);
+            rng.shuffle(&mut first);
+            rng.shuffle(&mut second);
+            rucksacks.push(first.into_iter().chain(second).collect());
+        }
+    }
+    lines(rucksacks)
+}
+
+pub fn d4(rng: &mut Rng, size: usize) -> String {
+    lines((0..size.max(1)).map(|_| {
+        let mut range = || {
+            let start = rng.range(1, 90);
+            (start, start + rng.range(0, 9))
+        };
+        let (a, b) = range();
+        let (c, d) = range();
+        format!("{}-{},{}-{}", a, b, c, d)
+    }))
+}
+
+/// Only moves crates from stacks that hold enough of them
+pub fn d5(rng: &mut Rng, size: usize) -> String {
+    let stack_count = rng.range(3, 9) as usize;
+    let mut stacks: Vec<Vec<char>> = (0..stack_count)
+        .map(|_| {
+            (0..rng.range(1, 8))
+                .map(|_| (b'A' + rng.below(26) as u8) as char)
+                .collect()
+        })
+        .collect();
+
+    let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+    let mut drawing: Vec<String> = (0..height)
+        .rev()
+        .map(|level| {
+            stacks
+                .iter()
+                .map(|s| match s.get(level) {
+                    Some(c) => format!("[{}]", c),
+                    None => "   ".to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect();
+    drawing.push(
+        (1..=stack_count)
+            .map(|i| format!(" {} ", i))
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+
+    let mut moves = Vec::new();
+    for _ in 0..size {
+        let non_empty: Vec<usize> = (0..stack_count)
+            .filter(|&i| !stacks[i].is_empty())
+            .collect();
+        let from = *rng.choose(&non_empty);
+        let mut to = rng.below(stack_count - 1);
+        if to >= from {
+            to += 1;
+        }
+        let count = rng.range(1, stacks[from].len() as i64) as usize;
+        let height = stacks[from].len();
+        let moved = stacks[from].split_off(height - count);
+        stacks[to].extend(moved);
+        moves.push(format!("move {} from {} to {}", count, from + 1, to + 1));
+    }
+
+    lines(drawing) + "\n" + &lines(moves)
+}
+
+/// No four characters in a row differ before the last 14, which all differ
+pub fn d6(rng: &mut Rng, size: usize) -> String {
+    let mut letters: Vec<char> = ('a'..='z').collect();
+    rng.shuffle(&mut letters);
+    let (few, rest) = letters.split_at(3);
+    let mut stream: String = (0..size).map(|_| *rng.choose(few)).collect();
+    stream.extend(&rest[..14]);
+    stream.extend((0..size / 4).map(|_| *rng.choose(&letters)));
+    stream + "\n"
+}
+
+struct Directory {
+    name: String,
+    files: Vec<(String, i64)>,
+    children: Vec<usize>,
+}
+
+fn write_listing(dirs: &[Directory], i: usize, out: &mut String) {
+    let dir = &dirs[i];
+    out.push_str("$ ls\n");
+    for &child in &dir.children {
+        let _ = writeln!(out, "dir {}", dirs[child].name);
+    }
+    for (name, size) in &dir.files {
+        let _ = writeln!(out, "{} {}", size, name);
+    }
+    for &child in &dir.children {
+        let _ = writeln!(out, "$ cd {}", dirs[child].name);
+        write_listing(dirs, child, out);
+        out.push_str("$ cd ..\n");
+    }
+}
+
+/// Lists every directory before entering it. The file sizes shrink with the number of
+/// directories so the total size fits the 32 bits the solver adds them in.
+pub fn d7(rng: &mut Rng, size: usize) -> String {
+    let max_file_size = (200_000_000 / (4 * size.max(1)) as i64).clamp(1000, 300_000);
+    let mut dirs: Vec<Directory> = Vec::new();
+    let mut names: Vec<HashSet<String>> = Vec::new();
+    for i in 0..size.max(1) {
+        let name = if i > 0 {
+            let parent = rng.below(i);
+            let len = rng.range(1, 8) as usize;
+            dirs[parent].children.push(i);
+            unique_word(rng, len, &mut names[parent])
+        } else {
+            "/".to_string()
+        };
+        dirs.push(Directory {
+            name,
+            files: Vec::new(),
+            children: Vec::new(),
+        });
+        names.push(HashSet::new());
+    }
+    for i in 0..dirs.len() {
+        for _ in 0..rng.range(0, 4) {
+            let len = rng.range(1, 8) as usize;
+            let mut name = unique_word(rng, len, &mut names[i]);
+            if rng.chance(1, 2) {
+                name = format!("{}.{}", name, rng.word(3));
+                names[i].insert(name.clone());
+            }
+            let size = rng.range(1, max_file_size);
+            dirs[i].files.push((name, size));
+        }
+    }
+
+    let mut out = "$ cd /\n".to_string();
+    write_listing(&dirs, 0, &mut out);
+    out
+}
+
+pub fn d8(rng: &mut Rng, size: usize) -> String {
+    let side = size.max(1);
+    lines((0..side).map(|_| {
+        (0..side)
+            .map(|_| (b'0' + rng.below(10) as u8) as char)
+            .collect()
+    }))
+}
+
+pub fn d9(rng: &mut Rng, size: usize) -> String {
+    lines(
+        (0..size.max(1))
+            .map(|_| format!("{} {}", rng.choose(&["U", "D", "L", "R"]), rng.range(1, 9))),
+    )
+}
+
+/// Runs for exactly `size` cycles, as the CRT of part 2 has no more than 240 pixels
+pub fn d10(rng: &mut Rng, size: usize) -> String {
+    let mut cycles = size.clamp(1, 240);
+    let mut x = 1;
+    let mut program = Vec::new();
+    while cycles > 0 {
+        if cycles >= 2 && rng.chance(2, 3) {
+            let v = rng.range((-1 - x).max(-15), (40 - x).min(15));
+            x += v;
+            program.push(format!("addx {}", v));
+            cycles -= 2;
+        } else {
+            program.push("noop".to_string());
+            cycles -= 1;
+        }
+    }
+    lines(program)
+}
+
+struct Monkey {
+    items: Vec<i64>,
+    /// `None` for `old * old`
+    operand: Option<i64>,
+    multiplies: bool,
+    divisor: i64,
+    targets: (usize, usize),
+}
+
+impl Monkey {
+    fn inspect(&self, old: i64) -> i64 {
+        let operand = self.operand.unwrap_or(old);
+        if self.multiplies {
+            old * operand
+        } else {
+            old + operand
+        }
+    }
+}
+
+/// Whether the worry levels of part 1 stay within the 32 bits the solver keeps them in
+fn fits_part_1(monkeys: &[Monkey]) -> bool {
+    let mut items: Vec<Vec<i64>> = monkeys.iter().map(|m| m.items.clone()).collect();
+    for _ in 0..20 {
+        for (i, m) in monkeys.iter().enumerate() {
+            for old in std::mem::take(&mut items[i]) {
+                let new = m.inspect(old);
+                if new > i32::MAX as i64 {
+                    return false;
+                }
+                let new = new / 3;
+                let target = if new % m.divisor == 0 {
+                    m.targets.0
+                } else {
+                    m.targets.1
+                };
+                items[target].push(new);
+            }
+        }
+    }
+    true
+}
+
+/// The divisors are primes, so their product stays below 2^31 for part 2. Inputs whose worry
+/// levels overflow in part 1 are drawn again, with fewer multiplications every few times as
+/// additions alone always keep the levels low.
+pub fn d11(rng: &mut Rng, size: usize) -> String {
+    const PRIMES: [i64; 9] = [2, 3, 5, 7, 11, 13, 17, 19, 23];
+    let count = size.max(2);
+    let mut attempt = 0;
+    let monkeys = loop {
+        let mut primes = PRIMES;
+        rng.shuffle(&mut primes);
+        // like in the puzzle, a single monkey squares the worry levels
+        let squarer = if attempt < 10 {
+            Some(rng.below(count))
+        } else {
+            None
+        };
+        let multiplier_odds = if attempt < 20 { 1 } else { 0 };
+        attempt += 1;
+        let monkeys: Vec<Monkey> = (0..count)
+            .map(|i| {
+                let mut other = || {
+                    let j = rng.below(count - 1);
+                    if j >= i {
+                        j + 1
+                    } else {
+                        j
+                    }
+                };
+                let yes = other();
+                let mut no = other();
+                while count > 2 && no == yes {
+                    no = other();
+                }
+                let (operand, multiplies) = if squarer == Some(i) {
+                    (None, true)
+                } else if rng.chance(multiplier_odds, 4) {
+                    (Some(rng.range(2, 19)), true)
+                } else {
+                    (Some(rng.range(1, 8)), false)
+                };
+                Monkey {
+                    items: (0..rng.range(1, 6)).map(|_| rng.range(50, 99)).collect(),
+                    operand,
+                    multiplies,
+                    divisor: primes[i % primes.len()],
+                    targets: (yes, no),
+                }
+            })
+            .collect();
+        if fits_part_1(&monkeys) {
+            break monkeys;
+        }
+    };
+
+    let blocks: Vec<String> = monkeys
+        .iter()
+        .enumerate()
+        .map(|(i, m)| {
+            let items: Vec<String> = m.items.iter().map(|i| i.to_string()).collect();
+            let operand = m.operand.map_or("old".to_string(), |n| n.to_string());
+            format!(
+                "Monkey {}:\n  Starting items: {}\n  Operation: new = old {} {}\n  \
+                 Test: divisible by {}\n    If true: throw to monkey {}\n    \
+                 If false: throw to monkey {}\n",
+                i,
+                items.join(", "),
+                if m.multiplies { '*' } else { '+' },
+                operand,
+                m.divisor,
+                m.targets.0,
+                m.targets.1
+            )
+        })
+        .collect();
+    blocks.join("\n")
+}
+
+/// A trail from S to E climbs one square at a time along every other row, snaking from side to
+/// side. The squares off the trail are random.
+pub fn d12(rng: &mut Rng, size: usize) -> String {
+    let rows = size.max(1);
+    let trail_rows = rows.div_ceil(2);
+    let mut width = (rows * 8 / 5).max(2);
+    while trail_rows * width + trail_rows - 1 < 26 {
+        width += 1;
+    }
+
+    let mut trail = Vec::new();
+    for r in 0..trail_rows {
+        let y = 2 * r;
+        let xs: Vec<usize> = if r % 2 == 0 {
+            (0..width).collect()
+        } else {
+            (0..width).rev().collect()
+        };
+        trail.extend(xs.iter().map(|&x| (x, y)));
+        if r + 1 < trail_rows {
+            trail.push((*xs.last().unwrap(), y + 1));
+        }
+    }
+
+    let mut grid: Vec<Vec<u8>> = (0..rows)
+        .map(|_| (0..width).map(|_| b'a' + rng.below(26) as u8).collect())
+        .collect();
+    // the climb needs 25 steps up, which are spread at random along the trail
+    let mut steps = vec![false; trail.len() - 1];
+    for step in steps.iter_mut().take(25) {
+        *step = true;
+    }
+    rng.shuffle(&mut steps);
+    let mut height = b'a';
+    for (i, &(x, y)) in trail.iter().enumerate() {
+        if i > 0 && steps[i - 1] {
+            height += 1;
+        }
+        grid[y][x] = height;
+    }
+    let (sx, sy) = trail[0];
+    let (ex, ey) = trail[trail.len() - 1];
+    grid[sy][sx] = b'S';
+    grid[ey][ex] = b'E';
+
+    lines(grid.into_iter().map(|row| String::from_utf8(row).unwrap()))
+}
+
+fn packet(rng: &mut Rng, depth: usize, out: &mut String) {
+    out.push('[');
+    for i in 0..rng.range(0, 4) {
+        if i > 0 {
+            out.push(',');
+        }
+        if depth < 3 && rng.chance(1, 3) {
+            packet(rng, depth + 1, out);
+        } else {
+            let _ = write!(out, "{}", rng.range(0, 10));
+        }
+    }
+    out.push(']');
+}
+
+pub fn d13(rng: &mut Rng, size: usize) -> String {
+    let pairs: Vec<String> = (0..size.max(1))
+        .map(|_| {
+            let mut pair = String::new();
+            packet(rng, 0, &mut pair);
+            pair.push('\n');
+            packet(rng, 0, &mut pair);
+            pair.push('\n');
+            pair
+        })
+        .collect();
+    pairs.join("\n")
+}
+
+/// Rock between two walls that are `half_width` from the sand source, and deeper than that, so
+/// the sand of part 1 cannot pile up to the source and the floor of part 2 is wide enough
+pub fn d14(rng: &mut Rng, size: usize) -> String {
+    let half_width = 10 + 2 * size as i64;
+    let (left, right) = (500 - half_width, 500 + half_width);
+    let top = half_width + 3;
+    let bottom = top + 10 + 2 * size as i64;
+
+    let mut paths = Vec::new();
+    for x in [left, right] {
+        paths.push(format!("{},{} -> {},{}", x, top, x, rng.range(top, bottom)));
+    }
+    for _ in 2..size {
+        let mut x = rng.range(left, right);
+        let mut y = rng.range(top, bottom);
+        let mut points = vec![format!("{},{}", x, y)];
+        for segment in 0..rng.range(1, 4) {
+            let length = rng.range(1, 6);
+            if segment % 2 == 0 {
+                x = (x + if rng.chance(1, 2) { -length } else { length }).clamp(left, right);
+            } else {
+                y = (y + length).min(bottom);
+            }
+            points.push(format!("{},{}", x, y));
+        }
+        paths.push(points.join(" -> "));
+    }
+    rng.shuffle(&mut paths);
+    lines(paths)
+}
+
+/// A point at the Manhattan distance `range` from (x, y)
+fn point_at(rng: &mut Rng, (x, y): (i64, i64), range: i64) -> (i64, i64) {
+    let dx = rng.range(-range, range);
+    let dy = range - dx.abs();
+    (x + dx, if rng.chance(1, 2) { y + dy } else { y - dy })
+}
+
+/// Four sensors on the diagonals around a hidden beacon cover the whole search area but for it,
+/// and the others never reach it. The solver searches 20x20 squares for up to 14 sensors and
+/// 4000000x4000000 ones for more.
+pub fn d15(rng: &mut Rng, size: usize) -> String {
+    let count = size.max(4);
+    let width: i64 = if count > 14 { 4_000_000 } else { 20 };
+    let hole = (rng.range(1, width - 1), rng.range(1, width - 1));
+
+    let mut readings: Vec<((i64, i64), (i64, i64))> = [(1, 1), (1, -1), (-1, 1), (-1, -1)]
+        .into_iter()
+        .map(|(sx, sy)| {
+            let sensor = (hole.0 + sx * width, hole.1 + sy * width);
+            (sensor, point_at(rng, sensor, 2 * width - 1))
+        })
+        .collect();
+    while readings.len() < count {
+        let sensor = (rng.range(0, width), rng.range(0, width));
+        let dist = (sensor.0 - hole.0).abs() + (sensor.1 - hole.1).abs();
+        if dist < 2 {
+            continue;
+        }
+        let range = rng.range(1, dist - 1);
+        readings.push((sensor, point_at(rng, sensor, range)));
+    }
+    rng.shuffle(&mut readings);
+
+    lines(readings.into_iter().map(|((sx, sy), (bx, by))| {
+        format!(
+            "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+            sx, sy, bx, by
+        )
+    }))
+}
+
+fn valve_name(i: usize) -> String {
+    let letter = |n: usize| (b'A' + n as u8) as char;
+    format!("{}{}", letter(i / 26 % 26), letter(i % 26))
+}
+
+/// A connected network where about half the valves have a flow. The search takes time
+/// exponential in the number of those, so sizes past 20 can take minutes.
+pub fn d16(rng: &mut Rng, size: usize) -> String {
+    let count = size.clamp(2, 26 * 26);
+    let mut names: Vec<String> = (1..26 * 26).map(valve_name).collect();
+    rng.shuffle(&mut names);
+    names.truncate(count - 1);
+    names.insert(0, valve_name(0));
+
+    let mut edges: Vec<Vec<usize>> = vec![Vec::new(); count];
+    let mut connect = |a: usize, b: usize| {
+        if a != b && !edges[a].contains(&b) {
+            edges[a].push(b);
+            edges[b].push(a);
+        }
+    };
+    for i in 1..count {
+        connect(i, rng.below(i));
+    }
+    for _ in 0..count / 3 {
+        connect(rng.below(count), rng.below(count));
+    }
+
+    let mut valves: Vec<String> = (0..count)
+        .map(|i| {
+            let rate = if i > 0 && rng.chance(1, 2) {
+                rng.range(1, 25)
+            } else {
+                0
+            };
+            let tunnels: Vec<&str> = edges[i].iter().map(|&j| names[j].as_str()).collect();
+            let (lead, valve) = if tunnels.len() == 1 {
+                ("tunnel leads", "valve")
+            } else {
+                ("tunnels lead", "valves")
+            };
+            format!(
+                "Valve {} has flow rate={}; {} to {} {}",
+                names[i],
+                rate,
+                lead,
+                valve,
+                tunnels.join(", ")
+            )
+        })
+        .collect();
+    rng.shuffle(&mut valves);
+    lines(valves)
+}
+
+/// Runs of up to five jets that alternate in direction, as a pattern that drifts to one side
+/// leaves a column empty and the solver never finds the cycle of the tower. It still misses it
+/// for a few patterns of less than a few hundred jets, and runs out of memory.
+pub fn d17(rng: &mut Rng, size: usize) -> String {
+    let mut jets = String::new();
+    let mut dir = *rng.choose(&['<', '>']);
+    while jets.len() < size.max(10) {
+        jets.extend((0..rng.range(1, 5)).map(|_| dir));
+        dir = if dir == '<' { '>' } else { '<' };
+    }
+    jets + "\n"
+}
+
+/// Distinct cubes packed in a box about twice their number in volume, so there are pockets
+pub fn d18(rng: &mut Rng, size: usize) -> String {
+    let count = size.max(1);
+    let side = ((2 * count) as f64).cbrt().ceil() as i64 + 1;
+    let mut cubes = HashSet::new();
+    let mut ordered = Vec::new();
+    while ordered.len() < count {
+        let cube = (rng.range(1, side), rng.range(1, side), rng.range(1, side));
+        if cubes.insert(cube) {
+            ordered.push(cube);
+        }
+    }
+    lines(
+        ordered
+            .into_iter()
+            .map(|(x, y, z)| format!("{},{},{}", x, y, z)),
+    )
+}
+
+/// Costs in the ranges of the puzzle. The blueprint ids are bytes, so there are at most 255.
+pub fn d19(rng: &mut Rng, size: usize) -> String {
+    lines((1..=size.clamp(1, 255)).map(|id| {
+        format!(
+            "Blueprint {}: Each ore robot costs {} ore. Each clay robot costs {} ore. \
+             Each obsidian robot costs {} ore and {} clay. \
+             Each geode robot costs {} ore and {} obsidian.",
+            id,
+            rng.range(2, 4),
+            rng.range(2, 4),
+            rng.range(2, 4),
+            rng.range(5, 20),
+            rng.range(2, 4),
+            rng.range(5, 20)
+        )
+    }))
+}
+
+/// Exactly one 0 among numbers that may repeat, like in the puzzle
+pub fn d20(rng: &mut Rng, size: usize) -> String {
+    let count = size.max(2);
+    let zero = rng.below(count);
+    lines((0..count).map(|i| {
+        if i == zero {
+            "0".to_string()
+        } else {
+            let n = rng.range(1, 10000);
+            (if rng.chance(1, 2) { -n } else { n }).to_string()
+        }
+    }))
+}
+
+/// Monkeys that yell `value` from a few operations on numbers up to 20
+fn yell(
+    rng: &mut Rng,
+    value: i64,
+    depth: usize,
+    names: &mut HashSet<String>,
+    jobs: &mut Vec<(String, String)>,
+) -> String {
+    let name = unique_word(rng, 4, names);
+    let job = if depth == 0 || value <= 2 {
+        value.to_string()
+    } else {
+        let divisor = (2..=5).find(|d| value % d == 0 && value / d > 1);
+        let (l, op, r) = match (rng.below(4), divisor) {
+            (0, _) => {
+                let l = rng.range(1, value - 1);
+                (l, '+', value - l)
+            }
+            (1, _) => {
+                let r = rng.range(1, 20);
+                (value + r, '-', r)
+            }
+            (2, Some(d)) => (value / d, '*', d),
+            _ => {
+                let r = rng.range(2, 4);
+                (value * r, '/', r)
+            }
+        };
+        let l = yell(rng, l, depth - 1, names, jobs);
+        let r = yell(rng, r, depth - 1, names, jobs);
+        format!("{} {} {}", l, op, r)
+    };
+    jobs.push((name.clone(), job));
+    name
+}
+
+/// A chain of `size` operations between humn and root, each with a constant side, built
+/// backwards from the answers of both parts so that every division is exact.
+pub fn d21(rng: &mut Rng, size: usize) -> String {
+    let mut names: HashSet<String> = ["root", "humn"].map(String::from).into();
+    let mut jobs: Vec<(String, String)> = Vec::new();
+
+    // the values yelled along the chain with the number of part 1 and with the answer of part 2
+    let first = rng.range(1, 1000);
+    let answer = rng.range(1, 10000);
+    jobs.push(("humn".to_string(), first.to_string()));
+    let (mut basic, mut advanced) = (first, answer);
+    let mut below = "humn".to_string();
+    for _ in 0..size.max(1) {
+        let c = rng.range(2, 20);
+        let constant = yell(rng, c, 2, &mut names, &mut jobs);
+        let name = unique_word(rng, 4, &mut names);
+        let small = basic.abs().max(advanced.abs()) < 1_000_000_000;
+        let job = match rng.below(5) {
+            0 if basic % c == 0 && advanced % c == 0 => {
+                (basic, advanced) = (basic / c, advanced / c);
+                format!("{} / {}", below, constant)
+            }
+            1 if small => {
+                (basic, advanced) = (basic * c, advanced * c);
+                format!("{} * {}", constant, below)
+            }
+            2 => {
+                (basic, advanced) = (c - basic, c - advanced);
+                format!("{} - {}", constant, below)
+            }
+            3 => {
+                (basic, advanced) = (basic - c, advanced - c);
+                format!("{} - {}", below, constant)
+            }
+            _ => {
+                (basic, advanced) = (basic + c, advanced + c);
+                format!("{} + {}", below, constant)
+            }
+        };
+        jobs.push((name.clone(), job));
+        below = name;
+    }
+
+    // root compares the chain to monkeys that yell what the chain yells with the answer
+    let other = if advanced >= 1 {
+        yell(rng, advanced, 3, &mut names, &mut jobs)
+    } else {
+        let plus = yell(rng, 1, 3, &mut names, &mut jobs);
+        let minus = yell(rng, 1 - advanced, 3, &mut names, &mut jobs);
+        let name = unique_word(rng, 4, &mut names);
+        jobs.push((name.clone(), format!("{} - {}", plus, minus)));
+        name
+    };
+    jobs.push(("root".to_string(), format!("{} + {}", below, other)));
+
+    rng.shuffle(&mut jobs);
+    lines(
+        jobs.into_iter()
+            .map(|(name, job)| format!("{}: {}", name, job)),
+    )
+}
+
+/// The solver only knows how to fold the layouts of the two examples, and the layout of the small
+/// one only with faces of 4 tiles, so smaller sizes give that layout and larger ones the other.
+pub fn d22(rng: &mut Rng, size: usize) -> String {
+    const SMALL: [(usize, usize); 6] = [(2, 0), (0, 1), (1, 1), (2, 1), (2, 2), (3, 2)];
+    const BIG: [(usize, usize); 6] = [(1, 0), (2, 0), (1, 1), (0, 2), (1, 2), (0, 3)];
+    let (side, faces) = if size <= 4 { (4, SMALL) } else { (size, BIG) };
+
+    let rows = 4 * side;
+    let mut board: Vec<Vec<u8>> = vec![Vec::new(); rows];
+    for (y, row) in board.iter_mut().enumerate() {
+        let width = faces
+            .iter()
+            .filter(|f| f.1 == y / side)
+            .map(|f| (f.0 + 1) * side)
+            .max()
+            .unwrap_or(0);
+        for x in 0..width {
+            let on_face = faces.contains(&(x / side, y / side));
+            row.push(match on_face {
+                false => b' ',
+                true if rng.chance(1, 10) => b'#',
+                true => b'.',
+            });
+        }
+    }
+    // the path starts on the leftmost tile of the top row
+    let start = board[0].iter().position(|&c| c != b' ').unwrap();
+    board[0][start] = b'.';
+    board.retain(|row| !row.is_empty());
+
+    let mut path = String::new();
+    for i in 0..(2 * side).max(8) {
+        if i > 0 {
+            path.push(*rng.choose(&['L', 'R']));
+        }
+        let _ = write!(path, "{}", rng.range(1, 2 * side as i64));
+    }
+
+    lines(board.into_iter().map(|row| String::from_utf8(row).unwrap())) + "\n" + &path + "\n"
+}
+
+pub fn d23(rng: &mut Rng, size: usize) -> String {
+    let side = size.max(1);
+    lines((0..side).map(|_| {
+        (0..side)
+            .map(|_| if rng.chance(1, 3) { '#' } else { '.' })
+            .collect()
+    }))
+}
+
+/// Whether a blizzard is at (x, y) of the inside of `valley` after `t` minutes
+fn is_windy(valley: &[Vec<u8>], x: usize, y: usize, t: usize) -> bool {
+    let (width, height) = (valley[0].len(), valley.len());
+    valley[y][(x + width - t % width) % width] == b'>'
+        || valley[y][(x + t) % width] == b'<'
+        || valley[(y + height - t % height) % height][x] == b'v'
+        || valley[(y + t) % height][x] == b'^'
+}
+
+/// When the expedition can first reach `to` after leaving `from` at minute `t`, where the
+/// positions are inside the valley but for its openings at (0, -1) and (width - 1, height)
+fn crossing_time(
+    valley: &[Vec<u8>],
+    from: (i64, i64),
+    to: (i64, i64),
+    mut t: usize,
+) -> Option<usize> {
+    let (width, height) = (valley[0].len() as i64, valley.len() as i64);
+    let period = num::integer::lcm(width, height) as usize;
+    let openings = [(0, -1), (width - 1, height)];
+
+    let mut seen = HashSet::new();
+    let mut here = vec![from];
+    while !here.is_empty() {
+        if here.contains(&to) {
+            return Some(t);
+        }
+        t += 1;
+        let mut next = Vec::new();
+        for (x, y) in here {
+            for (dx, dy) in [(0, 0), (1, 0), (-1, 0), (0, 1), (0, -1)] {
+                let (x, y) = (x + dx, y + dy);
+                let is_free = openings.contains(&(x, y))
+                    || ((0..width).contains(&x)
+                        && (0..height).contains(&y)
+                        && !is_windy(valley, x as usize, y as usize, t));
+                if is_free && seen.insert((x, y, t % period)) {
+                    next.push((x, y));
+                }
+            }
+        }
+        here = next;
+    }
+    None
+}
+
+/// No blizzard blows up or down the columns of the entrance and the exit, like in the puzzle.
+/// Valleys that cannot be crossed there, back and there again are drawn again.
+pub fn d24(rng: &mut Rng, size: usize) -> String {
+    let width = size.max(2);
+    let height = (size * 2 / 3).max(1);
+
+    let valley = loop {
+        let valley: Vec<Vec<u8>> = (0..height)
+            .map(|_| {
+                (0..width)
+                    .map(|x| {
+                        let vertical_allowed = x != 0 && x != width - 1;
+                        match rng.below(12) {
+                            0 => b'<',
+                            1 => b'>',
+                            2 if vertical_allowed => b'^',
+                            3 if vertical_allowed => b'v',
+                            _ => b'.',
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        let (entrance, exit) = ((0, -1), (width as i64 - 1, height as i64));
+        let crossed = crossing_time(&valley, entrance, exit, 0)
+            .and_then(|t| crossing_time(&valley, exit, entrance, t))
+            .and_then(|t| crossing_time(&valley, entrance, exit, t));
+        if crossed.is_some() {
+            break valley;
+        }
+    };
+
+    let mut rows = vec![format!("#.{}", "#".repeat(width))];
+    rows.extend(
+        valley
+            .into_iter()
+            .map(|row| format!("#{}#", String::from_utf8(row).unwrap())),
+    );
+    rows.push(format!("{}.#", "#".repeat(width)));
+    lines(rows)
+}
+
+fn to_snafu(mut n: i64) -> String {
+    let mut digits = Vec::new();
+    while n != 0 {
+        let d = (n + 2).rem_euclid(5) - 2;
+        n = (n - d) / 5;
+        digits.push(match d {
+            2 => '2',
+            1 => '1',
+            0 => '0',
+            -1 => '-',
+            _ => '=',
+        });
+    }
+    digits.iter().rev().collect()
+}
+
+pub fn d25(rng: &mut Rng, size: usize) -> String {
+    lines((0..size.max(1)).map(|_| {
+        let digits = rng.range(1, 20) as u32;
+        to_snafu(rng.range(1, 5i64.pow(digits)))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snafu_numbers_are_written_in_balanced_base_5() {
+        assert_eq!(to_snafu(1), "1");
+        assert_eq!(to_snafu(3), "1=");
+        assert_eq!(to_snafu(2022), "1=11-2");
+        assert_eq!(to_snafu(314159265), "1121-1110-1=0");
+    }
+}
@@ -0,0 +1,170 @@
+//! Seeded generators of random but valid puzzle inputs, for stress tests and for measuring how
+//! the solvers scale with the size of their input.
+//!
+//! The same day, seed and size always give the same input. What the size counts differs from
+//! day to day and is described by [`Generator::size`].
+
+mod days;
+mod rng;
+
+pub use rng::Rng;
+
+pub struct Generator {
+    pub day: u8,
+    /// What the size counts, like "elves" or "valves"
+    pub size: &'static str,
+    /// The size used when none is given, close to the size of the small example
+    pub default_size: usize,
+    generate: fn(&mut Rng, usize) -> String,
+}
+
+impl Generator {
+    pub fn generate(&self, seed: u64, size: usize) -> String {
+        (self.generate)(&mut Rng::new(seed), size)
+    }
+}
+
+const fn generator(
+    day: u8,
+    size: &'static str,
+    default_size: usize,
+    generate: fn(&mut Rng, usize) -> String,
+) -> Generator {
+    Generator {
+        day,
+        size,
+        default_size,
+        generate,
+    }
+}
+
+static GENERATORS: [Generator; 25] = [
+    generator(1, "elves", 10, days::d1),
+    generator(2, "rounds", 100, days::d2),
+    generator(3, "groups of three rucksacks", 10, days::d3),
+    generator(4, "pairs of elves", 100, days::d4),
+    generator(5, "rearrangement steps", 20, days::d5),
+    generator(
+        6,
+        "characters before the start-of-packet marker",
+        100,
+        days::d6,
+    ),
+    generator(7, "directories", 20, days::d7),
+    generator(8, "trees along each side of the grid", 30, days::d8),
+    generator(9, "motions", 100, days::d9),
+    generator(10, "cycles, at most 240", 240, days::d10),
+    generator(11, "monkeys", 4, days::d11),
+    generator(12, "rows of the heightmap", 5, days::d12),
+    generator(13, "pairs of packets", 8, days::d13),
+    generator(14, "rock paths, at least 2", 10, days::d14),
+    generator(
+        15,
+        "sensors, more than 14 for the full-size search area",
+        14,
+        days::d15,
+    ),
+    generator(16, "valves", 10, days::d16),
+    generator(17, "jets", 40, days::d17),
+    generator(18, "cubes", 13, days::d18),
+    generator(19, "blueprints, at most 255", 2, days::d19),
+    generator(20, "numbers", 7, days::d20),
+    generator(21, "monkeys between humn and root", 5, days::d21),
+    generator(22, "tiles along each side of a cube face", 4, days::d22),
+    generator(23, "tiles along each side of the grove", 12, days::d23),
+    generator(24, "columns inside the valley walls", 6, days::d24),
+    generator(25, "SNAFU numbers", 13, days::d25),
+];
+
+pub fn generators() -> &'static [Generator] {
+    &GENERATORS
+}
+
+/// The generator for `day`, if it is a day of the calendar
+pub fn generator_for(day: u8) -> Option<&'static Generator> {
+    GENERATORS.iter().find(|g| g.day == day)
+}
+
+/// A random input for `day`, or `None` if there is no such day
+pub fn generate(day: u8, seed: u64, size: usize) -> Option<String> {
+    generator_for(day).map(|g| g.generate(seed, size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::{self, CancelToken, NoProgress, Part, SolveError};
+
+    #[test]
+    fn there_is_a_generator_for_every_day() {
+        for (day, g) in (1..=25).zip(generators()) {
+            assert_eq!(g.day, day);
+        }
+        assert!(generate(26, 0, 1).is_none());
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_input() {
+        for g in generators() {
+            assert_eq!(g.generate(3, g.default_size), g.generate(3, g.default_size));
+        }
+        assert_ne!(generate(1, 1, 10), generate(1, 2, 10));
+    }
+
+    #[test]
+    fn generated_inputs_are_solved() {
+        for g in generators().iter().filter(|g| g.day != 19) {
+            let day = days::day(g.day).unwrap();
+            // the searches of these days are slow in debug builds
+            let size = match g.day {
+                16 => 6,
+                24 => 4,
+                _ => g.default_size,
+            };
+            for seed in 0..3 {
+                let input = g.generate(seed, size);
+                for part in [Part::Basic, Part::Advanced] {
+                    if let Err(e) = (day.solve)(&input, part, &CancelToken::new(), &NoProgress) {
+                        panic!(
+                            "day {} {} with seed {}: {}\n{}",
+                            g.day, part, seed, e, input
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn generated_blueprints_are_parsed() {
+        // even one blueprint takes half a minute to search in debug builds, so the search is
+        // cancelled before it starts
+        let day = days::day(19).unwrap();
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        for seed in 0..3 {
+            let input = generate(19, seed, 10).unwrap();
+            assert!(
+                matches!(
+                    (day.solve)(&input, Part::Basic, &cancel, &NoProgress),
+                    Err(SolveError::TimedOut { .. })
+                ),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn tiny_sizes_are_still_valid() {
+        for g in generators().iter().filter(|g| g.day != 19) {
+            let day = days::day(g.day).unwrap();
+            let input = g.generate(0, 1);
+            for part in [Part::Basic, Part::Advanced] {
+                if let Err(e) = (day.solve)(&input, part, &CancelToken::new(), &NoProgress) {
+                    panic!("day {} {} with size 1: {}\n{}", g.day, part, e, input);
+                }
+            }
+        }
+    }
+}
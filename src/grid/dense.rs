@@ -0,0 +1,268 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use super::{Pos, STEPS4, STEPS8};
+use crate::days::SolveError;
+
+/// Every cell of a `width` by `height` rectangle, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Pads the shorter rows at their end with `fill`, as the maps that leave out their trailing
+    /// blanks need.
+    pub fn from_ragged_rows(rows: Vec<Vec<T>>, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for mut row in rows {
+            row.resize(width, fill.clone());
+            cells.extend(row);
+        }
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Reads a map of characters, one row per line, up to the first empty line.
+    ///
+    /// `cell` turns a character into a cell, or explains why it is not a valid one. All rows
+    /// must be as long as the first.
+    pub fn parse(
+        input: &str,
+        mut cell: impl FnMut(char) -> Result<T, String>,
+    ) -> Result<Self, SolveError> {
+        let mut width = 0;
+        let mut height = 0;
+        let mut cells = Vec::new();
+
+        for (idx, line) in input.lines().enumerate() {
+            if line.is_empty() {
+                break;
+            }
+            let row_start = cells.len();
+            for (col, c) in line.chars().enumerate() {
+                let value =
+                    cell(c).map_err(|message| SolveError::parse(idx + 1, col + 1, message))?;
+                cells.push(value);
+            }
+            let len = cells.len() - row_start;
+            if height == 0 {
+                width = len;
+            } else if len != width {
+                return Err(SolveError::parse(
+                    idx + 1,
+                    1,
+                    format!(
+                        "expected {} columns like the first row, found {}",
+                        width, len
+                    ),
+                ));
+            }
+            height += 1;
+        }
+
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos).then(|| &self[pos])
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.x < self.width && pos.y < self.height
+    }
+
+    /// The position at signed coordinates, if it is on the grid
+    pub fn pos_at(&self, x: i32, y: i32) -> Option<Pos> {
+        let pos = Pos::new(usize::try_from(x).ok()?, usize::try_from(y).ok()?);
+        self.contains(pos).then_some(pos)
+    }
+
+    /// Every position, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Pos::new(x, y)))
+    }
+
+    /// Every cell with its position, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// The cells of a column, from the top
+    pub fn column(
+        &self,
+        x: usize,
+    ) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator + Clone {
+        assert!(x < self.width, "column {} is off the grid", x);
+        self.cells[x..].iter().step_by(self.width)
+    }
+
+    /// The positions reached by repeatedly stepping `dx` and `dy` from `from`, without `from`
+    /// itself, until the edge of the grid
+    pub fn ray(&self, from: Pos, dx: i32, dy: i32) -> impl Iterator<Item = Pos> + '_ {
+        std::iter::successors(Some(from), move |&pos| pos.offset(dx, dy))
+            .skip(1)
+            .take_while(|&pos| self.contains(pos))
+    }
+
+    /// The positions above, to the left, below and to the right that are on the grid
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        STEPS4
+            .into_iter()
+            .filter_map(move |(dx, dy)| pos.offset(dx, dy))
+            .filter(|&n| self.contains(n))
+    }
+
+    /// The positions around that are on the grid, including the diagonal ones
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        STEPS8
+            .into_iter()
+            .filter_map(move |(dx, dy)| pos.offset(dx, dy))
+            .filter(|&n| self.contains(n))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        assert!(self.contains(pos), "{:?} is off the grid", pos);
+        &self.cells[pos.y * self.width + pos.x]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        assert!(self.contains(pos), "{:?} is off the grid", pos);
+        &mut self.cells[pos.y * self.width + pos.x]
+    }
+}
+
+/// Prints each row on its own line
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            for cell in self.row(y) {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> Result<Grid<u32>, SolveError> {
+        Grid::parse(input, |c| {
+            c.to_digit(10).ok_or(format!("not a digit: {}", c))
+        })
+    }
+
+    #[test]
+    fn a_parsed_grid_prints_as_its_input() {
+        let grid = digits("123\n456\n\nignored").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Pos::new(2, 1)], 6);
+        assert_eq!(grid.to_string(), "123\n456\n");
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_cell() {
+        assert_eq!(
+            digits("123\n4x6\n"),
+            Err(SolveError::parse(2, 2, "not a digit: x"))
+        );
+        assert_eq!(
+            digits("123\n45\n"),
+            Err(SolveError::parse(
+                2,
+                1,
+                "expected 3 columns like the first row, found 2"
+            ))
+        );
+    }
+
+    #[test]
+    fn rows_columns_and_rays_stay_on_the_grid() {
+        let grid = digits("123\n456\n789\n").unwrap();
+        assert_eq!(grid.row(1), [4, 5, 6]);
+        assert_eq!(grid.column(2).rev().collect::<Vec<_>>(), [&9, &6, &3]);
+
+        let ray: Vec<_> = grid.ray(Pos::new(1, 1), -1, -1).collect();
+        assert_eq!(ray, [Pos::new(0, 0)]);
+        assert_eq!(grid.ray(Pos::new(2, 0), 1, 0).count(), 0);
+    }
+
+    #[test]
+    fn corners_have_fewer_neighbours() {
+        let grid = Grid::new(3, 3, ());
+        assert_eq!(grid.neighbours4(Pos::new(0, 0)).count(), 2);
+        assert_eq!(grid.neighbours8(Pos::new(0, 0)).count(), 3);
+        assert_eq!(grid.neighbours4(Pos::new(1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8(Pos::new(1, 1)).count(), 8);
+        assert_eq!(grid.pos_at(-1, 0), None);
+        assert_eq!(grid.pos_at(2, 2), Some(Pos::new(2, 2)));
+    }
+
+    #[test]
+    fn ragged_rows_are_padded() {
+        let grid = Grid::from_ragged_rows(vec![vec![1], vec![1, 2, 3]], 0);
+        assert_eq!(grid.row(0), [1, 0, 0]);
+        assert_eq!(grid.get(Pos::new(3, 0)), None);
+    }
+}
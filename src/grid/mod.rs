@@ -0,0 +1,101 @@
+//! Two-dimensional grids for the days whose input is a map.
+//!
+//! [`Grid`] stores every cell of a rectangle and is indexed by [`Pos`], with `y` growing
+//! downwards as in the puzzle inputs. [`SparseGrid`] stores only the occupied cells of an
+//! unbounded plane and is indexed by [`Point`], which may be negative.
+
+mod dense;
+mod sparse;
+
+use std::ops::Add;
+
+pub use dense::Grid;
+pub use sparse::{Bounds, SparseGrid};
+
+/// A cell of a [`Grid`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Pos {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Pos {
+    pub const fn new(x: usize, y: usize) -> Self {
+        Pos { x, y }
+    }
+
+    /// The position `dx` and `dy` away, unless it would be negative
+    pub fn offset(self, dx: i32, dy: i32) -> Option<Pos> {
+        Some(Pos {
+            x: self.x.checked_add_signed(dx as isize)?,
+            y: self.y.checked_add_signed(dy as isize)?,
+        })
+    }
+}
+
+/// A cell of a [`SparseGrid`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub const fn new(x: i32, y: i32) -> Self {
+        Point { x, y }
+    }
+
+    /// The points above, to the left, below and to the right, in that order
+    pub fn neighbours4(self) -> [Point; 4] {
+        STEPS4.map(|(dx, dy)| self + Point::new(dx, dy))
+    }
+
+    /// The points around, row by row from the top left
+    pub fn neighbours8(self) -> [Point; 8] {
+        STEPS8.map(|(dx, dy)| self + Point::new(dx, dy))
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+const STEPS4: [(i32, i32); 4] = [(0, -1), (-1, 0), (0, 1), (1, 0)];
+
+const STEPS8: [(i32, i32); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets_stop_at_zero() {
+        assert_eq!(Pos::new(1, 0).offset(-1, 1), Some(Pos::new(0, 1)));
+        assert_eq!(Pos::new(1, 0).offset(0, -1), None);
+    }
+
+    #[test]
+    fn points_have_eight_neighbours() {
+        let around = Point::new(0, 0).neighbours8();
+        assert_eq!(around[0], Point::new(-1, -1));
+        assert_eq!(around[7], Point::new(1, 1));
+        assert!(!around.contains(&Point::new(0, 0)));
+        assert!(Point::new(0, 0)
+            .neighbours4()
+            .iter()
+            .all(|p| around.contains(p)));
+    }
+}
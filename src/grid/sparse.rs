@@ -0,0 +1,137 @@
+use std::{
+    collections::{hash_map, HashMap},
+    fmt::Display,
+};
+
+use super::Point;
+
+/// The occupied cells of an unbounded plane.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+}
+
+/// The smallest rectangle holding every occupied cell, with all of its sides included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl Bounds {
+    pub fn width(&self) -> u32 {
+        self.left.abs_diff(self.right) + 1
+    }
+
+    pub fn height(&self) -> u32 {
+        self.top.abs_diff(self.bottom) + 1
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    /// Fills a cell, giving back what was there before
+    pub fn insert(&mut self, p: Point, value: T) -> Option<T> {
+        self.cells.insert(p, value)
+    }
+
+    pub fn remove(&mut self, p: Point) -> Option<T> {
+        self.cells.remove(&p)
+    }
+
+    /// The occupied cells, in no particular order
+    pub fn iter(&self) -> hash_map::Iter<'_, Point, T> {
+        self.cells.iter()
+    }
+
+    /// The bounds of the occupied cells, or `None` if there are none
+    pub fn bounds(&self) -> Option<Bounds> {
+        let mut points = self.cells.keys();
+        let first = points.next()?;
+        let start = Bounds {
+            left: first.x,
+            top: first.y,
+            right: first.x,
+            bottom: first.y,
+        };
+        Some(points.fold(start, |b, p| Bounds {
+            left: b.left.min(p.x),
+            top: b.top.min(p.y),
+            right: b.right.max(p.x),
+            bottom: b.bottom.max(p.y),
+        }))
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid::new()
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        SparseGrid {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Prints the cells within the bounds row by row, with `.` for the empty ones
+impl<T: Display> Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(b) = self.bounds() else {
+            return Ok(());
+        };
+        for y in b.top..=b.bottom {
+            for x in b.left..=b.right {
+                match self.get(Point::new(x, y)) {
+                    Some(cell) => write!(f, "{}", cell)?,
+                    None => write!(f, ".")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_bounds_include_negative_points() {
+        let grid: SparseGrid<char> = [(Point::new(-2, 1), 'a'), (Point::new(0, -1), 'b')]
+            .into_iter()
+            .collect();
+        let b = grid.bounds().unwrap();
+        assert_eq!((b.left, b.top, b.right, b.bottom), (-2, -1, 0, 1));
+        assert_eq!((b.width(), b.height()), (3, 3));
+        assert_eq!(grid.to_string(), "..b\n...\na..\n");
+        assert_eq!(SparseGrid::<char>::new().bounds(), None);
+    }
+}
@@ -1,4 +1,46 @@
-use std::env;
+use std::{
+    convert::Infallible,
+    env,
+    fmt::Display,
+    fs,
+    io::{self, BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use clap::ValueEnum;
+
+/// The directory of the bundled `d{day}/ex1.txt` and `d{day}/ex2.txt` examples in the source
+/// tree this binary was built from. It only exists on the machine that built it.
+pub const BUILD_INPUTS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/days");
+
+/// The environment variable naming the directory of the bundled examples
+pub const INPUTS_DIR_VAR: &str = "ADVENT22_INPUTS_DIR";
+
+/// Where the bundled examples are looked up when no directory is given, resolved at run time.
+///
+/// That is the directory in `ADVENT22_INPUTS_DIR` if it is set, else the first `days` or
+/// `src/days` directory with examples found next to the executable or above it, so a binary
+/// in `target/release` or copied along with the examples finds them. Only when there is none is
+/// it [`BUILD_INPUTS_DIR`].
+pub fn default_inputs_dir() -> PathBuf {
+    if let Some(dir) = env::var_os(INPUTS_DIR_VAR) {
+        return PathBuf::from(dir);
+    }
+    env::current_exe()
+        .ok()
+        .as_deref()
+        .and_then(Path::parent)
+        .and_then(find_inputs_dir)
+        .unwrap_or_else(|| PathBuf::from(BUILD_INPUTS_DIR))
+}
+
+fn find_inputs_dir(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .flat_map(|dir| [dir.join("days"), dir.join("src").join("days")])
+        .find(|dir| dir.join("d1").join("ex1.txt").is_file())
+}
 
 pub fn run_a_or_b(a: fn() -> (), b: fn() -> ()) {
     let part = env::args().nth(1).expect("Please specify one argument: a or b");
@@ -7,4 +49,196 @@ pub fn run_a_or_b(a: fn() -> (), b: fn() -> ()) {
         "b" => b(),
         _ => panic!("Please specify a or b, not {}", part),
     };
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Example {
+    #[value(alias = "s")]
+    Small,
+
+    #[value(alias = "b")]
+    Big,
+}
+
+/// Where the puzzle input for a run comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Example(Example),
+    File(PathBuf),
+    Stdin,
+}
+
+/// Parses `small`/`s` and `big`/`b` as the bundled examples, `-` as stdin and anything else as a path.
+impl FromStr for InputSource {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "small" | "s" => InputSource::Example(Example::Small),
+            "big" | "b" => InputSource::Example(Example::Big),
+            "-" => InputSource::Stdin,
+            path => InputSource::File(PathBuf::from(path)),
+        })
+    }
+}
+
+impl Display for InputSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputSource::Example(Example::Small) => write!(f, "small"),
+            InputSource::Example(Example::Big) => write!(f, "big"),
+            InputSource::File(path) => write!(f, "{}", path.display()),
+            InputSource::Stdin => write!(f, "-"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InputError {
+    /// The file that could not be read, or None for stdin
+    pub path: Option<PathBuf>,
+    pub source: io::Error,
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.path {
+            Some(path) => write!(
+                f,
+                "could not read input file '{}': {}",
+                path.display(),
+                self.source
+            ),
+            None => write!(f, "could not read input from stdin: {}", self.source),
+        }
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Returns the path of a bundled example.
+///
+/// Some days (like day 9) have a different small example for each part, named `ex1a.txt` and `ex1b.txt`.
+pub fn example_path(inputs_dir: &Path, day: u8, example: Example, basic: bool) -> PathBuf {
+    let dir = inputs_dir.join(format!("d{day}"));
+    match example {
+        Example::Small => {
+            let per_part = dir.join(if basic { "ex1a.txt" } else { "ex1b.txt" });
+            if per_part.exists() {
+                per_part
+            } else {
+                dir.join("ex1.txt")
+            }
+        }
+        Example::Big => dir.join("ex2.txt"),
+    }
+}
+
+impl InputSource {
+    pub fn read(&self, day: u8, basic: bool, inputs_dir: &Path) -> Result<String, InputError> {
+        match self {
+            InputSource::Example(example) => {
+                read_file(&example_path(inputs_dir, day, *example, basic))
+            }
+            InputSource::File(path) => read_file(path),
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(|source| InputError { path: None, source })?;
+                Ok(input)
+            }
+        }
+    }
+
+    /// Opens the input to be read a line at a time rather than all at once
+    pub fn open(
+        &self,
+        day: u8,
+        basic: bool,
+        inputs_dir: &Path,
+    ) -> Result<Box<dyn BufRead>, InputError> {
+        let Some(path) = self.path(day, basic, inputs_dir) else {
+            return Ok(Box::new(io::stdin().lock()));
+        };
+        match fs::File::open(&path) {
+            Ok(file) => Ok(Box::new(BufReader::new(file))),
+            Err(source) => Err(InputError {
+                path: Some(path),
+                source,
+            }),
+        }
+    }
+
+    /// The file the input is read from, or `None` for stdin
+    pub fn path(&self, day: u8, basic: bool, inputs_dir: &Path) -> Option<PathBuf> {
+        match self {
+            InputSource::Example(example) => Some(example_path(inputs_dir, day, *example, basic)),
+            InputSource::File(path) => Some(path.clone()),
+            InputSource::Stdin => None,
+        }
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, InputError> {
+    fs::read_to_string(path).map_err(|source| InputError {
+        path: Some(path.to_path_buf()),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn input_source_parses_examples_stdin_and_paths() {
+        assert_eq!("small".parse(), Ok(InputSource::Example(Example::Small)));
+        assert_eq!("s".parse(), Ok(InputSource::Example(Example::Small)));
+        assert_eq!("big".parse(), Ok(InputSource::Example(Example::Big)));
+        assert_eq!("b".parse(), Ok(InputSource::Example(Example::Big)));
+        assert_eq!("-".parse(), Ok(InputSource::Stdin));
+        assert_eq!(
+            "inputs/d5.txt".parse(),
+            Ok(InputSource::File(PathBuf::from("inputs/d5.txt")))
+        );
+    }
+
+    #[test]
+    fn example_path_prefers_per_part_examples() {
+        let dir = Path::new(BUILD_INPUTS_DIR);
+        assert_eq!(
+            example_path(dir, 9, Example::Small, false),
+            dir.join("d9/ex1b.txt")
+        );
+        assert_eq!(
+            example_path(dir, 5, Example::Small, true),
+            dir.join("d5/ex1.txt")
+        );
+        assert_eq!(
+            example_path(dir, 9, Example::Big, true),
+            dir.join("d9/ex2.txt")
+        );
+    }
+
+    #[test]
+    fn the_examples_are_found_above_the_executable() {
+        let exe_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("target/release");
+        assert_eq!(find_inputs_dir(&exe_dir), Some(PathBuf::from(BUILD_INPUTS_DIR)));
+        assert_eq!(find_inputs_dir(Path::new("/")), None);
+    }
+
+    #[test]
+    fn missing_files_are_reported_with_their_path() {
+        let err = InputSource::File(PathBuf::from("does/not/exist.txt"))
+            .read(1, true, Path::new(BUILD_INPUTS_DIR))
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("could not read input file 'does/not/exist.txt'"));
+    }
+}
@@ -0,0 +1,130 @@
+use std::{
+    fmt::{Display, Write},
+    time::Duration,
+};
+
+/// Writes a string as a JSON string literal, including the quotes.
+pub fn quote(s: &str) -> String {
+    let mut res = String::with_capacity(s.len() + 2);
+    res.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(res, "\\u{:04x}", c as u32).unwrap(),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
+
+/// Builds a single-line JSON object field by field, in insertion order.
+#[derive(Debug, Clone, Default)]
+pub struct JsonObject {
+    fields: Vec<(String, String)>,
+}
+
+impl JsonObject {
+    pub fn new() -> Self {
+        JsonObject::default()
+    }
+
+    pub fn string(mut self, key: &str, value: &str) -> Self {
+        self.fields.push((key.to_string(), quote(value)));
+        self
+    }
+
+    /// Adds a number (or any value whose Display output is valid JSON).
+    pub fn number(mut self, key: &str, value: impl Display) -> Self {
+        self.fields.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Adds a duration as a number of milliseconds, rounded to the microsecond.
+    pub fn millis(self, key: &str, value: Duration) -> Self {
+        self.number(key, format!("{:.3}", value.as_secs_f64() * 1000.0))
+    }
+
+    pub fn bool(mut self, key: &str, value: bool) -> Self {
+        self.fields.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn null(mut self, key: &str) -> Self {
+        self.fields.push((key.to_string(), "null".to_string()));
+        self
+    }
+
+    pub fn object(mut self, key: &str, value: JsonObject) -> Self {
+        self.fields.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Adds an array of objects, in the order they are given.
+    pub fn objects(mut self, key: &str, values: impl IntoIterator<Item = JsonObject>) -> Self {
+        let values: Vec<_> = values.into_iter().map(|value| value.to_string()).collect();
+        self.fields
+            .push((key.to_string(), format!("[{}]", values.join(","))));
+        self
+    }
+}
+
+impl Display for JsonObject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{")?;
+        for (i, (key, value)) in self.fields.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}:{}", quote(key), value)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_escapes_special_characters() {
+        assert_eq!(quote("CMZ"), "\"CMZ\"");
+        assert_eq!(quote("a\"b\\c"), "\"a\\\"b\\\\c\"");
+        assert_eq!(quote("#.\n.#"), "\"#.\\n.#\"");
+        assert_eq!(quote("\u{1}"), "\"\\u0001\"");
+    }
+
+    #[test]
+    fn objects_keep_their_field_order() {
+        let obj = JsonObject::new()
+            .number("day", 1)
+            .string("answer", "24000")
+            .bool("ok", true)
+            .null("error")
+            .object(
+                "timings",
+                JsonObject::new().millis("solve_ms", Duration::from_micros(500)),
+            );
+        assert_eq!(
+            obj.to_string(),
+            r#"{"day":1,"answer":"24000","ok":true,"error":null,"timings":{"solve_ms":0.500}}"#
+        );
+    }
+
+    #[test]
+    fn arrays_hold_objects() {
+        let top = [1, 3].map(|elf| JsonObject::new().number("elf", elf));
+        assert_eq!(
+            JsonObject::new().objects("top", top).to_string(),
+            r#"{"top":[{"elf":1},{"elf":3}]}"#
+        );
+        assert_eq!(
+            JsonObject::new().objects("top", []).to_string(),
+            r#"{"top":[]}"#
+        );
+    }
+}
@@ -1,2 +1,12 @@
+pub mod answers;
 pub mod input;
+pub mod json;
+pub mod log;
+pub mod memory;
+pub mod parse;
+pub mod render;
+pub mod search;
 pub mod days;
+pub mod gen;
+pub mod grid;
+pub mod serve;
@@ -0,0 +1,300 @@
+//! Levelled logging on stderr, with the day a message comes from as its target so that one day
+//! can be looked at closely.
+//!
+//! What is logged is set once by [`init`] with a [`Filter`], which has a default level and
+//! levels for some targets, like `warn,d19=debug`. Messages are logged with the [`error!`],
+//! [`warn!`], [`info!`], [`debug!`] and [`trace!`] macros, whose target is the day, or the
+//! module outside of the days, they are written in. A [`Span`] logs how long a phase took.
+//! Until [`init`] is called, only warnings and errors are logged.
+
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        OnceLock,
+    },
+    time::Instant,
+};
+
+/// The environment variable that holds a filter for the command line
+pub const LOG_ENV_VAR: &str = "ADVENT22_LOG";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        })
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(Level::Error),
+            "warn" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(format!(
+                "'{}' is not a level: error, warn, info, debug, trace or off",
+                s
+            )),
+        }
+    }
+}
+
+/// The most detailed level logged for each target, where `None` logs nothing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    default: Option<Level>,
+    targets: Vec<(String, Option<Level>)>,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Filter::new(Some(Level::Warn))
+    }
+}
+
+fn parse_level(s: &str) -> Result<Option<Level>, String> {
+    match s {
+        "off" => Ok(None),
+        _ => s.parse().map(Some),
+    }
+}
+
+/// A comma separated list of a default level and `target=level` pairs, like `info,d16=trace`
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut filter = Filter::default();
+        for directive in s.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => {
+                    let level = parse_level(level.trim())?;
+                    filter.targets.push((target.trim().to_string(), level));
+                }
+                None => filter.default = parse_level(directive)?,
+            }
+        }
+        Ok(filter)
+    }
+}
+
+impl Filter {
+    pub fn new(default: Option<Level>) -> Self {
+        Filter {
+            default,
+            targets: Vec::new(),
+        }
+    }
+
+    /// The same filter with another default level, and the same levels for its targets
+    pub fn with_default(self, default: Option<Level>) -> Self {
+        Filter { default, ..self }
+    }
+
+    /// The level of a target, which is the last one given for it, else the default one
+    pub fn level(&self, target: &str) -> Option<Level> {
+        self.targets
+            .iter()
+            .rev()
+            .find(|(t, _)| t == target)
+            .map_or(self.default, |&(_, level)| level)
+    }
+
+    /// The most detailed level of any target
+    fn max(&self) -> Option<Level> {
+        self.targets
+            .iter()
+            .map(|(target, _)| self.level(target))
+            .fold(self.default, Ord::max)
+    }
+}
+
+static FILTER: OnceLock<Filter> = OnceLock::new();
+/// The most detailed level of the filter, to check most messages without looking at their target
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(Level::Warn as u8);
+static START: OnceLock<Instant> = OnceLock::new();
+
+/// Sets what is logged from now on. Only the first call has an effect.
+pub fn init(filter: Filter) {
+    START.get_or_init(Instant::now);
+    let max = filter.max().map_or(0, |level| level as u8);
+    if FILTER.set(filter).is_ok() {
+        MAX_LEVEL.store(max, Ordering::Relaxed);
+    }
+}
+
+pub fn enabled(level: Level, target: &str) -> bool {
+    if level as u8 > MAX_LEVEL.load(Ordering::Relaxed) {
+        return false;
+    }
+    match FILTER.get() {
+        Some(filter) => filter.level(target) >= Some(level),
+        None => level <= Level::Warn,
+    }
+}
+
+/// Writes a message on stderr with the time since [`init`]; the macros call it only for the
+/// messages that are enabled.
+pub fn write(level: Level, target: &str, message: fmt::Arguments) {
+    let elapsed = START.get_or_init(Instant::now).elapsed();
+    eprintln!(
+        "[{:>8.3}s {:<5} {}] {}",
+        elapsed.as_secs_f64(),
+        level,
+        target,
+        message
+    );
+}
+
+/// The target of a module: the day for the modules of a day, like `d19` for
+/// `advent22::days::d19::simulation`, and else the first module under the crate
+pub fn target(module_path: &'static str) -> &'static str {
+    let path = module_path
+        .strip_prefix("advent22::")
+        .unwrap_or(module_path);
+    let path = path.strip_prefix("days::").unwrap_or(path);
+    path.split("::").next().unwrap_or(path)
+}
+
+/// Logs a message at a level, with the target of the module it is in unless one is given.
+#[macro_export]
+macro_rules! log {
+    ($level:expr, target: $target:expr, $($arg:tt)+) => {{
+        let level = $level;
+        let target = $target;
+        if $crate::log::enabled(level, target) {
+            $crate::log::write(level, target, format_args!($($arg)+));
+        }
+    }};
+    ($level:expr, $($arg:tt)+) => {
+        $crate::log!($level, target: $crate::log::target(module_path!()), $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Error, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Warn, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Info, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Debug, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Trace, $($arg)+) };
+}
+
+pub use crate::{debug, error, info, log, trace, warn};
+
+/// A phase of a run, which logs how long it took when it is dropped, and that it started at the
+/// trace level.
+#[must_use = "the span ends as soon as it is dropped"]
+pub struct Span {
+    level: Level,
+    target: &'static str,
+    name: String,
+    /// When the span started, if it is logged
+    start: Option<Instant>,
+}
+
+impl Span {
+    pub fn enter(level: Level, target: &'static str, name: impl Display) -> Self {
+        let start = enabled(level, target).then(Instant::now);
+        let name = match start {
+            Some(_) => name.to_string(),
+            None => String::new(),
+        };
+        if start.is_some() && enabled(Level::Trace, target) {
+            write(Level::Trace, target, format_args!("{} started", name));
+        }
+        Span {
+            level,
+            target,
+            name,
+            start,
+        }
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if let Some(start) = self.start {
+            let elapsed = start.elapsed();
+            write(
+                self.level,
+                self.target,
+                format_args!("{} took {:.3?}", self.name, elapsed),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_have_levels_by_target() {
+        let filter: Filter = "info, d19=trace,d16=off,d19=debug".parse().unwrap();
+        assert_eq!(filter.level("d1"), Some(Level::Info));
+        assert_eq!(filter.level("d19"), Some(Level::Debug));
+        assert_eq!(filter.level("d16"), None);
+        assert_eq!(filter.max(), Some(Level::Debug));
+
+        assert_eq!("".parse::<Filter>(), Ok(Filter::default()));
+        assert_eq!(
+            "d1=TRACE".parse::<Filter>().unwrap().level("d2"),
+            Some(Level::Warn)
+        );
+        assert!("d1=loud".parse::<Filter>().is_err());
+        assert!("verbose".parse::<Filter>().is_err());
+    }
+
+    #[test]
+    fn a_filter_keeps_its_targets_with_another_default() {
+        let filter: Filter = "off,d9=info".parse().unwrap();
+        let filter = filter.with_default(Some(Level::Debug));
+        assert_eq!(filter.level("d1"), Some(Level::Debug));
+        assert_eq!(filter.level("d9"), Some(Level::Info));
+    }
+
+    #[test]
+    fn the_days_are_targets() {
+        assert_eq!(target("advent22::days::d19::simulation"), "d19");
+        assert_eq!(target("advent22::days::d1"), "d1");
+        assert_eq!(target("advent22::serve"), "serve");
+        assert_eq!(target("advent22"), "advent22");
+    }
+}
@@ -1,98 +1,1147 @@
-use std::fs;
+use std::{
+    fmt::Display,
+    io::{self, IsTerminal},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 
-use advent22::days;
-use clap::{Parser, ValueEnum};
+use advent22::{
+    answers::{self, MANIFEST_FILE_NAME},
+    days, gen,
+    grid::Bounds,
+    input::{default_inputs_dir, Example, InputError, InputSource},
+    json::JsonObject,
+    log::{self, Filter, Level},
+    memory::{self, Bytes, Measurement, MemoryStats},
+    render,
+    serve::{self, Server},
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use rayon::prelude::*;
 
-use days::*;
+use days::{
+    d1::CalorieLedger, stream::StreamError, CancelToken, DayInfo, NoProgress, Part, Progress,
+    SolveError,
+};
 
-fn read_example(day: u8, big: bool) -> String {
-    let path = if big {
-        format!("src/days/d{day}/ex2.txt")
-    } else {
-        format!("src/days/d{day}/ex1.txt")
-    };
-    fs::read_to_string(path).unwrap()
-}
+/// Counts the allocations for the memory columns and the JSON timings
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static GLOBAL: memory::CountingAllocator = memory::CountingAllocator;
 
-struct Context {
-    big: bool,
+struct Context<'a> {
+    input: &'a InputSource,
+    options: &'a SolveOptions,
     basic: bool,
     day: u8,
+    diagnostics: Diagnostics,
+    /// Where to draw the state the day ends in
+    render: Option<&'a Path>,
+    /// Whether the input is read a line at a time rather than all at once
+    stream: bool,
+    /// What to show of the calories the elves of day 1 carry
+    ledger: Option<LedgerReport>,
 }
 
-impl Context {
-    fn run<R: Runner>(&self, _: R) {
-        let input = read_example(self.day, self.big);
-        let result = R::run(&input, self.basic);
-        println!("{}", result)
+impl<'a> Context<'a> {
+    fn new(input: &'a InputSource, options: &'a SolveOptions, day: u8, part: Part) -> Self {
+        Context {
+            input,
+            options,
+            basic: part == Part::Basic,
+            day,
+            diagnostics: Diagnostics::default(),
+            render: None,
+            stream: false,
+            ledger: None,
+        }
     }
 }
 
-fn main() {
-    let cli = Cli::parse();
+/// How the results are printed on stdout
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Plain answers and aligned tables
+    Text,
+    /// One JSON object per line for every run
+    Json,
+}
+
+enum RunError {
+    NoSuchDay(u8),
+    Input(InputError),
+    Solve(SolveError),
+    Render(String),
+    NotStreamed(u8),
+    NoLedger(u8),
+}
+
+impl RunError {
+    /// Exit codes for the single run; 2 is used by clap for usage errors
+    fn exit_code(&self) -> ExitCode {
+        ExitCode::from(match self {
+            RunError::NoSuchDay(_)
+            | RunError::Input(_)
+            | RunError::Render(_)
+            | RunError::NotStreamed(_)
+            | RunError::NoLedger(_) => 1,
+            RunError::Solve(SolveError::Parse { .. }) => 3,
+            RunError::Solve(SolveError::Unsupported(_)) => 4,
+            RunError::Solve(SolveError::NoSolution) => 5,
+            RunError::Solve(SolveError::TimedOut { .. }) => 6,
+        })
+    }
+
+    fn to_json(&self) -> JsonObject {
+        let kind = match self {
+            RunError::NoSuchDay(_) => "no_such_day",
+            RunError::Input(_) => "input",
+            RunError::Render(_) => "render",
+            RunError::NotStreamed(_) => "not_streamed",
+            RunError::NoLedger(_) => "no_ledger",
+            RunError::Solve(e) => return e.to_json(),
+        };
+        JsonObject::new()
+            .string("kind", kind)
+            .string("message", &self.to_string())
+    }
+}
+
+impl Display for RunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunError::NoSuchDay(day) => write!(f, "{} is not a valid day", day),
+            RunError::Input(e) => write!(f, "{}", e),
+            RunError::Solve(e) => write!(f, "{}", e),
+            RunError::Render(message) => write!(f, "{}", message),
+            RunError::NotStreamed(day) => {
+                let streamed: Vec<_> = days::registry()
+                    .iter()
+                    .filter(|info| info.stream.is_some())
+                    .map(|info| info.number.to_string())
+                    .collect();
+                write!(
+                    f,
+                    "day {} cannot read its input a line at a time, only days {} can",
+                    day,
+                    streamed.join(", ")
+                )
+            }
+            RunError::NoLedger(day) => write!(
+                f,
+                "--top and --stats are about the elves of day 1, not day {}",
+                day
+            ),
+        }
+    }
+}
+
+struct Timed {
+    answer: String,
+    read_time: Duration,
+    parse_time: Duration,
+    solve_time: Duration,
+    /// What parsing and solving allocated, with the `alloc-stats` feature
+    memory: Option<MemoryStats>,
+    /// The elves of day 1, when the ledger is shown
+    ledger: Option<CalorieLedger>,
+}
+
+impl Timed {
+    fn to_json(&self) -> JsonObject {
+        let obj = JsonObject::new()
+            .millis("read_ms", self.read_time)
+            .millis("parse_ms", self.parse_time)
+            .millis("solve_ms", self.solve_time);
+        match self.memory {
+            Some(memory) => obj
+                .number("peak_heap_bytes", memory.peak_bytes)
+                .number("allocations", memory.allocations),
+            None => obj,
+        }
+    }
+}
+
+/// What `--top` and `--stats` show of the elves of day 1
+#[derive(Clone, Copy)]
+struct LedgerReport {
+    top: Option<usize>,
+    stats: bool,
+}
+
+impl LedgerReport {
+    fn print(self, ledger: &CalorieLedger) {
+        if let Some(n) = self.top {
+            println!(
+                "{:>4}  {:>6}  {:>10}  {:>5}",
+                "rank", "elf", "calories", "items"
+            );
+            for ranked in ledger.top(n) {
+                let elf = ranked.elf;
+                println!(
+                    "{:>4}  {:>6}  {:>10}  {:>5}",
+                    ranked.rank, elf.number, elf.calories, elf.items
+                );
+            }
+        }
+        if self.stats {
+            let stats = ledger.stats();
+            println!(
+                "{} elves carry {} items worth {} calories: {:.1} per elf on average, {:.1} for \
+                 the median elf and {} for the {} carrying the most",
+                stats.elves,
+                stats.items,
+                stats.calories,
+                stats.mean,
+                stats.median,
+                stats.most,
+                match stats.tied_for_most {
+                    1 => "one".to_string(),
+                    n => format!("{} tied", n),
+                }
+            );
+        }
+    }
+
+    fn to_json(self, ledger: &CalorieLedger) -> JsonObject {
+        let mut obj = JsonObject::new();
+        if let Some(n) = self.top {
+            let top = ledger.top(n).into_iter().map(|ranked| {
+                JsonObject::new()
+                    .number("rank", ranked.rank)
+                    .number("elf", ranked.elf.number)
+                    .number("calories", ranked.elf.calories)
+                    .number("items", ranked.elf.items)
+            });
+            obj = obj.objects("top", top);
+        }
+        if self.stats {
+            let stats = ledger.stats();
+            obj = obj.object(
+                "stats",
+                JsonObject::new()
+                    .number("elves", stats.elves)
+                    .number("items", stats.items)
+                    .number("calories", stats.calories)
+                    .number("mean", stats.mean)
+                    .number("median", stats.median)
+                    .number("most", stats.most)
+                    .number("tied_for_most", stats.tied_for_most),
+            );
+        }
+        obj
+    }
+}
+
+/// The last of what a search reported about itself, for the `diagnostics` of the JSON record
+#[derive(Default)]
+struct Diagnostics(Mutex<SearchReport>);
+
+#[derive(Default, Clone, Copy)]
+struct SearchReport {
+    items: Option<(usize, usize)>,
+    states: u64,
+    best: Option<u64>,
+}
+
+impl Diagnostics {
+    /// The reported numbers, or `None` if the day reported nothing
+    fn to_json(&self) -> Option<JsonObject> {
+        let report = *self.0.lock().unwrap();
+        if report.items.is_none() && report.states == 0 && report.best.is_none() {
+            return None;
+        }
+        let mut obj = JsonObject::new();
+        if let Some((done, total)) = report.items {
+            obj = obj.number("items_done", done).number("items_total", total);
+        }
+        if report.states > 0 {
+            obj = obj.number("states_explored", report.states);
+        }
+        if let Some(best) = report.best {
+            obj = obj.number("best_so_far", best);
+        }
+        Some(obj)
+    }
+}
+
+impl Progress for Diagnostics {
+    fn items_done(&self, done: usize, total: usize) {
+        self.0.lock().unwrap().items = Some((done, total));
+    }
+
+    fn states_explored(&self, states: u64) {
+        self.0.lock().unwrap().states = states;
+    }
+
+    fn best_so_far(&self, best: u64) {
+        self.0.lock().unwrap().best = Some(best);
+    }
+}
+
+/// Passes every update on to both
+struct Both<'a>(&'a dyn Progress, &'a dyn Progress);
+
+impl Progress for Both<'_> {
+    fn items_done(&self, done: usize, total: usize) {
+        self.0.items_done(done, total);
+        self.1.items_done(done, total);
+    }
+
+    fn states_explored(&self, states: u64) {
+        self.0.states_explored(states);
+        self.1.states_explored(states);
+    }
+
+    fn best_so_far(&self, best: u64) {
+        self.0.best_so_far(best);
+        self.1.best_so_far(best);
+    }
+}
+
+/// Shows the progress of the running searches on one line of stderr, redrawn at most a few times
+/// a second.
+///
+/// There is a single display, so the days that run at once share the line instead of overwriting
+/// each other. When stderr is not a terminal every redraw of a search is printed on its own line
+/// instead.
+struct StderrProgress {
+    board: Mutex<ProgressBoard>,
+}
 
+struct ProgressBoard {
+    /// The running searches, in the order they started
+    searches: Vec<(u64, String, SearchReport)>,
+    next_id: u64,
+    drawn_at: Option<Instant>,
+}
+
+/// The line of one search on the [`StderrProgress`] display, taken off it when dropped
+struct SearchLine<'a> {
+    display: &'a StderrProgress,
+    id: u64,
+}
+
+static PROGRESS: StderrProgress = StderrProgress {
+    board: Mutex::new(ProgressBoard {
+        searches: Vec::new(),
+        next_id: 0,
+        drawn_at: None,
+    }),
+};
+
+impl StderrProgress {
+    const REDRAW_INTERVAL: Duration = Duration::from_millis(200);
+
+    fn search(&self, day: u8, part: Part) -> SearchLine<'_> {
+        let mut board = self.board.lock().unwrap();
+        let id = board.next_id;
+        board.next_id += 1;
+        let prefix = format!("day {} {}", day, part);
+        board.searches.push((id, prefix, SearchReport::default()));
+        SearchLine { display: self, id }
+    }
+
+    fn update(&self, id: u64, force: bool, update: impl FnOnce(&mut SearchReport)) {
+        let mut board = self.board.lock().unwrap();
+        let Some(index) = board.searches.iter().position(|search| search.0 == id) else {
+            return;
+        };
+        update(&mut board.searches[index].2);
+        let recently_drawn = board
+            .drawn_at
+            .is_some_and(|t| t.elapsed() < Self::REDRAW_INTERVAL);
+        if !force && recently_drawn {
+            return;
+        }
+        board.drawn_at = Some(Instant::now());
+
+        if io::stderr().is_terminal() {
+            let text: Vec<_> = board
+                .searches
+                .iter()
+                .map(|(_, prefix, report)| describe(prefix, report))
+                .collect();
+            eprint!("\r\x1b[2K{}", text.join(" | "));
+        } else {
+            let (_, prefix, report) = &board.searches[index];
+            eprintln!("{}", describe(prefix, report));
+        }
+    }
+
+    /// Takes a finished search off the line, and clears the line if it was the last one drawn
+    fn finish(&self, id: u64) {
+        let mut board = self.board.lock().unwrap();
+        board.searches.retain(|search| search.0 != id);
+        if board.searches.is_empty() && board.drawn_at.is_some() && io::stderr().is_terminal() {
+            eprint!("\r\x1b[2K");
+            board.drawn_at = None;
+        }
+    }
+}
+
+fn describe(prefix: &str, report: &SearchReport) -> String {
+    let mut text = prefix.to_string();
+    if let Some((done, total)) = report.items {
+        text += &format!(", {}/{} done", done, total);
+    }
+    if report.states > 0 {
+        text += &format!(", {} states explored", report.states);
+    }
+    if let Some(best) = report.best {
+        text += &format!(", best so far {}", best);
+    }
+    text
+}
+
+impl Progress for SearchLine<'_> {
+    fn items_done(&self, done: usize, total: usize) {
+        self.display
+            .update(self.id, true, |report| report.items = Some((done, total)));
+    }
+
+    fn states_explored(&self, states: u64) {
+        self.display
+            .update(self.id, false, |report| report.states = states);
+    }
+
+    fn best_so_far(&self, best: u64) {
+        self.display
+            .update(self.id, false, |report| report.best = Some(best));
+    }
+}
+
+impl Drop for SearchLine<'_> {
+    fn drop(&mut self) {
+        self.display.finish(self.id);
+    }
+}
+
+impl Context<'_> {
+    fn run(&self, day: &DayInfo) -> Result<Timed, RunError> {
+        if self.stream {
+            return self.run_streamed(day);
+        }
+        // a picture that cannot be drawn is reported before the day is solved for nothing
+        let render = match self.render {
+            Some(path) => Some((path, self.renderer(day, path)?)),
+            None => None,
+        };
+
+        let start = Instant::now();
+        let input = self
+            .input
+            .read(self.day, self.basic, &self.options.inputs_dir)
+            .map_err(RunError::Input)?;
+        let read_time = start.elapsed();
+
+        let cancel = match self.options.timeout {
+            Some(timeout) => CancelToken::with_timeout(timeout),
+            None => CancelToken::new(),
+        };
+        let line = self
+            .options
+            .progress
+            .then(|| PROGRESS.search(self.day, self.part()));
+        let display: &dyn Progress = match &line {
+            Some(line) => line,
+            None => &NoProgress,
+        };
+        let hooks = Both(&self.diagnostics, display);
+        let measurement = memory::ENABLED.then(Measurement::start);
+        // the ledger is what day 1 parses its input into, so it is kept rather than parsed again
+        let solution = match self.ledger {
+            Some(_) => {
+                days::solve_with_parsed::<days::d1::Day>(&input, self.part(), &cancel, &hooks)
+                    .map(|(solution, ledger)| (solution, Some(ledger)))
+            }
+            None => {
+                (day.solve)(&input, self.part(), &cancel, &hooks).map(|solution| (solution, None))
+            }
+        };
+        let memory = measurement.map(Measurement::end);
+        drop(line);
+        let (solution, ledger) = solution.map_err(RunError::Solve)?;
+
+        if let Some((path, render)) = render {
+            let image = render(&input, self.part()).map_err(RunError::Solve)?;
+            render::save(&image, path)
+                .map_err(|e| RunError::Render(format!("{}: {}", path.display(), e)))?;
+        }
+
+        Ok(Timed {
+            answer: solution.answer,
+            read_time,
+            parse_time: solution.parse_time,
+            solve_time: solution.solve_time,
+            memory,
+            ledger,
+        })
+    }
+
+    /// Solves the day reading its input one line at a time, so all of the time is spent solving
+    fn run_streamed(&self, day: &DayInfo) -> Result<Timed, RunError> {
+        let stream = day.stream.ok_or(RunError::NotStreamed(self.day))?;
+        let inputs_dir = &self.options.inputs_dir;
+        let mut reader = self
+            .input
+            .open(self.day, self.basic, inputs_dir)
+            .map_err(RunError::Input)?;
+
+        let measurement = memory::ENABLED.then(Measurement::start);
+        let start = Instant::now();
+        let answer = stream(&mut reader, self.part()).map_err(|e| match e {
+            StreamError::Read(source) => RunError::Input(InputError {
+                path: self.input.path(self.day, self.basic, inputs_dir),
+                source,
+            }),
+            StreamError::Solve(e) => RunError::Solve(e),
+        })?;
+        let solve_time = start.elapsed();
+
+        Ok(Timed {
+            answer,
+            read_time: Duration::ZERO,
+            parse_time: Duration::ZERO,
+            solve_time,
+            memory: measurement.map(Measurement::end),
+            ledger: None,
+        })
+    }
+
+    fn renderer(&self, day: &DayInfo, path: &Path) -> Result<days::RenderFn, RunError> {
+        let render = day.render.ok_or_else(|| {
+            RunError::Render(format!("day {} has no state that can be drawn", self.day))
+        })?;
+        if render::Format::from_path(path).is_none() {
+            return Err(RunError::Render(format!(
+                "{}: expected a .png, .ppm or .svg file",
+                path.display()
+            )));
+        }
+        Ok(render)
+    }
+
+    fn run_day(&self) -> Result<Timed, RunError> {
+        let day = days::day(self.day).ok_or(RunError::NoSuchDay(self.day))?;
+        if self.ledger.is_some() && self.day != 1 {
+            return Err(RunError::NoLedger(self.day));
+        }
+        self.run(day)
+    }
+
+    fn part(&self) -> Part {
+        if self.basic {
+            Part::Basic
+        } else {
+            Part::Advanced
+        }
+    }
+
+    /// The JSON record of a run, with either the answer and its timings or the error, and the
+    /// diagnostics the search reported
+    fn to_json(&self, result: &Result<Timed, RunError>) -> JsonObject {
+        let obj = JsonObject::new()
+            .number("day", self.day)
+            .string("part", &self.part().to_string())
+            .string("input", &self.input.to_string());
+        let obj = match result {
+            Ok(timed) => {
+                let obj = obj
+                    .string("answer", &timed.answer)
+                    .object("timings", timed.to_json());
+                let obj = match (self.ledger, &timed.ledger) {
+                    (Some(report), Some(ledger)) => obj.object("ledger", report.to_json(ledger)),
+                    _ => obj,
+                };
+                obj.null("error")
+            }
+            Err(e) => obj
+                .null("answer")
+                .null("timings")
+                .object("error", e.to_json()),
+        };
+        match self.diagnostics.to_json() {
+            Some(diagnostics) => obj.object("diagnostics", diagnostics),
+            None => obj.null("diagnostics"),
+        }
+    }
+}
+
+fn run_single(args: RunArgs, options: &SolveOptions, format: Format) -> ExitCode {
     let c = Context {
-        big: cli.example == Example::Big,
-        basic: cli.part == Part::Basic,
-        day: cli.day,
+        render: args.render.as_deref(),
+        stream: args.stream,
+        ledger: (args.top.is_some() || args.stats).then_some(LedgerReport {
+            top: args.top,
+            stats: args.stats,
+        }),
+        ..Context::new(&args.input, options, args.day, args.part)
     };
 
-    match cli.day {
-        1 => c.run(d1::Day),
-        2 => c.run(d2::Day),
-        3 => c.run(d3::Day),
-        4 => c.run(d4::Day),
-        5 => c.run(d5::Day),
-        6 => c.run(d6::Day),
-        7 => c.run(d7::Day),
-        8 => c.run(d8::Day),
-        9 => c.run(d9::Day),
-        10 => c.run(d10::Day),
-        11 => c.run(d11::Day),
-        12 => c.run(d12::Day),
-        13 => c.run(d13::Day),
-        14 => c.run(d14::Day),
-        15 => c.run(d15::Day),
-        16 => c.run(d16::Day),
-        17 => c.run(d17::Day),
-        18 => c.run(d18::Day),
-        19 => c.run(d19::Day),
-        20 => c.run(d20::Day),
-        21 => c.run(d21::Day),
-        22 => c.run(d22::Day),
-        23 => c.run(d23::Day),
-        24 => c.run(d24::Day),
-        25 => c.run(d25::Day),
-        _ => println!("Not a valid day")
+    let result = c.run_day();
+    if format == Format::Json {
+        println!("{}", c.to_json(&result));
+    }
+    match result {
+        Ok(timed) => {
+            if format == Format::Text {
+                println!("{}", timed.answer);
+                if let (Some(report), Some(ledger)) = (c.ledger, &timed.ledger) {
+                    report.print(ledger);
+                }
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            e.exit_code()
+        }
+    }
+}
+
+fn run_all(example: Example, options: &SolveOptions, format: Format) -> ExitCode {
+    let input = InputSource::Example(example);
+    let mut total = Duration::ZERO;
+    let mut failed = false;
+    let start = Instant::now();
+
+    if format == Format::Text {
+        let threads = rayon::current_num_threads();
+        // the allocations are counted for the whole process, so the days are measured one by one
+        if threads > 1 && !memory::ENABLED {
+            println!(
+                "the days run {} at a time, so their timings include the contention; \
+                 use --jobs 1 for exact ones",
+                threads
+            );
+        }
+        print!(
+            "{:>3}  {:<8}  {:<24}  {:>12}  {:>12}  {:>12}",
+            "day", "part", "answer", "read", "parse", "solve"
+        );
+        if memory::ENABLED {
+            print!("  {:>10}  {:>12}", "peak heap", "allocations");
+        }
+        println!();
+    }
+
+    let runs: Vec<_> = days::registry()
+        .iter()
+        .flat_map(|info| [Part::Basic, Part::Advanced].map(|part| (info, part)))
+        .collect();
+    let run = |&(info, part): &(&DayInfo, Part)| {
+        let c = Context::new(&input, options, info.number, part);
+        let result = c.run(info);
+        (c, result)
+    };
+    // the days run concurrently unless their allocations are counted, but their results are
+    // collected and printed in order
+    let results: Vec<_> = if memory::ENABLED {
+        runs.iter().map(run).collect()
+    } else {
+        runs.par_iter().map(run).collect()
+    };
+
+    for (c, result) in results {
+        let (day, part) = (c.day, c.part());
+        if format == Format::Json {
+            println!("{}", c.to_json(&result));
+            failed |= result.is_err();
+            continue;
+        }
+        match result {
+            Ok(timed) => {
+                // multi-line answers (like the CRT of day 10) continue below the row
+                let mut lines = timed.answer.lines();
+                print!(
+                    "{:>3}  {:<8}  {:<24}  {:>12.3?}  {:>12.3?}  {:>12.3?}",
+                    day,
+                    part,
+                    lines.next().unwrap_or(""),
+                    timed.read_time,
+                    timed.parse_time,
+                    timed.solve_time
+                );
+                if let Some(memory) = timed.memory {
+                    print!(
+                        "  {:>10}  {:>12}",
+                        Bytes(memory.peak_bytes),
+                        memory.allocations
+                    );
+                }
+                println!();
+                for line in lines {
+                    println!("{:>3}  {:<8}  {}", "", "", line);
+                }
+                total += timed.read_time + timed.parse_time + timed.solve_time;
+            }
+            Err(e) => {
+                println!("{:>3}  {:<8}  error: {}", day, part, e);
+                failed = true;
+            }
+        }
+    }
+    if format == Format::Text {
+        println!("total: {:.3?}, wall time: {:.3?}", total, start.elapsed());
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn verify(manifest: Option<PathBuf>, options: &SolveOptions, format: Format) -> ExitCode {
+    let manifest = manifest.unwrap_or_else(|| options.inputs_dir.join(MANIFEST_FILE_NAME));
+    let expected_answers = match answers::load_manifest(&manifest) {
+        Ok(answers) => answers,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut passed = 0;
+    let mut mismatched = 0;
+    let mut failed = 0;
+
+    for a in expected_answers {
+        let c = Context::new(&a.input, options, a.day, a.part);
+        let prefix = format!("{:>3}  {:<8}  {}", a.day, a.part, a.input);
+
+        let result = c.run_day();
+        if format == Format::Json {
+            let status = match &result {
+                Ok(timed) if answers::matches(&a.expected, &timed.answer) => {
+                    passed += 1;
+                    "pass"
+                }
+                Ok(_) => {
+                    mismatched += 1;
+                    "mismatch"
+                }
+                Err(_) => {
+                    failed += 1;
+                    "fail"
+                }
+            };
+            let record = c
+                .to_json(&result)
+                .string("expected", &a.expected)
+                .string("status", status);
+            println!("{}", record);
+            continue;
+        }
+        match result {
+            Ok(timed) if answers::matches(&a.expected, &timed.answer) => {
+                println!("pass      {}", prefix);
+                passed += 1;
+            }
+            Ok(timed) => {
+                println!(
+                    "MISMATCH  {}: expected {}, got {}",
+                    prefix,
+                    answers::escape(&a.expected),
+                    answers::escape(timed.answer.trim_end())
+                );
+                mismatched += 1;
+            }
+            Err(e) => {
+                println!("FAIL      {}: {}", prefix, e);
+                failed += 1;
+            }
+        }
+    }
+
+    if format == Format::Text {
+        println!(
+            "{} passed, {} mismatched, {} failed",
+            passed, mismatched, failed
+        );
+    }
+
+    if mismatched + failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn serve(port: u16, max_input: usize, max_connections: usize, options: &SolveOptions) -> ExitCode {
+    let defaults = serve::Config::default();
+    let config = serve::Config {
+        max_body_len: max_input,
+        solve_timeout: options.timeout.unwrap_or(defaults.solve_timeout),
+        max_connections,
+        ..defaults
+    };
+    let server = match Server::bind(("127.0.0.1", port), config) {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("error: could not listen on port {}: {}", port, e);
+            return ExitCode::FAILURE;
+        }
     };
+    if let Ok(addr) = server.local_addr() {
+        eprintln!("listening on http://{}", addr);
+    }
+    match server.run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
 }
 
-#[derive(ValueEnum, Clone, PartialEq, Eq)]
-enum Example {
-    #[value(alias = "s")]
-    Small,
+fn generate(day: u8, seed: u64, size: Option<usize>) -> ExitCode {
+    let Some(generator) = gen::generator_for(day) else {
+        eprintln!("error: {} is not a valid day", day);
+        return ExitCode::FAILURE;
+    };
+    print!(
+        "{}",
+        generator.generate(seed, size.unwrap_or(generator.default_size))
+    );
+    ExitCode::SUCCESS
+}
 
-    #[value(alias = "b")]
-    Big,
+fn animate(args: AnimateArgs, options: &SolveOptions) -> ExitCode {
+    match record(&args, options) {
+        Ok((frames, steps)) => {
+            println!(
+                "{} frames of {} steps written to {}",
+                frames,
+                steps,
+                args.out.display()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            e.exit_code()
+        }
+    }
 }
 
-#[derive(ValueEnum, Clone, PartialEq, Eq)]
-enum Part {
-    #[value(alias = "b", alias = "1")]
-    Basic,
+/// Simulates the day and writes its frames, and tells how many there are of how many steps
+fn record(args: &AnimateArgs, options: &SolveOptions) -> Result<(usize, usize), RunError> {
+    let day = days::day(args.day).ok_or(RunError::NoSuchDay(args.day))?;
+    let animate = day.animate.ok_or_else(|| {
+        RunError::Render(format!("day {} is not simulated step by step", args.day))
+    })?;
+    let is_gif = args
+        .out
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("gif"));
+    if !is_gif && render::Format::from_path(&args.out).is_none() {
+        return Err(RunError::Render(format!(
+            "{}: expected a .gif, .png, .ppm or .svg file",
+            args.out.display()
+        )));
+    }
+
+    let input = args
+        .input
+        .read(args.day, args.part == Part::Basic, &options.inputs_dir)
+        .map_err(RunError::Input)?;
+    let mut recorder = render::Recorder::new(args.stride.get(), args.max_frames);
+    animate(&input, args.part, &mut recorder).map_err(RunError::Solve)?;
 
-    #[value(alias = "a", alias = "2")]
-    Advanced,
+    let delay = Duration::from_millis(args.delay);
+    render::save_frames(recorder.frames(), args.crop, delay, &args.out)
+        .map_err(|e| RunError::Render(format!("{}: {}", args.out.display(), e)))?;
+    Ok((recorder.frames().len(), recorder.steps()))
+}
+
+fn list() -> ExitCode {
+    for day in days::registry() {
+        println!("{:>3}  {}", day.number, day.title);
+        for part in [Part::Basic, Part::Advanced] {
+            println!(
+                "     {:<8}  {} ({})",
+                part,
+                day.part_name(part),
+                day.answer_type
+            );
+        }
+        if let Some(generator) = gen::generator_for(day.number) {
+            println!(
+                "     {:<8}  {} (default {})",
+                "size", generator.size, generator.default_size
+            );
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+/// The filter of `$ADVENT22_LOG`, whose default level `-v` overrides
+fn log_filter(verbose: u8) -> Result<Filter, String> {
+    let filter = match std::env::var(log::LOG_ENV_VAR) {
+        Ok(spec) => spec.parse()?,
+        Err(_) => Filter::default(),
+    };
+    let level = match verbose {
+        0 => return Ok(filter),
+        1 => Level::Info,
+        2 => Level::Debug,
+        _ => Level::Trace,
+    };
+    Ok(filter.with_default(Some(level)))
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match log_filter(cli.verbose) {
+        Ok(filter) => log::init(filter),
+        Err(e) => {
+            eprintln!("error: ${}: {}", log::LOG_ENV_VAR, e);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    if let Some(jobs) = cli.options.jobs {
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(jobs.get());
+        if let Err(e) = pool.build_global() {
+            eprintln!("error: could not start {} threads: {}", jobs, e);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    match cli.command {
+        Some(Command::RunAll { example }) => run_all(example, &cli.options, cli.format),
+        Some(Command::Verify { manifest }) => verify(manifest, &cli.options, cli.format),
+        Some(Command::List) => list(),
+        Some(Command::Serve {
+            port,
+            max_input,
+            max_connections,
+        }) => serve(port, max_input, max_connections, &cli.options),
+        Some(Command::Generate { day, seed, size }) => generate(day, seed, size),
+        Some(Command::Animate(args)) => animate(args, &cli.options),
+        None => run_single(
+            cli.run
+                .expect("clap requires the run arguments without a subcommand"),
+            &cli.options,
+            cli.format,
+        ),
+    }
 }
 
 #[derive(Parser)]
+#[command(
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true,
+    after_help = "Exit codes: 1 if the input could not be read or the picture not written, \
+                  3 if it could not be parsed, 4 if the input is not supported, \
+                  5 if there is no solution and 6 if the run timed out"
+)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    run: Option<RunArgs>,
+
+    #[command(flatten)]
+    options: SolveOptions,
+
+    /// Output format of the answers; progress and errors always go to stderr, and the JSON
+    /// records also hold what the searches reported about themselves
+    #[arg(long, global = true, value_enum, default_value = "text")]
+    format: Format,
+
+    /// Log on stderr how long the days take to parse and solve, then what they find along the
+    /// way with -vv, then every step with -vvv. `$ADVENT22_LOG` gives the levels of single days,
+    /// like `d19=debug,d16=off`
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+}
+
+/// How every day is run, whatever the command
+#[derive(Args)]
+struct SolveOptions {
+    /// Directory containing the bundled examples (as `d{day}/ex1.txt` and `d{day}/ex2.txt`).
+    /// Defaults to `$ADVENT22_INPUTS_DIR`, else to the `days` or `src/days` directory next to
+    /// or above the executable
+    #[arg(long, global = true, default_value_os_t = default_inputs_dir())]
+    inputs_dir: PathBuf,
+
+    /// Stop the slow searches after this many seconds and report a partial answer where the day
+    /// can produce one
+    #[arg(long, global = true, value_name = "SECS", value_parser = parse_timeout)]
+    timeout: Option<Duration>,
+
+    /// Show the progress of the slow searches on stderr
+    #[arg(long, global = true)]
+    progress: bool,
+
+    /// How many threads run the days and the searches in parallel [default: one per core]
+    #[arg(long, short, global = true, value_name = "N")]
+    jobs: Option<NonZeroUsize>,
+}
+
+#[derive(Args)]
+struct AnimateArgs {
+    day: u8,
+
+    /// `small` or `big` for the bundled examples, `-` for stdin, or the path to an input file
+    #[arg(value_name = "INPUT", default_value = "small")]
+    input: InputSource,
+
+    #[arg(value_enum, default_value = "basic")]
+    part: Part,
+
+    /// A .gif file, or a .png, .ppm or .svg one after which the pictures are numbered, like
+    /// `frame-0000.png` for `frame.png`
+    #[arg(long, value_name = "PATH")]
+    out: PathBuf,
+
+    /// Keep one step in this many
+    #[arg(long, value_name = "N", default_value = "1")]
+    stride: NonZeroUsize,
+
+    /// The most frames that are kept; beyond it every other one is dropped and the stride
+    /// doubled, so that the frames still cover the whole simulation
+    #[arg(long, value_name = "N", default_value_t = 200, value_parser = parse_max_frames)]
+    max_frames: usize,
+
+    /// Draw only this rectangle of the map, in the coordinates of the day
+    #[arg(long, value_name = "X,Y,WIDTH,HEIGHT", value_parser = parse_crop)]
+    crop: Option<Bounds>,
+
+    /// How long each frame of a GIF shows
+    #[arg(long, value_name = "MS", default_value_t = 100)]
+    delay: u64,
+}
+
+fn parse_max_frames(s: &str) -> Result<usize, String> {
+    match s.parse() {
+        Ok(n) if n >= 2 => Ok(n),
+        _ => Err(format!(
+            "'{}' is not a number of frames of at least 2, for the start and the end",
+            s
+        )),
+    }
+}
+
+fn parse_crop(s: &str) -> Result<Bounds, String> {
+    let numbers: Vec<i32> = s
+        .split(',')
+        .map(|n| n.trim().parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("'{}' is not four numbers like 0,0,40,30", s))?;
+    let [x, y, width, height] = numbers[..] else {
+        return Err(format!("'{}' is not four numbers like 0,0,40,30", s));
+    };
+    if width < 1 || height < 1 {
+        return Err(format!("the {}x{} rectangle is empty", width, height));
+    }
+    Ok(Bounds {
+        left: x,
+        top: y,
+        right: x + width - 1,
+        bottom: y + height - 1,
+    })
+}
+
+fn parse_timeout(s: &str) -> Result<Duration, String> {
+    let secs: f64 = s
+        .parse()
+        .map_err(|_| format!("'{}' is not a number of seconds", s))?;
+    Duration::try_from_secs_f64(secs).map_err(|e| e.to_string())
+}
+
+#[derive(Args)]
+struct RunArgs {
     #[arg()]
     day: u8,
 
-    #[arg(value_enum)]
-    example: Example,
+    /// `small` or `big` for the bundled examples, `-` for stdin, or the path to an input file
+    #[arg(value_name = "INPUT")]
+    input: InputSource,
 
     #[arg(value_enum)]
     part: Part,
+
+    /// Also draw the state the day ends in, for the days whose state is a map, as a .png, .ppm
+    /// or .svg picture
+    #[arg(long, value_name = "PATH")]
+    render: Option<PathBuf>,
+
+    /// Read the input a line at a time instead of all at once, so that inputs larger than memory
+    /// can be solved, for the days whose input is a list of lines; the other days answer with
+    /// the list of those that can. All of the time is then reported as solving
+    #[arg(long, conflicts_with = "render")]
+    stream: bool,
+
+    /// Day 1 only: also rank the N elves carrying the most calories, with the number of items
+    /// each carries. Elves carrying as many calories share a rank
+    #[arg(long, value_name = "N", conflicts_with = "stream")]
+    top: Option<usize>,
+
+    /// Day 1 only: also show how many elves, items and calories there are, and the mean and
+    /// median calories of the elves
+    #[arg(long, conflicts_with = "stream")]
+    stats: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run both parts of every day and print the answers with timings
+    #[command(alias = "all")]
+    RunAll {
+        #[arg(value_enum, default_value = "small")]
+        example: Example,
+    },
+
+    /// List every day with its title, its parts, the type of its answers and the size of its
+    /// generated inputs
+    List,
+
+    /// Solve the inputs posted to `/day/{n}/part/{1|2}` on a local HTTP port and answer in JSON
+    ///
+    /// Each request is solved for at most `--timeout` seconds, 30 by default.
+    Serve {
+        #[arg(long, default_value_t = 2022)]
+        port: u16,
+
+        /// The largest input that is accepted, in bytes
+        #[arg(long, value_name = "BYTES", default_value_t = 1024 * 1024)]
+        max_input: usize,
+
+        /// How many requests are served at once; the others are answered as busy
+        #[arg(long, value_name = "N", default_value_t = 16)]
+        max_connections: usize,
+    },
+
+    /// Print a random but valid input for a day, the same one for the same seed and size
+    Generate {
+        day: u8,
+
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+
+        /// What it counts depends on the day, like elves for day 1 or valves for day 16, and is
+        /// shown by `list` [default: about the size of the small example]
+        #[arg(long)]
+        size: Option<usize>,
+    },
+
+    /// Write the steps of a simulated day as an animated GIF, or as one numbered picture each
+    Animate(AnimateArgs),
+
+    /// Check the answers of every day against an answers manifest
+    Verify {
+        /// Lines of `<day> <part> <input> <expected answer>` [default: answers.txt in the inputs
+        /// directory]
+        #[arg(long)]
+        manifest: Option<PathBuf>,
+    },
 }
@@ -0,0 +1,153 @@
+//! How much memory the days use, counted by an allocator that wraps the system one.
+//!
+//! With the `alloc-stats` feature the command line installs the [`CountingAllocator`] as its
+//! global allocator, and a [`Measurement`] tells how many allocations a run made and how far the
+//! heap grew during it. The library leaves the global allocator to the binaries that link it, so
+//! any other binary must install the [`CountingAllocator`] itself for the counts to be kept.
+//! The counters are shared by all the threads, so only one run should be measured at a time,
+//! though that run may use as many threads as it likes.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt::{self, Display},
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+};
+
+/// Whether the allocations are counted, which they are with the `alloc-stats` feature
+pub const ENABLED: bool = cfg!(feature = "alloc-stats");
+
+/// The bytes allocated and not yet freed
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+/// The most bytes allocated at once since the last measurement started
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// The system allocator, counting the allocations and the bytes in use
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn grow(size: usize) {
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn shrink(size: usize) {
+        CURRENT.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::shrink(layout.size());
+    }
+
+    /// Counted as freeing the old block and allocating the new one
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            Self::shrink(layout.size());
+            Self::grow(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// What a run allocated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// The most the heap grew above what it held when the run started
+    pub peak_bytes: usize,
+    pub allocations: u64,
+}
+
+/// Counts the allocations from its start to its end
+pub struct Measurement {
+    base: usize,
+    allocations: u64,
+}
+
+impl Measurement {
+    pub fn start() -> Self {
+        let base = CURRENT.load(Ordering::Relaxed);
+        PEAK.store(base, Ordering::Relaxed);
+        Measurement {
+            base,
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn end(self) -> MemoryStats {
+        MemoryStats {
+            peak_bytes: PEAK.load(Ordering::Relaxed).saturating_sub(self.base),
+            allocations: ALLOCATIONS.load(Ordering::Relaxed) - self.allocations,
+        }
+    }
+}
+
+/// A number of bytes, shown in the largest unit that keeps it above 1, like `12.3 MiB`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bytes(pub usize);
+
+impl Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+        if self.0 < 1024 {
+            return f.pad(&format!("{} B", self.0));
+        }
+        let mut size = self.0 as f64 / 1024.0;
+        let mut unit = 0;
+        while size >= 1024.0 && unit + 1 < UNITS.len() {
+            size /= 1024.0;
+            unit += 1;
+        }
+        f.pad(&format!("{:.1} {}", size, UNITS[unit]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc-stats")]
+    #[global_allocator]
+    static GLOBAL: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn bytes_are_shown_in_their_unit() {
+        assert_eq!(Bytes(0).to_string(), "0 B");
+        assert_eq!(Bytes(1023).to_string(), "1023 B");
+        assert_eq!(Bytes(1536).to_string(), "1.5 KiB");
+        assert_eq!(Bytes(5 << 30).to_string(), "5.0 GiB");
+        assert_eq!(format!("{:>9}", Bytes(1 << 20)), "  1.0 MiB");
+    }
+
+    #[cfg(feature = "alloc-stats")]
+    #[test]
+    fn a_measurement_sees_what_was_allocated() {
+        let measurement = Measurement::start();
+        let big = vec![0u8; 64 << 20];
+        drop(std::hint::black_box(big));
+        let stats = measurement.end();
+        // the other tests allocate and free at the same time, so this is only roughly the size
+        assert!(stats.peak_bytes >= 32 << 20);
+        assert!(stats.allocations >= 1);
+    }
+}
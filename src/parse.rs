@@ -0,0 +1,260 @@
+//! Parsing the lines of the inputs piece by piece, with errors that point at where a line stops
+//! looking as expected, like `line 4, column 23: expected an integer`.
+//!
+//! A [`Parser`] moves along one line. Each of its methods reads one piece (a literal, a token, an
+//! integer, a list) and either moves past it or fails with a [`SolveError::Parse`] at the column
+//! where the piece was expected, so that parsers of whole lines are written as a sequence of
+//! pieces with `?` after each.
+
+use std::str::FromStr;
+
+use crate::days::SolveError;
+
+/// A place in the input. Both `line` and `column` start at 1, as in [`SolveError::Parse`], and
+/// `column` counts characters rather than bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn error(self, message: impl Into<String>) -> SolveError {
+        SolveError::parse(self.line, self.column, message)
+    }
+}
+
+/// The types [`Parser::integer`] reads
+pub trait Integer: FromStr {
+    /// Whether the type has negative numbers, which are written with a minus sign
+    const SIGNED: bool;
+}
+
+macro_rules! integers {
+    ($signed:expr => $($t:ty),*) => {
+        $(impl Integer for $t {
+            const SIGNED: bool = $signed;
+        })*
+    };
+}
+
+integers!(true => i8, i16, i32, i64, i128, isize);
+integers!(false => u8, u16, u32, u64, u128, usize);
+
+#[derive(Debug)]
+pub struct Parser<'a> {
+    text: &'a str,
+    /// The byte offset of what is left to read
+    pos: usize,
+    line: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// A parser of the line at `idx`, counting from 0 as [`str::lines`] does
+    pub fn new(text: &'a str, idx: usize) -> Self {
+        Parser {
+            text,
+            pos: 0,
+            line: idx + 1,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        Span {
+            line: self.line,
+            column: self.text[..self.pos].chars().count() + 1,
+        }
+    }
+
+    /// What is left of the line
+    pub fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    /// An error at what is left of the line
+    pub fn error(&self, message: impl Into<String>) -> SolveError {
+        self.span().error(message)
+    }
+
+    /// Moves past `literal` if the line goes on with it, telling whether it did
+    pub fn optional(&mut self, literal: &str) -> bool {
+        let found = self.rest().starts_with(literal);
+        if found {
+            self.pos += literal.len();
+        }
+        found
+    }
+
+    pub fn literal(&mut self, literal: &str) -> Result<(), SolveError> {
+        match self.optional(literal) {
+            true => Ok(()),
+            false => Err(self.error(format!("expected '{}'", literal))),
+        }
+    }
+
+    /// Moves past the first of `literals` the line goes on with
+    pub fn one_of(&mut self, literals: &[&'static str]) -> Result<&'static str, SolveError> {
+        if let Some(&literal) = literals.iter().find(|&&l| self.optional(l)) {
+            return Ok(literal);
+        }
+        let quoted: Vec<_> = literals.iter().map(|l| format!("'{}'", l)).collect();
+        let expected = match quoted.split_last() {
+            Some((last, [])) => last.clone(),
+            Some((last, others)) => format!("{} or {}", others.join(", "), last),
+            None => "nothing".to_string(),
+        };
+        Err(self.error(format!("expected {}", expected)))
+    }
+
+    /// Skips any spaces
+    pub fn spaces(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start_matches(' ').len();
+    }
+
+    /// Reads the longest run of at least one character that `accept`s, which is `what` the line
+    /// should go on with
+    pub fn token(
+        &mut self,
+        what: &str,
+        accept: impl Fn(char) -> bool,
+    ) -> Result<&'a str, SolveError> {
+        let rest = self.rest();
+        let len = rest.find(|c| !accept(c)).unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error(format!("expected {}", what)));
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    /// Reads digits, with a minus sign before them if `T` is signed, as a number of type `T`
+    pub fn integer<T: Integer>(&mut self) -> Result<T, SolveError> {
+        let (start, span) = (self.pos, self.span());
+        if !T::SIGNED && self.rest().starts_with('-') {
+            return Err(span.error("expected a digit"));
+        }
+        self.optional("-");
+        let parsed = match self.token("an integer", |c| c.is_ascii_digit()) {
+            Ok(_) => self.text[start..self.pos]
+                .parse()
+                .map_err(|_| span.error("the integer is out of range")),
+            Err(_) => Err(span.error("expected an integer")),
+        };
+        if parsed.is_err() {
+            self.pos = start;
+        }
+        parsed
+    }
+
+    /// Reads one or more items with `sep` between them
+    pub fn separated<T>(
+        &mut self,
+        sep: &str,
+        mut item: impl FnMut(&mut Self) -> Result<T, SolveError>,
+    ) -> Result<Vec<T>, SolveError> {
+        let mut items = vec![item(self)?];
+        while self.optional(sep) {
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+
+    /// Checks that the whole line was read
+    pub fn end(&self) -> Result<(), SolveError> {
+        match self.rest().is_empty() {
+            true => Ok(()),
+            false => Err(self.error(format!("unexpected '{}'", self.rest()))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_line_is_read_piece_by_piece() {
+        let mut p = Parser::new("at x=-2, y=15: 3 4 5", 0);
+        p.literal("at x=").unwrap();
+        assert_eq!(p.integer::<i32>(), Ok(-2));
+        assert_eq!(p.one_of(&[", z=", ", y="]), Ok(", y="));
+        assert_eq!(p.integer::<u8>(), Ok(15));
+        assert!(!p.optional(";"));
+        p.literal(":").unwrap();
+        p.spaces();
+        assert_eq!(p.separated(" ", Parser::integer::<u32>), Ok(vec![3, 4, 5]));
+        assert_eq!(p.end(), Ok(()));
+    }
+
+    #[test]
+    fn errors_point_at_what_was_expected() {
+        let mut p = Parser::new("Valve AA has flow rate=x", 3);
+        p.literal("Valve ").unwrap();
+        assert_eq!(p.token("a name", |c| c.is_ascii_uppercase()), Ok("AA"));
+        assert_eq!(
+            p.token("a name", |c| c.is_ascii_uppercase()),
+            Err(SolveError::parse(4, 9, "expected a name"))
+        );
+        assert_eq!(
+            p.one_of(&[" has", " had", " has flow"])
+                .and_then(|_| p.literal("rate")),
+            Err(SolveError::parse(4, 13, "expected 'rate'"))
+        );
+        p.literal(" flow rate=").unwrap();
+        let error = p.integer::<u32>().unwrap_err();
+        assert_eq!(error, SolveError::parse(4, 24, "expected an integer"));
+        assert_eq!(error.to_string(), "line 4, column 24: expected an integer");
+        assert_eq!(p.end(), Err(SolveError::parse(4, 24, "unexpected 'x'")));
+    }
+
+    #[test]
+    fn failed_integers_are_not_read() {
+        let mut p = Parser::new("-x 300", 0);
+        assert_eq!(
+            p.integer::<i32>(),
+            Err(SolveError::parse(1, 1, "expected an integer"))
+        );
+        assert_eq!(p.rest(), "-x 300");
+        p.literal("-x ").unwrap();
+        assert_eq!(
+            p.integer::<u8>(),
+            Err(SolveError::parse(1, 4, "the integer is out of range"))
+        );
+        assert_eq!(p.integer::<u16>(), Ok(300));
+    }
+
+    #[test]
+    fn unsigned_integers_have_no_sign() {
+        let mut p = Parser::new("-5", 0);
+        assert_eq!(
+            p.integer::<u32>(),
+            Err(SolveError::parse(1, 1, "expected a digit"))
+        );
+        assert_eq!(p.integer::<i32>(), Ok(-5));
+    }
+
+    #[test]
+    fn columns_count_characters() {
+        let mut p = Parser::new("café=x", 2);
+        p.literal("café=").unwrap();
+        assert_eq!(
+            p.integer::<i32>(),
+            Err(SolveError::parse(3, 6, "expected an integer"))
+        );
+        assert_eq!(p.end(), Err(SolveError::parse(3, 6, "unexpected 'x'")));
+    }
+
+    #[test]
+    fn alternatives_are_listed() {
+        let mut p = Parser::new("/", 0);
+        assert_eq!(
+            p.one_of(&["+", "-", "*"]),
+            Err(SolveError::parse(1, 1, "expected '+', '-' or '*'"))
+        );
+        assert_eq!(
+            p.one_of(&["+"]),
+            Err(SolveError::parse(1, 1, "expected '+'"))
+        );
+    }
+}
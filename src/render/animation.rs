@@ -0,0 +1,253 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use super::{default_scale, gif, write, Format, Image, Rgb};
+use crate::grid::{Bounds, Point};
+
+/// A picture of one step of a simulation, with the point of the map at its top left corner so
+/// that the frames of a map that grows line up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub origin: Point,
+    pub image: Image,
+}
+
+impl Frame {
+    pub fn new(origin: Point, image: Image) -> Self {
+        Frame { origin, image }
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        (!self.image.is_empty()).then(|| Bounds {
+            left: self.origin.x,
+            top: self.origin.y,
+            right: self.origin.x + self.image.width() as i32 - 1,
+            bottom: self.origin.y + self.image.height() as i32 - 1,
+        })
+    }
+}
+
+/// Keeps the frames of a simulation, drawing only the steps that it keeps.
+///
+/// One step in `stride` is kept. Once there are more than `limit` frames, every other one is
+/// dropped and the stride doubled, so that the frames of a long simulation still cover all of it.
+pub struct Recorder {
+    stride: usize,
+    limit: usize,
+    steps: usize,
+    frames: Vec<Frame>,
+    /// Whether the last step was kept, so that the end is not kept twice
+    kept_last: bool,
+}
+
+impl Recorder {
+    pub fn new(stride: usize, limit: usize) -> Self {
+        assert!(stride > 0, "the stride must be at least 1");
+        assert!(
+            limit >= 2,
+            "an animation needs room for its start and its end"
+        );
+        Recorder {
+            stride,
+            limit,
+            steps: 0,
+            frames: Vec::new(),
+            kept_last: false,
+        }
+    }
+
+    /// Called by a simulation at its start and after each of its steps
+    pub fn step(&mut self, draw: impl FnOnce() -> Frame) {
+        self.kept_last = self.steps.is_multiple_of(self.stride);
+        self.steps += 1;
+        if !self.kept_last {
+            return;
+        }
+        self.frames.push(draw());
+        if self.frames.len() > self.limit {
+            let mut index = 0;
+            self.frames.retain(|_| {
+                index += 1;
+                index % 2 == 1
+            });
+            self.stride *= 2;
+        }
+    }
+
+    /// Called once the simulation is over, to keep its end whatever the stride
+    pub fn end(&mut self, draw: impl FnOnce() -> Frame) {
+        if self.kept_last {
+            return;
+        }
+        if self.frames.len() == self.limit {
+            self.frames.pop();
+        }
+        self.frames.push(draw());
+        self.kept_last = true;
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+}
+
+/// Lays the frames out on one canvas that holds all of them, or only the part of it within
+/// `crop`, which is in the coordinates of the map
+fn compose(frames: &[Frame], crop: Option<Bounds>) -> io::Result<Vec<Image>> {
+    let union = frames
+        .iter()
+        .filter_map(Frame::bounds)
+        .reduce(|a, b| Bounds {
+            left: a.left.min(b.left),
+            top: a.top.min(b.top),
+            right: a.right.max(b.right),
+            bottom: a.bottom.max(b.bottom),
+        });
+    let canvas = match (union, crop) {
+        (Some(union), Some(crop)) => Some(Bounds {
+            left: union.left.max(crop.left),
+            top: union.top.max(crop.top),
+            right: union.right.min(crop.right),
+            bottom: union.bottom.min(crop.bottom),
+        })
+        .filter(|b| b.left <= b.right && b.top <= b.bottom),
+        (union, None) => union,
+        (None, Some(_)) => None,
+    };
+    let canvas = canvas
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "there is nothing to draw"))?;
+
+    Ok(frames
+        .iter()
+        .map(|frame| {
+            let (width, height) = (canvas.width() as usize, canvas.height() as usize);
+            let mut image = Image::new(width, height, Rgb::BLACK);
+            for (pos, &colour) in frame.image.iter() {
+                let x = frame.origin.x + pos.x as i32 - canvas.left;
+                let y = frame.origin.y + pos.y as i32 - canvas.top;
+                if let Some(pos) = image.pos_at(x, y) {
+                    image[pos] = colour;
+                }
+            }
+            image
+        })
+        .collect())
+}
+
+/// The path of the frame at `index`, like `frame-0007.png` for `frame.png`
+fn numbered(path: &Path, index: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = path.extension().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}-{:04}.{}", stem, index, extension))
+}
+
+/// Writes the frames as an animated GIF showing each for `delay`, or as one numbered picture
+/// each if the extension of `path` is that of a picture. All the frames have the size of the
+/// canvas that holds them, or of `crop`.
+pub fn save_frames(
+    frames: &[Frame],
+    crop: Option<Bounds>,
+    delay: Duration,
+    path: &Path,
+) -> io::Result<()> {
+    let images = compose(frames, crop)?;
+    let scale = default_scale(&images[0]);
+
+    let is_gif = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("gif"));
+    if is_gif {
+        let mut out = BufWriter::new(File::create(path)?);
+        gif::write(&images, scale, delay, &mut out)?;
+        return out.flush();
+    }
+
+    let format = Format::from_path(path).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "{}: expected a .gif, .png, .ppm or .svg file",
+                path.display()
+            ),
+        )
+    })?;
+    for (index, image) in images.iter().enumerate() {
+        let mut out = BufWriter::new(File::create(numbered(path, index))?);
+        write(image, format, scale, &mut out)?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Pos;
+
+    fn dot(x: i32, y: i32) -> Frame {
+        Frame::new(Point::new(x, y), Image::new(1, 1, Rgb::WHITE))
+    }
+
+    #[test]
+    fn long_simulations_keep_fewer_frames() {
+        let mut recorder = Recorder::new(1, 4);
+        for step in 0..10 {
+            recorder.step(|| dot(step, 0));
+        }
+        recorder.end(|| dot(99, 0));
+        let xs: Vec<_> = recorder.frames().iter().map(|f| f.origin.x).collect();
+        // the stride went from 1 to 2 to 4, and the end was kept after step 8
+        assert_eq!(xs, [0, 4, 8, 99]);
+        assert_eq!(recorder.steps(), 10);
+    }
+
+    #[test]
+    fn the_end_is_kept_once() {
+        let mut recorder = Recorder::new(3, 10);
+        for step in 0..7 {
+            recorder.step(|| dot(step, 0));
+        }
+        recorder.end(|| dot(6, 0));
+        let xs: Vec<_> = recorder.frames().iter().map(|f| f.origin.x).collect();
+        assert_eq!(xs, [0, 3, 6]);
+    }
+
+    #[test]
+    fn frames_are_laid_out_on_the_map() {
+        let frames = [dot(-1, 0), dot(2, 1)];
+        let images = compose(&frames, None).unwrap();
+        assert_eq!((images[0].width(), images[0].height()), (4, 2));
+        assert_eq!(images[0][Pos::new(0, 0)], Rgb::WHITE);
+        assert_eq!(images[1][Pos::new(3, 1)], Rgb::WHITE);
+        assert_eq!(images[1][Pos::new(0, 0)], Rgb::BLACK);
+
+        let crop = Bounds {
+            left: 2,
+            top: 0,
+            right: 10,
+            bottom: 10,
+        };
+        let images = compose(&frames, Some(crop)).unwrap();
+        assert_eq!((images[1].width(), images[1].height()), (1, 2));
+        assert_eq!(images[1][Pos::new(0, 1)], Rgb::WHITE);
+
+        let crop = Bounds { left: 5, ..crop };
+        assert!(compose(&frames, Some(crop)).is_err());
+    }
+
+    #[test]
+    fn frames_are_numbered_after_the_path() {
+        assert_eq!(
+            numbered(Path::new("out/rope.png"), 12),
+            Path::new("out/rope-0012.png")
+        );
+    }
+}
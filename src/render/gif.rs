@@ -0,0 +1,303 @@
+//! Animated GIF files, with one palette for all the frames and LZW compressed image data.
+
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+    time::Duration,
+};
+
+use super::{Image, Rgb};
+
+/// The widest an LZW code can be in a GIF
+const MAX_CODE_WIDTH: u32 = 12;
+
+/// Writes frames of the same size that loop forever, showing each for `delay`, with `scale`
+/// by `scale` pixels for each of their own.
+pub fn write(
+    frames: &[Image],
+    scale: usize,
+    delay: Duration,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let (width, height) = match frames.first() {
+        Some(first) => (first.width() * scale, first.height() * scale),
+        None => (0, 0),
+    };
+    let too_big = |side: usize| u16::try_from(side).map_err(|_| invalid("too big for a GIF"));
+    let (width, height) = (too_big(width)?, too_big(height)?);
+
+    let palette = palette(frames)?;
+    // the palette has 2^(bits) entries, of which the unused ones are black
+    let bits = (usize::BITS - (palette.len() - 1).leading_zeros()).max(1);
+
+    out.write_all(b"GIF89a")?;
+    out.write_all(&width.to_le_bytes())?;
+    out.write_all(&height.to_le_bytes())?;
+    let size = bits as u8 - 1;
+    out.write_all(&[0x80 | size << 4 | size, 0, 0])?;
+    let mut colours = vec![Rgb::BLACK; 1 << bits];
+    for (&colour, &index) in &palette {
+        colours[index as usize] = colour;
+    }
+    for Rgb(r, g, b) in colours {
+        out.write_all(&[r, g, b])?;
+    }
+
+    // loop forever
+    out.write_all(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00")?;
+
+    let centis = u16::try_from(delay.as_millis() / 10).unwrap_or(u16::MAX);
+    let min_code_width = bits.max(2);
+    for frame in frames {
+        out.write_all(&[0x21, 0xf9, 4, 0])?;
+        out.write_all(&centis.to_le_bytes())?;
+        out.write_all(&[0, 0])?;
+
+        out.write_all(&[0x2c, 0, 0, 0, 0])?;
+        out.write_all(&width.to_le_bytes())?;
+        out.write_all(&height.to_le_bytes())?;
+        out.write_all(&[0, min_code_width as u8])?;
+
+        let indices = frame.map(|c| palette[c]);
+        let mut scaled = Vec::with_capacity(width as usize * height as usize);
+        for y in 0..indices.height() {
+            let row: Vec<u8> = indices
+                .row(y)
+                .iter()
+                .flat_map(|&index| std::iter::repeat_n(index, scale))
+                .collect();
+            for _ in 0..scale {
+                scaled.extend(&row);
+            }
+        }
+        for block in lzw(&scaled, min_code_width).chunks(255) {
+            out.write_all(&[block.len() as u8])?;
+            out.write_all(block)?;
+        }
+        out.write_all(&[0])?;
+    }
+
+    out.write_all(&[0x3b])
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message.to_string())
+}
+
+/// The index of every colour of the frames, in the order they first appear
+fn palette(frames: &[Image]) -> io::Result<HashMap<Rgb, u8>> {
+    let mut palette = HashMap::new();
+    for (_, &colour) in frames.iter().flat_map(Image::iter) {
+        if !palette.contains_key(&colour) {
+            let index = u8::try_from(palette.len())
+                .map_err(|_| invalid("a GIF cannot have more than 256 colours"))?;
+            palette.insert(colour, index);
+        }
+    }
+    if palette.is_empty() {
+        palette.insert(Rgb::BLACK, 0);
+    }
+    Ok(palette)
+}
+
+/// Packs codes of growing widths from the lowest bit of each byte up
+struct Bits {
+    bytes: Vec<u8>,
+    pending: u32,
+    count: u32,
+}
+
+impl Bits {
+    fn push(&mut self, code: u32, width: u32) {
+        self.pending |= code << self.count;
+        self.count += width;
+        while self.count >= 8 {
+            self.bytes.push(self.pending as u8);
+            self.pending >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.pending as u8);
+        }
+        self.bytes
+    }
+}
+
+/// The state of the LZW compression of one frame
+struct Lzw {
+    bits: Bits,
+    /// The code of each code followed by each index, at `code << min_code_width | index`, or 0
+    /// if there is none yet
+    table: Vec<u16>,
+    min_code_width: u32,
+    width: u32,
+    /// The last code given out
+    last: u32,
+}
+
+impl Lzw {
+    fn clear_code(&self) -> u32 {
+        1 << self.min_code_width
+    }
+
+    fn end_code(&self) -> u32 {
+        self.clear_code() + 1
+    }
+
+    fn clear(&mut self) {
+        self.bits.push(self.clear_code(), self.width);
+        self.table.fill(0);
+        self.width = self.min_code_width + 1;
+        self.last = self.end_code();
+    }
+
+    /// Gives out the next code as the decoder does, widening the codes once they need another
+    /// bit and starting over once there are no codes left. Tells whether it started over.
+    fn next_code(&mut self) -> bool {
+        self.last += 1;
+        if self.last == 1 << self.width {
+            self.width += 1;
+        }
+        if self.last == (1 << MAX_CODE_WIDTH) - 1 {
+            self.clear();
+            return true;
+        }
+        false
+    }
+}
+
+/// Compresses the indices of the colours as GIF does, with codes starting one bit wider than
+/// `min_code_width`.
+fn lzw(indices: &[u8], min_code_width: u32) -> Vec<u8> {
+    let mut lzw = Lzw {
+        bits: Bits {
+            bytes: Vec::new(),
+            pending: 0,
+            count: 0,
+        },
+        table: vec![0; 1 << (MAX_CODE_WIDTH + min_code_width)],
+        min_code_width,
+        width: min_code_width + 1,
+        last: 0,
+    };
+    lzw.clear();
+
+    if let Some((&first, rest)) = indices.split_first() {
+        let mut code = first as u32;
+        for &index in rest {
+            let key = (code << min_code_width | index as u32) as usize;
+            if lzw.table[key] != 0 {
+                code = lzw.table[key] as u32;
+                continue;
+            }
+            lzw.bits.push(code, lzw.width);
+            code = index as u32;
+            if !lzw.next_code() {
+                lzw.table[key] = lzw.last as u16;
+            }
+        }
+        lzw.bits.push(code, lzw.width);
+        // the decoder gives out a code after the last one too, which may widen the end code
+        lzw.next_code();
+    }
+    lzw.bits.push(lzw.end_code(), lzw.width);
+    lzw.bits.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Pos;
+
+    /// Decodes the codes as a GIF reader does
+    fn unlzw(bytes: &[u8], min_code_width: u32) -> Vec<u8> {
+        let clear = 1 << min_code_width;
+        let end = clear + 1;
+        let mut width = min_code_width + 1;
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let reset = |table: &mut Vec<Vec<u8>>| {
+            *table = (0..clear).map(|i| vec![i as u8]).collect();
+            table.extend([vec![], vec![]]);
+        };
+        reset(&mut table);
+
+        let (mut pending, mut count, mut bytes) = (0u32, 0, bytes.iter());
+        let mut out = Vec::new();
+        let mut previous: Option<Vec<u8>> = None;
+        loop {
+            while count < width {
+                pending |= (*bytes.next().unwrap() as u32) << count;
+                count += 8;
+            }
+            let code = pending & ((1 << width) - 1);
+            pending >>= width;
+            count -= width;
+
+            if code == clear {
+                reset(&mut table);
+                width = min_code_width + 1;
+                previous = None;
+                continue;
+            }
+            if code == end {
+                return out;
+            }
+            let entry = match (table.get(code as usize), &previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(p)) => [p.clone(), vec![p[0]]].concat(),
+                (None, None) => panic!("code {} before any other", code),
+            };
+            out.extend(&entry);
+            if let Some(p) = previous {
+                if table.len() < 1 << MAX_CODE_WIDTH {
+                    table.push([p, vec![entry[0]]].concat());
+                    if table.len() == 1 << width && width < MAX_CODE_WIDTH {
+                        width += 1;
+                    }
+                }
+            }
+            previous = Some(entry);
+        }
+    }
+
+    #[test]
+    fn lzw_codes_decode_to_the_indices() {
+        let repetitive: Vec<u8> = (0..20_000).map(|i| (i / 7 % 3) as u8).collect();
+        let noisy: Vec<u8> = (0..20_000u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 27) as u8)
+            .collect();
+        for (indices, min_code_width) in [
+            (vec![], 2),
+            (vec![1], 2),
+            (vec![0, 0, 0, 0], 2),
+            (repetitive, 2),
+            (noisy, 5),
+        ] {
+            let bytes = lzw(&indices, min_code_width);
+            assert_eq!(unlzw(&bytes, min_code_width), indices);
+        }
+    }
+
+    #[test]
+    fn a_gif_has_a_palette_and_a_block_a_frame() {
+        let mut second = Image::new(3, 2, Rgb::BLACK);
+        second[Pos::new(1, 1)] = Rgb::RED;
+        let frames = [Image::new(3, 2, Rgb::BLACK), second];
+        let mut out = Vec::new();
+        write(&frames, 2, Duration::from_millis(250), &mut out).unwrap();
+
+        assert_eq!(&out[..10], b"GIF89a\x06\x00\x04\x00");
+        // two colours fit in the smallest palette, of two entries
+        assert_eq!(out[10], 0x80);
+        assert_eq!(&out[13..19], [0x10, 0x10, 0x18, 0xe0, 0x40, 0x40]);
+        assert_eq!(
+            out.windows(4).filter(|w| *w == [0x21, 0xf9, 4, 0]).count(),
+            2
+        );
+        assert!(out.windows(2).any(|w| w == 25u16.to_le_bytes()));
+        assert_eq!(out.last(), Some(&0x3b));
+    }
+}
@@ -0,0 +1,255 @@
+//! Pictures of the states of the puzzles, written as PNG, PPM or SVG without any dependency.
+//!
+//! A state is drawn as an [`Image`] with one pixel per cell of its map, which the writers scale
+//! up so that the picture can be looked at. The cells say their colour through [`Paint`], which
+//! gives a [`Render`] to the grids of [`crate::grid`] that hold them. The steps of a simulation
+//! are kept by a [`Recorder`] as [`Frame`]s, which are written as an animated GIF or one
+//! numbered picture each.
+
+mod animation;
+mod gif;
+mod png;
+
+use std::{
+    fmt::{self, Display},
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::grid::{Grid, Point, Pos, SparseGrid};
+
+pub use animation::{save_frames, Frame, Recorder};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0x10, 0x10, 0x18);
+    pub const WHITE: Rgb = Rgb(0xf0, 0xf0, 0xf0);
+    pub const GREY: Rgb = Rgb(0x80, 0x80, 0x80);
+    pub const SAND: Rgb = Rgb(0xe0, 0xc0, 0x70);
+    pub const GREEN: Rgb = Rgb(0x40, 0xb0, 0x50);
+    pub const BLUE: Rgb = Rgb(0x50, 0x90, 0xe0);
+    pub const RED: Rgb = Rgb(0xe0, 0x40, 0x40);
+}
+
+/// A picture, one colour per pixel
+pub type Image = Grid<Rgb>;
+
+/// The colour of a cell of a map
+pub trait Paint {
+    fn colour(&self) -> Rgb;
+}
+
+impl Paint for Rgb {
+    fn colour(&self) -> Rgb {
+        *self
+    }
+}
+
+/// Draws a state as a picture
+pub trait Render {
+    fn render(&self) -> Image;
+
+    /// The picture as a frame of an animation, with its top left corner at the origin
+    fn frame(&self) -> Frame {
+        Frame::new(Point::new(0, 0), self.render())
+    }
+}
+
+impl<T: Paint> Render for Grid<T> {
+    fn render(&self) -> Image {
+        self.map(Paint::colour)
+    }
+}
+
+/// The empty cells within the bounds are black
+impl<T: Paint> Render for SparseGrid<T> {
+    fn render(&self) -> Image {
+        let Some(b) = self.bounds() else {
+            return Image::new(0, 0, Rgb::BLACK);
+        };
+        let mut image = Image::new(b.width() as usize, b.height() as usize, Rgb::BLACK);
+        for (p, cell) in self.iter() {
+            image[Pos::new((p.x - b.left) as usize, (p.y - b.top) as usize)] = cell.colour();
+        }
+        image
+    }
+
+    /// The frame starts at the top left corner of the bounds
+    fn frame(&self) -> Frame {
+        let origin = match self.bounds() {
+            Some(b) => Point::new(b.left, b.top),
+            None => Point::new(0, 0),
+        };
+        Frame::new(origin, self.render())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Png,
+    Ppm,
+    Svg,
+}
+
+impl Format {
+    /// The format named by the extension of a file, in any case
+    pub fn from_path(path: &Path) -> Option<Format> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "png" => Some(Format::Png),
+            "ppm" => Some(Format::Ppm),
+            "svg" => Some(Format::Svg),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Format::Png => f.pad("png"),
+            Format::Ppm => f.pad("ppm"),
+            Format::Svg => f.pad("svg"),
+        }
+    }
+}
+
+/// How many pixels of the picture a cell takes, so that small maps are not tiny and big ones
+/// not huge
+pub fn default_scale(image: &Image) -> usize {
+    let longest = image.width().max(image.height()).max(1);
+    (1024 / longest).clamp(1, 16)
+}
+
+/// Writes a picture with `scale` by `scale` pixels for each of its own.
+pub fn write(image: &Image, format: Format, scale: usize, out: &mut impl Write) -> io::Result<()> {
+    match format {
+        Format::Png => png::write(&scaled(image, scale), out),
+        Format::Ppm => write_ppm(&scaled(image, scale), out),
+        Format::Svg => write_svg(image, scale, out),
+    }
+}
+
+/// Writes a picture to a file in the format named by its extension, at its default scale.
+pub fn save(image: &Image, path: &Path) -> io::Result<()> {
+    let format = Format::from_path(path).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{}: expected a .png, .ppm or .svg file", path.display()),
+        )
+    })?;
+    let mut out = BufWriter::new(File::create(path)?);
+    write(image, format, default_scale(image), &mut out)?;
+    out.flush()
+}
+
+fn scaled(image: &Image, scale: usize) -> Image {
+    if scale == 1 {
+        return image.clone();
+    }
+    let mut big = Image::new(image.width() * scale, image.height() * scale, Rgb::BLACK);
+    for pos in big.positions() {
+        big[pos] = image[Pos::new(pos.x / scale, pos.y / scale)];
+    }
+    big
+}
+
+/// The binary PPM (P6) format
+fn write_ppm(image: &Image, out: &mut impl Write) -> io::Result<()> {
+    write!(out, "P6\n{} {}\n255\n", image.width(), image.height())?;
+    for (_, &Rgb(r, g, b)) in image.iter() {
+        out.write_all(&[r, g, b])?;
+    }
+    Ok(())
+}
+
+/// One rectangle for each run of cells of the same colour in a row
+fn write_svg(image: &Image, scale: usize, out: &mut impl Write) -> io::Result<()> {
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" shape-rendering="crispEdges">"#,
+        image.width() * scale,
+        image.height() * scale
+    )?;
+    for y in 0..image.height() {
+        let row = image.row(y);
+        let mut x = 0;
+        while x < row.len() {
+            let Rgb(r, g, b) = row[x];
+            let run = row[x..].iter().take_while(|&&c| c == row[x]).count();
+            writeln!(
+                out,
+                r##"<rect x="{}" y="{}" width="{}" height="{}" fill="#{:02x}{:02x}{:02x}"/>"##,
+                x * scale,
+                y * scale,
+                run * scale,
+                scale,
+                r,
+                g,
+                b
+            )?;
+            x += run;
+        }
+    }
+    writeln!(out, "</svg>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkerboard() -> Image {
+        let mut image = Image::new(2, 2, Rgb::BLACK);
+        image[Pos::new(1, 0)] = Rgb::WHITE;
+        image[Pos::new(0, 1)] = Rgb::WHITE;
+        image
+    }
+
+    #[test]
+    fn a_ppm_has_a_header_and_three_bytes_a_pixel() {
+        let mut out = Vec::new();
+        write(&checkerboard(), Format::Ppm, 3, &mut out).unwrap();
+        let header = b"P6\n6 6\n255\n";
+        assert_eq!(&out[..header.len()], header);
+        assert_eq!(out.len(), header.len() + 6 * 6 * 3);
+    }
+
+    #[test]
+    fn an_svg_has_a_rectangle_a_run() {
+        let mut out = Vec::new();
+        let mut image = checkerboard();
+        image[Pos::new(1, 1)] = Rgb::WHITE;
+        write(&image, Format::Svg, 10, &mut out).unwrap();
+        let svg = String::from_utf8(out).unwrap();
+        assert_eq!(svg.matches("<rect").count(), 3);
+        assert!(svg.contains(r##"<rect x="0" y="10" width="20" height="10" fill="#f0f0f0"/>"##));
+    }
+
+    #[test]
+    fn formats_are_named_by_the_extension() {
+        assert_eq!(Format::from_path(Path::new("a/b.PNG")), Some(Format::Png));
+        assert_eq!(Format::from_path(Path::new("b.svg")), Some(Format::Svg));
+        assert_eq!(Format::from_path(Path::new("b.gif")), None);
+        assert_eq!(Format::from_path(Path::new("b")), None);
+    }
+
+    #[test]
+    fn sparse_grids_are_drawn_within_their_bounds() {
+        struct On;
+        impl Paint for On {
+            fn colour(&self) -> Rgb {
+                Rgb::GREEN
+            }
+        }
+        let grid: SparseGrid<On> = [(Point::new(-1, 5), On), (Point::new(1, 6), On)]
+            .into_iter()
+            .collect();
+        let image = grid.render();
+        assert_eq!((image.width(), image.height()), (3, 2));
+        assert_eq!(image[Pos::new(0, 0)], Rgb::GREEN);
+        assert_eq!(image[Pos::new(1, 0)], Rgb::BLACK);
+    }
+}
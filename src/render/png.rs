@@ -0,0 +1,110 @@
+//! PNG files with uncompressed image data, which every reader accepts and which need neither
+//! a compressor nor a dependency.
+
+use std::io::{self, Write};
+
+use super::{Image, Rgb};
+
+/// The most a stored deflate block can hold
+const MAX_STORED_LEN: usize = 65535;
+
+pub fn write(image: &Image, out: &mut impl Write) -> io::Result<()> {
+    out.write_all(b"\x89PNG\r\n\x1a\n")?;
+
+    let mut header = Vec::with_capacity(13);
+    header.extend((image.width() as u32).to_be_bytes());
+    header.extend((image.height() as u32).to_be_bytes());
+    // 8 bits per channel, RGB, and the only compression, filtering and interlacing methods
+    header.extend([8, 2, 0, 0, 0]);
+    write_chunk(out, b"IHDR", &header)?;
+
+    // every row starts with its filter, which is none
+    let mut raw = Vec::with_capacity(image.height() * (1 + 3 * image.width()));
+    for y in 0..image.height() {
+        raw.push(0);
+        for &Rgb(r, g, b) in image.row(y) {
+            raw.extend([r, g, b]);
+        }
+    }
+    write_chunk(out, b"IDAT", &zlib_stored(&raw))?;
+
+    write_chunk(out, b"IEND", &[])
+}
+
+fn write_chunk(out: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    out.write_all(kind)?;
+    out.write_all(data)?;
+    let crc = crc32(kind.iter().chain(data));
+    out.write_all(&crc.to_be_bytes())
+}
+
+/// A zlib stream holding `data` in stored (uncompressed) deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut stream = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_STORED_LEN).peekable();
+    if blocks.peek().is_none() {
+        stream.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let is_last = blocks.peek().is_none();
+        stream.push(is_last as u8);
+        let len = block.len() as u16;
+        stream.extend(len.to_le_bytes());
+        stream.extend((!len).to_le_bytes());
+        stream.extend(block);
+    }
+    stream.extend(adler32(data).to_be_bytes());
+    stream
+}
+
+fn crc32<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in bytes {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksums_match_the_known_values() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn big_images_are_split_in_blocks() {
+        let data = vec![7; MAX_STORED_LEN + 1];
+        let stream = zlib_stored(&data);
+        // the header, two blocks of 5 bytes and their data, and the checksum
+        assert_eq!(stream.len(), 2 + 2 * 5 + data.len() + 4);
+        assert_eq!(stream[2], 0);
+        assert_eq!(stream[2 + 5 + MAX_STORED_LEN], 1);
+    }
+
+    #[test]
+    fn a_png_ends_with_its_end_chunk() {
+        let mut out = Vec::new();
+        write(&Image::new(3, 2, Rgb::RED), &mut out).unwrap();
+        assert!(out.starts_with(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x03\0\0\0\x02"));
+        assert!(out.ends_with(b"\0\0\0\0IEND\xae\x42\x60\x82"));
+    }
+}
@@ -0,0 +1,390 @@
+//! Graph searches over a successor function, for the days that look for a shortest route.
+//!
+//! A [`Search`] is set up with how it remembers the nodes it has seen (any [`Visited`]) and
+//! optionally a [`CancelToken`], then runs one of [`Search::bfs`], [`Search::dijkstra`],
+//! [`Search::astar`] or [`Search::flood_fill`]. The searches that look for a goal give back the
+//! whole path to it with its cost, and all of them count the nodes they explored.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashSet, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+use crate::{
+    days::{CancelToken, SolveError},
+    grid::{Grid, Pos},
+};
+
+/// Remembers which nodes a search has already reached.
+pub trait Visited<N> {
+    /// Marks a node as visited, and tells whether it was not already
+    fn visit(&mut self, node: &N) -> bool;
+}
+
+impl<N: Hash + Eq + Clone> Visited<N> for HashSet<N> {
+    fn visit(&mut self, node: &N) -> bool {
+        self.insert(node.clone())
+    }
+}
+
+/// For searches over the cells of a grid of the same size
+impl Visited<Pos> for Grid<bool> {
+    fn visit(&mut self, node: &Pos) -> bool {
+        !std::mem::replace(&mut self[*node], true)
+    }
+}
+
+/// Remembers a key of each node instead of the node, for nodes that are equivalent without
+/// being equal, or that are expensive to keep.
+pub struct VisitedBy<K, F> {
+    pub seen: HashSet<K>,
+    key: F,
+}
+
+impl<K, F> VisitedBy<K, F> {
+    pub fn new(key: F) -> Self {
+        VisitedBy {
+            seen: HashSet::new(),
+            key,
+        }
+    }
+}
+
+impl<N, K: Hash + Eq, F: FnMut(&N) -> K> Visited<N> for VisitedBy<K, F> {
+    fn visit(&mut self, node: &N) -> bool {
+        self.seen.insert((self.key)(node))
+    }
+}
+
+/// How much work a search did
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// The nodes whose successors were looked at
+    pub explored: u64,
+    /// The nodes that were queued, including the starts
+    pub enqueued: u64,
+}
+
+/// The cheapest path from one of the starts to a goal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found<N, C> {
+    /// The nodes of the path, from the start to the goal, both included
+    pub path: Vec<N>,
+    pub cost: C,
+    pub stats: Stats,
+}
+
+impl<N, C> Found<N, C> {
+    pub fn goal(&self) -> &N {
+        self.path.last().expect("a path holds at least its start")
+    }
+}
+
+/// Why a search did not find a goal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotFound {
+    /// Every reachable node was explored
+    Exhausted(Stats),
+    Cancelled(Stats),
+}
+
+impl From<NotFound> for SolveError {
+    fn from(e: NotFound) -> Self {
+        match e {
+            NotFound::Exhausted(_) => SolveError::NoSolution,
+            NotFound::Cancelled(_) => SolveError::timed_out(None::<u32>),
+        }
+    }
+}
+
+pub struct Search<'c, V> {
+    visited: V,
+    cancel: Option<&'c CancelToken>,
+}
+
+/// The nodes reached so far, each with the index of the one it was reached from
+struct Tree<N> {
+    nodes: Vec<(N, Option<usize>)>,
+}
+
+impl<N: Clone> Tree<N> {
+    fn add(&mut self, node: N, parent: Option<usize>) -> usize {
+        self.nodes.push((node, parent));
+        self.nodes.len() - 1
+    }
+
+    fn path_to(&self, mut index: usize) -> Vec<N> {
+        let mut path = vec![self.nodes[index].0.clone()];
+        while let Some(parent) = self.nodes[index].1 {
+            path.push(self.nodes[parent].0.clone());
+            index = parent;
+        }
+        path.reverse();
+        path
+    }
+}
+
+impl<'c, V> Search<'c, V> {
+    pub fn new(visited: V) -> Self {
+        Search {
+            visited,
+            cancel: None,
+        }
+    }
+
+    /// Stops the search once the token is cancelled
+    pub fn cancel(self, token: &'c CancelToken) -> Self {
+        Search {
+            cancel: Some(token),
+            ..self
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel.is_some_and(|token| token.is_cancelled())
+    }
+
+    /// Finds a path with the fewest steps from any of the starts to a goal.
+    pub fn bfs<N, I>(
+        mut self,
+        starts: impl IntoIterator<Item = N>,
+        mut successors: impl FnMut(&N) -> I,
+        mut is_goal: impl FnMut(&N) -> bool,
+    ) -> Result<Found<N, u32>, NotFound>
+    where
+        N: Clone,
+        V: Visited<N>,
+        I: IntoIterator<Item = N>,
+    {
+        let mut stats = Stats::default();
+        let mut tree = Tree { nodes: Vec::new() };
+        let mut queue = VecDeque::new();
+        for start in starts {
+            if self.visited.visit(&start) {
+                queue.push_back((tree.add(start, None), 0));
+                stats.enqueued += 1;
+            }
+        }
+
+        while let Some((index, steps)) = queue.pop_front() {
+            if self.is_cancelled() {
+                return Err(NotFound::Cancelled(stats));
+            }
+            let node = tree.nodes[index].0.clone();
+            if is_goal(&node) {
+                return Ok(Found {
+                    path: tree.path_to(index),
+                    cost: steps,
+                    stats,
+                });
+            }
+            stats.explored += 1;
+            for next in successors(&node) {
+                if self.visited.visit(&next) {
+                    queue.push_back((tree.add(next, Some(index)), steps + 1));
+                    stats.enqueued += 1;
+                }
+            }
+        }
+        Err(NotFound::Exhausted(stats))
+    }
+
+    /// Finds a cheapest path from any of the starts to a goal, with the cost of every step
+    /// given along with it by `successors`.
+    pub fn dijkstra<N, C, I>(
+        self,
+        starts: impl IntoIterator<Item = N>,
+        successors: impl FnMut(&N) -> I,
+        is_goal: impl FnMut(&N) -> bool,
+    ) -> Result<Found<N, C>, NotFound>
+    where
+        N: Clone,
+        C: Ord + Copy + Default + Add<Output = C>,
+        V: Visited<N>,
+        I: IntoIterator<Item = (N, C)>,
+    {
+        self.astar(starts, successors, |_| C::default(), is_goal)
+    }
+
+    /// Like [`Search::dijkstra`], but explores first the nodes that `heuristic` estimates to be
+    /// closer to a goal. The path is the cheapest if the estimate never exceeds the cost left
+    /// and never drops by more than the cost of a step.
+    pub fn astar<N, C, I>(
+        mut self,
+        starts: impl IntoIterator<Item = N>,
+        mut successors: impl FnMut(&N) -> I,
+        mut heuristic: impl FnMut(&N) -> C,
+        mut is_goal: impl FnMut(&N) -> bool,
+    ) -> Result<Found<N, C>, NotFound>
+    where
+        N: Clone,
+        C: Ord + Copy + Default + Add<Output = C>,
+        V: Visited<N>,
+        I: IntoIterator<Item = (N, C)>,
+    {
+        let mut stats = Stats::default();
+        let mut tree = Tree { nodes: Vec::new() };
+        // ordered by the estimated total cost, then by the cost so far
+        let mut queue = BinaryHeap::new();
+        for start in starts {
+            let estimate = heuristic(&start);
+            let index = tree.add(start, None);
+            queue.push(Reverse((estimate, C::default(), index)));
+            stats.enqueued += 1;
+        }
+
+        while let Some(Reverse((_, cost, index))) = queue.pop() {
+            if self.is_cancelled() {
+                return Err(NotFound::Cancelled(stats));
+            }
+            let node = tree.nodes[index].0.clone();
+            // the same node may be queued again at a higher cost before it is settled
+            if !self.visited.visit(&node) {
+                continue;
+            }
+            if is_goal(&node) {
+                return Ok(Found {
+                    path: tree.path_to(index),
+                    cost,
+                    stats,
+                });
+            }
+            stats.explored += 1;
+            for (next, step) in successors(&node) {
+                let next_cost = cost + step;
+                let estimate = next_cost + heuristic(&next);
+                let next_index = tree.add(next, Some(index));
+                queue.push(Reverse((estimate, next_cost, next_index)));
+                stats.enqueued += 1;
+            }
+        }
+        Err(NotFound::Exhausted(stats))
+    }
+
+    /// Visits every node reachable from the starts, and gives back what was visited.
+    pub fn flood_fill<N, I>(
+        mut self,
+        starts: impl IntoIterator<Item = N>,
+        mut successors: impl FnMut(&N) -> I,
+    ) -> Result<(V, Stats), NotFound>
+    where
+        V: Visited<N>,
+        I: IntoIterator<Item = N>,
+    {
+        let mut stats = Stats::default();
+        let mut queue = VecDeque::new();
+        for start in starts {
+            if self.visited.visit(&start) {
+                queue.push_back(start);
+                stats.enqueued += 1;
+            }
+        }
+
+        while let Some(node) = queue.pop_front() {
+            if self.is_cancelled() {
+                return Err(NotFound::Cancelled(stats));
+            }
+            stats.explored += 1;
+            for next in successors(&node) {
+                if self.visited.visit(&next) {
+                    queue.push_back(next);
+                    stats.enqueued += 1;
+                }
+            }
+        }
+        Ok((self.visited, stats))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A line of nodes 0 to 9, where every node also jumps 3 ahead at a cost of 5
+    fn line(&n: &u32) -> Vec<(u32, u32)> {
+        let mut next = vec![(n + 1, 1)];
+        if n + 3 <= 9 {
+            next.push((n + 3, 5));
+        }
+        next.retain(|&(m, _)| m <= 9);
+        next
+    }
+
+    #[test]
+    fn bfs_finds_the_fewest_steps() {
+        let steps = |n: &u32| line(n).into_iter().map(|(m, _)| m);
+        let found = Search::new(HashSet::new())
+            .bfs([0], steps, |&n| n == 9)
+            .unwrap();
+        assert_eq!(found.path, [0, 3, 6, 9]);
+        assert_eq!(found.cost, 3);
+        assert_eq!(*found.goal(), 9);
+    }
+
+    #[test]
+    fn dijkstra_and_astar_find_the_cheapest_path() {
+        let found = Search::new(HashSet::new())
+            .dijkstra([0], line, |&n| n == 9)
+            .unwrap();
+        assert_eq!(found.path, (0..=9).collect::<Vec<_>>());
+        assert_eq!(found.cost, 9);
+
+        let guided = Search::new(HashSet::new())
+            .astar([0], line, |&n| 9 - n, |&n| n == 9)
+            .unwrap();
+        assert_eq!(guided.cost, 9);
+        assert!(guided.stats.explored <= found.stats.explored);
+    }
+
+    #[test]
+    fn an_unreachable_goal_exhausts_the_search() {
+        let result = Search::new(HashSet::new()).bfs([0u32], |&n| [n / 2], |&n| n == 1);
+        assert_eq!(
+            result,
+            Err(NotFound::Exhausted(Stats {
+                explored: 1,
+                enqueued: 1
+            }))
+        );
+        assert_eq!(
+            SolveError::from(result.unwrap_err()),
+            SolveError::NoSolution
+        );
+    }
+
+    #[test]
+    fn a_cancelled_search_stops() {
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let result =
+            Search::new(HashSet::new())
+                .cancel(&cancel)
+                .bfs([0u32], |&n| [n + 1], |_| false);
+        assert!(matches!(result, Err(NotFound::Cancelled(_))));
+    }
+
+    #[test]
+    fn nodes_can_be_told_apart_by_a_key() {
+        // the nodes are equivalent modulo 4, so only 4 of them are ever explored
+        let visited = VisitedBy::new(|&n: &u32| n % 4);
+        let (visited, stats) = Search::new(visited)
+            .flood_fill([0u32], |&n| [n + 1])
+            .unwrap();
+        assert_eq!(visited.seen.len(), 4);
+        assert_eq!(stats.explored, 4);
+    }
+
+    #[test]
+    fn a_grid_remembers_its_cells() {
+        let grid = Grid::new(3, 2, ());
+        let (visited, stats) = Search::new(Grid::new(3, 2, false))
+            .flood_fill([Pos::new(0, 0)], |&p| {
+                grid.neighbours4(p).collect::<Vec<_>>()
+            })
+            .unwrap();
+        assert!(visited.iter().all(|(_, &seen)| seen));
+        assert_eq!(stats.explored, 6);
+    }
+}
@@ -0,0 +1,488 @@
+//! A small HTTP/1.1 server that solves the days, for tools that would rather not run the binary.
+//!
+//! `POST /day/{n}/part/{1|2}` with the puzzle input as the body answers with a JSON object like
+//! `{"day":1,"part":"basic","answer":"24000","timings":{...},"error":null}`. Every connection
+//! serves a single request and is closed after the response.
+//!
+//! Each connection is served on its own thread and each solve on another one, so a day that does
+//! not check its [`CancelToken`] still gets an answer in time. A solve that outlives its deadline
+//! keeps its connection slot until it ends, so at most `max_connections` of them run at once.
+
+use std::{
+    fmt::Display,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, RecvTimeoutError, TrySendError},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    days::{self, CancelToken, NoProgress, Part, Solution, SolveError},
+    json::JsonObject,
+    log,
+};
+
+/// The longest request line and headers that are accepted, together
+const MAX_HEAD_LEN: u64 = 8 * 1024;
+
+/// How much of an unread body is drained before closing, so the client sees the response
+const MAX_DRAIN_LEN: u64 = 64 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// The largest puzzle input that is accepted, in bytes
+    pub max_body_len: usize,
+    /// How long a day may be solved before answering with the best answer so far
+    pub solve_timeout: Duration,
+    /// How long after its deadline a solve may take to stop and report its best answer so far,
+    /// before it is given up on
+    pub solve_grace: Duration,
+    /// How long reading the request or writing the response may stall
+    pub io_timeout: Duration,
+    /// How many connections are served at once; the others are answered as busy right away
+    pub max_connections: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            max_body_len: 1024 * 1024,
+            solve_timeout: Duration::from_secs(30),
+            solve_grace: Duration::from_secs(1),
+            io_timeout: Duration::from_secs(5),
+            max_connections: 16,
+        }
+    }
+}
+
+pub struct Server {
+    listener: TcpListener,
+    config: Arc<Config>,
+}
+
+/// One of the `max_connections` that may be served at once, given back when dropped.
+///
+/// The connection and its solve share it, as the solve can outlive the connection.
+struct Slot(Arc<AtomicUsize>);
+
+impl Slot {
+    fn acquire(active: &Arc<AtomicUsize>, max: usize) -> Option<Slot> {
+        active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < max).then_some(n + 1)
+            })
+            .ok()
+            .map(|_| Slot(Arc::clone(active)))
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl Server {
+    pub fn bind(addr: impl ToSocketAddrs, config: Config) -> io::Result<Server> {
+        Ok(Server {
+            listener: TcpListener::bind(addr)?,
+            config: Arc::new(config),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Serves connections forever, each one on its own thread.
+    ///
+    /// Once `max_connections` are being served, the next ones are answered as busy by a single
+    /// thread, and dropped if even that one falls behind.
+    pub fn run(self) -> io::Result<()> {
+        let active = Arc::new(AtomicUsize::new(0));
+        let (busy, rejected) = mpsc::sync_channel::<TcpStream>(self.config.max_connections);
+        let config = Arc::clone(&self.config);
+        thread::spawn(move || {
+            for stream in rejected {
+                let busy = Response::error(503, "busy", "too many requests are being served");
+                if let Err(e) = write_response(stream, &config, &busy) {
+                    log::error!("{}", e);
+                }
+            }
+        });
+
+        for stream in self.listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    log::error!("could not accept a connection: {}", e);
+                    continue;
+                }
+            };
+            let slot = match Slot::acquire(&active, self.config.max_connections) {
+                Some(slot) => Arc::new(slot),
+                None => {
+                    if let Err(TrySendError::Full(_)) = busy.try_send(stream) {
+                        log::warn!("dropped a connection while too busy to answer it");
+                    }
+                    continue;
+                }
+            };
+            let config = Arc::clone(&self.config);
+            thread::spawn(move || {
+                if let Err(e) = handle_connection(stream, &config, slot) {
+                    log::error!("{}", e);
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    content_length: Option<usize>,
+}
+
+struct Response {
+    status: u16,
+    body: JsonObject,
+}
+
+impl Response {
+    fn error(status: u16, kind: &str, message: impl Display) -> Response {
+        let error = JsonObject::new()
+            .string("kind", kind)
+            .string("message", &message.to_string());
+        Response {
+            status,
+            body: JsonObject::new().object("error", error),
+        }
+    }
+
+    fn write_to(&self, out: &mut impl Write) -> io::Result<()> {
+        let body = format!("{}\n", self.body);
+        write!(
+            out,
+            "HTTP/1.1 {} {}\r\n\
+             Content-Type: application/json\r\n\
+             Content-Length: {}\r\n\
+             Connection: close\r\n\
+             \r\n\
+             {}",
+            self.status,
+            reason(self.status),
+            body.len(),
+            body
+        )?;
+        out.flush()
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Content",
+        431 => "Request Header Fields Too Large",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
+
+fn handle_connection(stream: TcpStream, config: &Config, slot: Arc<Slot>) -> io::Result<()> {
+    stream.set_read_timeout(Some(config.io_timeout))?;
+
+    let mut reader = BufReader::new(stream);
+    let response = respond(&mut reader, config, slot).unwrap_or_else(|response| response);
+    write_response(reader.into_inner(), config, &response)
+}
+
+fn write_response(mut stream: TcpStream, config: &Config, response: &Response) -> io::Result<()> {
+    stream.set_read_timeout(Some(config.io_timeout))?;
+    stream.set_write_timeout(Some(config.io_timeout))?;
+    response.write_to(&mut stream)?;
+
+    // closing a socket with unread data resets the connection, which can lose the response
+    stream.shutdown(Shutdown::Write)?;
+    let _ = io::copy(&mut stream.take(MAX_DRAIN_LEN), &mut io::sink());
+    Ok(())
+}
+
+fn respond(
+    reader: &mut impl BufRead,
+    config: &Config,
+    slot: Arc<Slot>,
+) -> Result<Response, Response> {
+    let request = read_head(reader)?;
+
+    let (day, part) = route(&request.path).ok_or_else(|| {
+        Response::error(
+            404,
+            "not_found",
+            format!("no such resource: {}", request.path),
+        )
+    })?;
+    if request.method != "POST" {
+        return Err(Response::error(
+            405,
+            "method_not_allowed",
+            "the input must be sent with POST",
+        ));
+    }
+    let day = days::day(day).ok_or_else(|| {
+        Response::error(404, "no_such_day", format!("{} is not a valid day", day))
+    })?;
+
+    let len = request.content_length.ok_or_else(|| {
+        Response::error(411, "length_required", "the request has no Content-Length")
+    })?;
+    if len > config.max_body_len {
+        return Err(Response::error(
+            413,
+            "too_large",
+            format!(
+                "the input is {} bytes, but at most {} are accepted",
+                len, config.max_body_len
+            ),
+        ));
+    }
+    let mut body = vec![0; len];
+    reader.read_exact(&mut body).map_err(read_error)?;
+    let input = String::from_utf8(body)
+        .map_err(|_| Response::error(400, "bad_request", "the input is not UTF-8"))?;
+
+    let solve = day.solve;
+    let deadline = (config.solve_timeout, config.solve_grace);
+    let result = solve_in_time(deadline, slot, move |cancel| {
+        solve(&input, part, cancel, &NoProgress)
+    })?;
+    let obj = JsonObject::new()
+        .number("day", day.number)
+        .string("part", &part.to_string());
+    match result {
+        Ok(solution) => Ok(Response {
+            status: 200,
+            body: obj
+                .string("answer", &solution.answer)
+                .object(
+                    "timings",
+                    JsonObject::new()
+                        .millis("parse_ms", solution.parse_time)
+                        .millis("solve_ms", solution.solve_time),
+                )
+                .null("error"),
+        }),
+        Err(e) => Ok(Response {
+            status: match e {
+                SolveError::TimedOut { .. } => 503,
+                _ => 422,
+            },
+            body: obj
+                .null("answer")
+                .null("timings")
+                .object("error", e.to_json()),
+        }),
+    }
+}
+
+/// Solves on a worker thread and gives up waiting for it once `timeout` and then `grace` have
+/// passed, whether or not it checks its token.
+fn solve_in_time(
+    (timeout, grace): (Duration, Duration),
+    slot: Arc<Slot>,
+    solve: impl FnOnce(&CancelToken) -> Result<Solution, SolveError> + Send + 'static,
+) -> Result<Result<Solution, SolveError>, Response> {
+    let cancel = CancelToken::with_timeout(timeout);
+    let (sender, receiver) = mpsc::channel();
+    let worker_cancel = cancel.clone();
+    thread::spawn(move || {
+        let _slot = slot;
+        let _ = sender.send(solve(&worker_cancel));
+    });
+
+    match receiver.recv_timeout(timeout + grace) {
+        Ok(result) => Ok(result),
+        Err(RecvTimeoutError::Timeout) => {
+            // still worth asking, in case the day checks its token only now and then
+            cancel.cancel();
+            Ok(Err(SolveError::timed_out(None::<String>)))
+        }
+        Err(RecvTimeoutError::Disconnected) => Err(Response::error(
+            500,
+            "internal",
+            "the solver stopped without an answer",
+        )),
+    }
+}
+
+/// Reads the request line and the headers, but not the body.
+fn read_head(reader: &mut impl BufRead) -> Result<Request, Response> {
+    let mut head = reader.take(MAX_HEAD_LEN);
+
+    let request_line = read_line(&mut head)?;
+    let mut words = request_line.split(' ');
+    let (method, path) = match (words.next(), words.next(), words.next(), words.next()) {
+        (Some(method), Some(path), Some(version), None) if version.starts_with("HTTP/1.") => {
+            (method.to_string(), path.to_string())
+        }
+        _ => {
+            return Err(Response::error(
+                400,
+                "bad_request",
+                "malformed request line",
+            ))
+        }
+    };
+
+    let mut content_length = None;
+    loop {
+        let line = read_line(&mut head)?;
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| Response::error(400, "bad_request", "malformed header"))?;
+        if name.eq_ignore_ascii_case("content-length") {
+            let len = value.trim().parse().map_err(|_| {
+                Response::error(400, "bad_request", "the Content-Length is not a number")
+            })?;
+            content_length = Some(len);
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            return Err(Response::error(
+                411,
+                "length_required",
+                "the body must be sent with a Content-Length",
+            ));
+        }
+    }
+
+    Ok(Request {
+        method,
+        path,
+        content_length,
+    })
+}
+
+fn read_line(head: &mut io::Take<impl BufRead>) -> Result<String, Response> {
+    let mut line = String::new();
+    head.read_line(&mut line).map_err(read_error)?;
+    if !line.ends_with('\n') {
+        return Err(if head.limit() == 0 {
+            Response::error(
+                431,
+                "head_too_large",
+                "the request line and headers are too long",
+            )
+        } else {
+            Response::error(400, "bad_request", "the request ended in its headers")
+        });
+    }
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn read_error(e: io::Error) -> Response {
+    match e.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => {
+            Response::error(408, "timeout", "the request was not received in time")
+        }
+        io::ErrorKind::UnexpectedEof => Response::error(
+            400,
+            "bad_request",
+            "the body is shorter than its Content-Length",
+        ),
+        _ => Response::error(400, "bad_request", e),
+    }
+}
+
+/// Parses `/day/{n}/part/{1|2}`.
+fn route(path: &str) -> Option<(u8, Part)> {
+    let rest = path.strip_prefix("/day/")?;
+    let (day, part) = rest.split_once("/part/")?;
+    let part = match part {
+        "1" => Part::Basic,
+        "2" => Part::Advanced,
+        _ => return None,
+    };
+    Some((day.parse().ok()?, part))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_are_parsed() {
+        assert_eq!(route("/day/16/part/1"), Some((16, Part::Basic)));
+        assert_eq!(route("/day/1/part/2"), Some((1, Part::Advanced)));
+        assert_eq!(route("/day/1/part/3"), None);
+        assert_eq!(route("/day/x/part/1"), None);
+        assert_eq!(route("/days/1/part/1"), None);
+    }
+
+    #[test]
+    fn the_head_is_read_up_to_the_body() {
+        let mut reader =
+            "POST /day/1/part/1 HTTP/1.1\r\nHost: x\r\ncontent-length: 5\r\n\r\n1000\n".as_bytes();
+        let request = read_head(&mut reader).ok().unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/day/1/part/1");
+        assert_eq!(request.content_length, Some(5));
+        assert_eq!(reader, b"1000\n");
+    }
+
+    fn no_solution(cancel: &CancelToken) -> Result<Solution, SolveError> {
+        while !cancel.is_cancelled() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        Err(SolveError::timed_out(Some(7)))
+    }
+
+    #[test]
+    fn a_solve_that_ignores_its_deadline_is_given_up_on() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let slot = Arc::new(Slot::acquire(&active, 1).unwrap());
+
+        let (release, wait) = mpsc::channel::<()>();
+        let deadline = (Duration::from_millis(10), Duration::from_millis(5));
+        let result = solve_in_time(deadline, slot, move |_| {
+            let _ = wait.recv();
+            Err(SolveError::NoSolution)
+        });
+
+        assert!(matches!(
+            result,
+            Ok(Err(SolveError::TimedOut { partial: None }))
+        ));
+        // the worker is still running and keeps its slot
+        assert!(Slot::acquire(&active, 1).is_none());
+        drop(release);
+    }
+
+    #[test]
+    fn a_solve_that_stops_in_time_keeps_its_partial_answer() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let slot = Arc::new(Slot::acquire(&active, 1).unwrap());
+
+        let deadline = (Duration::from_millis(10), Duration::from_secs(1));
+        let result = solve_in_time(deadline, slot, no_solution);
+
+        assert!(matches!(
+            result,
+            Ok(Err(SolveError::TimedOut { partial: Some(ref partial) })) if partial == "7"
+        ));
+    }
+}
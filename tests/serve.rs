@@ -0,0 +1,173 @@
+use std::{
+    io::{Read, Write},
+    net::{SocketAddr, TcpStream},
+    thread,
+    time::Duration,
+};
+
+use advent22::serve::{Config, Server};
+
+fn start(config: Config) -> SocketAddr {
+    let server = Server::bind("127.0.0.1:0", config).unwrap();
+    let addr = server.local_addr().unwrap();
+    thread::spawn(move || server.run());
+    addr
+}
+
+/// Sends a raw request and returns the status code and the body of the response.
+fn send(addr: SocketAddr, request: &[u8]) -> (u16, String) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream.write_all(request).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+    (status, body.trim_end().to_string())
+}
+
+fn post(addr: SocketAddr, path: &str, input: &str) -> (u16, String) {
+    let request = format!(
+        "POST {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+        path,
+        input.len(),
+        input
+    );
+    send(addr, request.as_bytes())
+}
+
+#[test]
+fn a_posted_input_is_solved() {
+    let addr = start(Config::default());
+    let input = include_str!("../src/days/d1/ex1.txt");
+
+    let (status, body) = post(addr, "/day/1/part/2", input);
+
+    assert_eq!(status, 200);
+    assert!(
+        body.starts_with(r#"{"day":1,"part":"advanced","answer":"45000","timings":{"parse_ms":"#),
+        "{}",
+        body
+    );
+    assert!(body.ends_with(r#"},"error":null}"#), "{}", body);
+}
+
+#[test]
+fn parse_errors_are_reported_with_their_location() {
+    let addr = start(Config::default());
+
+    let (status, body) = post(addr, "/day/1/part/1", "1000\nabc\n");
+
+    assert_eq!(status, 422);
+    assert!(
+        body.contains(r#""error":{"kind":"parse","line":2,"column":1,"#),
+        "{}",
+        body
+    );
+}
+
+#[test]
+fn unknown_routes_and_days_are_not_found() {
+    let addr = start(Config::default());
+
+    assert_eq!(post(addr, "/day/1/part/3", "").0, 404);
+    assert_eq!(post(addr, "/solve", "").0, 404);
+
+    let (status, body) = post(addr, "/day/26/part/1", "");
+    assert_eq!(status, 404);
+    assert!(body.contains(r#""kind":"no_such_day""#), "{}", body);
+}
+
+#[test]
+fn only_post_is_allowed() {
+    let addr = start(Config::default());
+
+    let (status, _) = send(
+        addr,
+        b"GET /day/1/part/1 HTTP/1.1\r\nHost: localhost\r\n\r\n",
+    );
+
+    assert_eq!(status, 405);
+}
+
+#[test]
+fn the_input_size_is_limited() {
+    let addr = start(Config {
+        max_body_len: 16,
+        ..Config::default()
+    });
+
+    // the body is not sent: the server must answer from the headers alone
+    let (status, body) = send(
+        addr,
+        b"POST /day/1/part/1 HTTP/1.1\r\nContent-Length: 1000000\r\n\r\n",
+    );
+    assert_eq!(status, 413);
+    assert!(body.contains(r#""kind":"too_large""#), "{}", body);
+
+    let (status, _) = send(addr, b"POST /day/1/part/1 HTTP/1.1\r\n\r\n");
+    assert_eq!(status, 411);
+}
+
+#[test]
+fn the_headers_are_limited() {
+    let addr = start(Config::default());
+    let request = format!(
+        "POST /day/1/part/1 HTTP/1.1\r\nX-Padding: {}\r\n\r\n",
+        "x".repeat(10_000)
+    );
+
+    let (status, _) = send(addr, request.as_bytes());
+
+    assert_eq!(status, 431);
+}
+
+#[test]
+fn a_stalled_request_times_out() {
+    let addr = start(Config {
+        io_timeout: Duration::from_millis(100),
+        ..Config::default()
+    });
+
+    let (status, body) = send(
+        addr,
+        b"POST /day/1/part/1 HTTP/1.1\r\nContent-Length: 10\r\n\r\n1000",
+    );
+
+    assert_eq!(status, 408);
+    assert!(body.contains(r#""kind":"timeout""#), "{}", body);
+}
+
+#[test]
+fn a_slow_search_answers_with_its_best_answer_so_far() {
+    let addr = start(Config {
+        solve_timeout: Duration::from_millis(100),
+        ..Config::default()
+    });
+    let input = include_str!("../src/days/d16/ex2.txt");
+
+    let (status, body) = post(addr, "/day/16/part/2", input);
+
+    assert_eq!(status, 503);
+    assert!(
+        body.contains(r#""error":{"kind":"timed_out","partial":""#),
+        "{}",
+        body
+    );
+}
+
+#[test]
+fn connections_past_the_limit_are_answered_as_busy() {
+    let addr = start(Config {
+        max_connections: 1,
+        ..Config::default()
+    });
+    // holds the only slot while the server waits for its request
+    let idle = TcpStream::connect(addr).unwrap();
+
+    let (status, body) = post(addr, "/day/1/part/1", "1000\n");
+
+    assert_eq!(status, 503);
+    assert!(body.contains(r#""kind":"busy""#), "{}", body);
+    drop(idle);
+}